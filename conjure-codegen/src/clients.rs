@@ -11,10 +11,12 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-use proc_macro2::TokenStream;
+use heck::ShoutySnakeCase;
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 
 use crate::context::Context;
+use crate::servers;
 use crate::types::{
     ArgumentDefinition, AuthType, EndpointDefinition, ParameterType, ServiceDefinition, Type,
 };
@@ -49,6 +51,11 @@ fn generate_inner(ctx: &Context, def: &ServiceDefinition, style: Style) -> Token
         Style::Sync => quote!(Client),
     };
 
+    let markers = def
+        .endpoints()
+        .iter()
+        .flat_map(|e| generate_markers(ctx, e));
+
     let endpoints = def
         .endpoints()
         .iter()
@@ -69,11 +76,42 @@ fn generate_inner(ctx: &Context, def: &ServiceDefinition, style: Style) -> Token
                 #name(client)
             }
 
+            #(#markers)*
+
             #(#endpoints)*
         }
     }
 }
 
+fn generate_markers(ctx: &Context, endpoint: &EndpointDefinition) -> Option<TokenStream> {
+    let markers = endpoint
+        .markers()
+        .iter()
+        .flat_map(|m| ctx.marker_name(m))
+        .collect::<Vec<_>>();
+
+    if markers.is_empty() {
+        return None;
+    }
+
+    let docs = format!(
+        "The fully qualified names of the `{}` endpoint's marker types.",
+        ctx.field_name(endpoint.endpoint_name())
+    );
+    let name = Ident::new(
+        &format!(
+            "{}_MARKERS",
+            endpoint.endpoint_name().to_shouty_snake_case()
+        ),
+        Span::call_site(),
+    );
+
+    Some(quote! {
+        #[doc = #docs]
+        pub const #name: &[&str] = &[#(#markers),*];
+    })
+}
+
 fn generate_endpoint(
     ctx: &Context,
     def: &ServiceDefinition,
@@ -81,6 +119,7 @@ fn generate_endpoint(
     endpoint: &EndpointDefinition,
 ) -> TokenStream {
     let docs = ctx.docs(endpoint.docs());
+    let attributes = ctx.marker_attributes(endpoint.markers());
     let deprecated = match endpoint.deprecated() {
         Some(docs) => {
             let docs = &**docs;
@@ -112,15 +151,9 @@ fn generate_endpoint(
     let result = ctx.result_ident(def.service_name());
     let ret = return_type(ctx, endpoint);
     let ret_name = return_type_name(ctx, def, &ret);
-    let where_ = where_(ctx, style, body_arg);
-
-    let method = endpoint
-        .http_method()
-        .as_str()
-        .parse::<TokenStream>()
-        .unwrap();
+    let where_ = where_(ctx, style, endpoint, body_arg);
 
-    let path = &**endpoint.http_path();
+    let setup_endpoint = setup_endpoint(ctx, def, endpoint);
 
     let path_params = quote!(path_params_);
     let setup_path_params = setup_path_params(ctx, endpoint, &path_params);
@@ -145,9 +178,11 @@ fn generate_endpoint(
     quote! {
         #docs
         #deprecated
+        #attributes
         pub #async_ fn #name #params(&self #auth_arg #(, #args)*) -> #result<#ret_name, conjure_http::private::Error>
         #where_
         {
+            #setup_endpoint
             #setup_path_params
             #setup_query_params
             #setup_headers
@@ -155,8 +190,7 @@ fn generate_endpoint(
             #setup_response_visitor
 
             self.0.request(
-                conjure_http::private::http::Method::#method,
-                #path,
+                &ENDPOINT,
                 #path_params,
                 #query_params,
                 #headers,
@@ -182,18 +216,34 @@ fn params(ctx: &Context, body_arg: Option<&ArgumentDefinition>) -> TokenStream {
     }
 }
 
-fn where_(ctx: &Context, style: Style, body_arg: Option<&ArgumentDefinition>) -> TokenStream {
-    match body_arg {
-        Some(a) if ctx.is_binary(a.type_()) => {
+fn where_(
+    ctx: &Context,
+    style: Style,
+    endpoint: &EndpointDefinition,
+    body_arg: Option<&ArgumentDefinition>,
+) -> TokenStream {
+    let mut predicates = vec![];
+
+    if let Some(a) = body_arg {
+        if ctx.is_binary(a.type_()) {
             let bound = match style {
                 Style::Async => {
                     quote!(conjure_http::client::AsyncWriteBody<T::BinaryWriter> + Sync + Send)
                 }
                 Style::Sync => quote!(conjure_http::client::WriteBody<T::BinaryWriter>),
             };
-            quote!(where U: #bound,)
+            predicates.push(quote!(U: #bound));
         }
-        _ => quote!(),
+    }
+
+    for bound in ctx.marker_bounds(endpoint.markers()) {
+        predicates.push(quote!(T: #bound));
+    }
+
+    if predicates.is_empty() {
+        quote!()
+    } else {
+        quote!(where #(#predicates,)*)
     }
 }
 
@@ -235,6 +285,36 @@ fn return_type_name(ctx: &Context, def: &ServiceDefinition, ty: &ReturnType<'_>)
     }
 }
 
+fn setup_endpoint(
+    ctx: &Context,
+    def: &ServiceDefinition,
+    endpoint: &EndpointDefinition,
+) -> TokenStream {
+    let service = def.service_name().name();
+    let name = &**endpoint.endpoint_name();
+    let method = endpoint
+        .http_method()
+        .as_str()
+        .parse::<TokenStream>()
+        .unwrap();
+    let path = &**endpoint.http_path();
+    let parameters = servers::parameters(ctx, endpoint);
+    let deprecated = endpoint.deprecated().is_some();
+    let markers = servers::markers(ctx, endpoint.markers());
+
+    quote! {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            #service,
+            #name,
+            conjure_http::private::http::Method::#method,
+            #path,
+            #parameters,
+            #deprecated,
+        )
+        #markers;
+    }
+}
+
 fn setup_path_params(
     ctx: &Context,
     endpoint: &EndpointDefinition,
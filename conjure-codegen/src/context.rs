@@ -15,10 +15,11 @@ use heck::{CamelCase, SnakeCase};
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use std::cell::Cell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use crate::types::{
-    ConjureDefinition, Documentation, PrimitiveType, Type, TypeDefinition, TypeName,
+    ConjureDefinition, Documentation, ExternalReference, PrimitiveType, Type, TypeDefinition,
+    TypeName,
 };
 
 struct TypeContext {
@@ -29,15 +30,31 @@ struct TypeContext {
 
 pub struct Context {
     types: HashMap<TypeName, TypeContext>,
+    external_types: HashMap<TypeName, TokenStream>,
+    marker_attributes: HashMap<TypeName, Vec<TokenStream>>,
+    marker_bounds: HashMap<TypeName, Vec<TokenStream>>,
     exhaustive: bool,
+    staged_builders: bool,
     strip_prefix: Vec<String>,
 }
 
 impl Context {
-    pub fn new(defs: &ConjureDefinition, exhaustive: bool, strip_prefix: Option<&str>) -> Context {
+    pub fn new(
+        defs: &ConjureDefinition,
+        exhaustive: bool,
+        staged_builders: bool,
+        strip_prefix: Option<&str>,
+        external_types: &BTreeMap<String, String>,
+        marker_attributes: &BTreeMap<String, Vec<String>>,
+        marker_bounds: &BTreeMap<String, Vec<String>>,
+    ) -> Context {
         let mut context = Context {
             types: HashMap::new(),
+            external_types: HashMap::new(),
+            marker_attributes: HashMap::new(),
+            marker_bounds: HashMap::new(),
             exhaustive,
+            staged_builders,
             strip_prefix: vec![],
         };
 
@@ -45,6 +62,26 @@ impl Context {
             context.strip_prefix = context.raw_module_path(strip_prefix);
         }
 
+        for (java_type, rust_type) in external_types {
+            context
+                .external_types
+                .insert(qualified_type_name(java_type), rust_type.parse().unwrap());
+        }
+
+        for (marker, attributes) in marker_attributes {
+            let attributes = attributes.iter().map(|a| a.parse().unwrap()).collect();
+            context
+                .marker_attributes
+                .insert(qualified_type_name(marker), attributes);
+        }
+
+        for (marker, bounds) in marker_bounds {
+            let bounds = bounds.iter().map(|b| b.parse().unwrap()).collect();
+            context
+                .marker_bounds
+                .insert(qualified_type_name(marker), bounds);
+        }
+
         for def in defs.types() {
             let name = match &def {
                 TypeDefinition::Alias(def) => def.type_name().clone(),
@@ -70,12 +107,25 @@ impl Context {
         self.exhaustive
     }
 
+    pub fn staged_builders(&self) -> bool {
+        self.staged_builders
+    }
+
+    fn external_type(&self, def: &ExternalReference) -> Option<&TokenStream> {
+        self.external_types.get(def.external_reference())
+    }
+
+    fn is_mapped(&self, def: &ExternalReference) -> bool {
+        self.external_type(def).is_some()
+    }
+
     fn needs_box(&self, def: &Type) -> bool {
         match def {
             Type::Primitive(_) => false,
             Type::Optional(def) => self.needs_box(def.item_type()),
             Type::List(_) | Type::Set(_) | Type::Map(_) => false,
             Type::Reference(def) => self.ref_needs_box(def),
+            Type::External(def) if self.is_mapped(def) => false,
             Type::External(def) => self.needs_box(def.fallback()),
         }
     }
@@ -101,6 +151,7 @@ impl Context {
             Type::Set(def) => self.has_double(def.item_type()),
             Type::Map(def) => self.has_double(def.key_type()) || self.has_double(def.value_type()),
             Type::Reference(def) => self.ref_has_double(def),
+            Type::External(def) if self.is_mapped(def) => false,
             Type::External(def) => self.has_double(def.fallback()),
         }
     }
@@ -142,6 +193,7 @@ impl Context {
             Type::Optional(def) => self.is_copy(def.item_type()),
             Type::List(_) | Type::Set(_) | Type::Map(_) => false,
             Type::Reference(def) => self.ref_is_copy(def),
+            Type::External(def) if self.is_mapped(def) => false,
             Type::External(def) => self.is_copy(def.fallback()),
         }
     }
@@ -167,6 +219,7 @@ impl Context {
             Type::Primitive(_) => true,
             Type::Optional(_) | Type::List(_) | Type::Set(_) | Type::Map(_) => false,
             Type::Reference(def) => self.ref_is_required(def),
+            Type::External(def) if self.is_mapped(def) => true,
             Type::External(def) => self.is_required(def.fallback()),
         }
     }
@@ -197,6 +250,7 @@ impl Context {
             },
             Type::Optional(_) | Type::List(_) | Type::Set(_) | Type::Map(_) => true,
             Type::Reference(def) => self.ref_is_default(def),
+            Type::External(def) if self.is_mapped(def) => false,
             Type::External(def) => self.is_default(def.fallback()),
         }
     }
@@ -225,6 +279,7 @@ impl Context {
             },
             Type::Optional(_) | Type::List(_) | Type::Set(_) | Type::Map(_) => false,
             Type::Reference(def) => self.ref_is_display(def),
+            Type::External(def) if self.is_mapped(def) => false,
             Type::External(def) => self.is_display(def.fallback()),
         }
     }
@@ -272,7 +327,10 @@ impl Context {
                 quote!(std::collections::BTreeMap<#key, #value>)
             }
             Type::Reference(def) => self.type_path(this_type, def),
-            Type::External(def) => self.rust_type(this_type, def.fallback()),
+            Type::External(def) => match self.external_type(def) {
+                Some(rust_type) => rust_type.clone(),
+                None => self.rust_type(this_type, def.fallback()),
+            },
         }
     }
 
@@ -284,7 +342,9 @@ impl Context {
                 quote!(#option<#item>)
             }
             Type::Reference(def) => self.ref_boxed_rust_type(this_type, def),
-            Type::External(def) => self.boxed_rust_type(this_type, def.fallback()),
+            Type::External(def) if !self.is_mapped(def) => {
+                self.boxed_rust_type(this_type, def.fallback())
+            }
             def => self.rust_type(this_type, def),
         }
     }
@@ -314,6 +374,7 @@ impl Context {
     pub fn option_inner_type<'a>(&self, def: &'a Type) -> Option<&'a Type> {
         match def {
             Type::Optional(def) => Some(def.item_type()),
+            Type::External(def) if self.is_mapped(def) => None,
             Type::External(def) => self.option_inner_type(def.fallback()),
             _ => None,
         }
@@ -353,7 +414,10 @@ impl Context {
                 quote!(&std::collections::BTreeMap<#key, #value>)
             }
             Type::Reference(def) => self.borrowed_rust_type_ref(this_type, def),
-            Type::External(def) => self.borrowed_rust_type(this_type, def.fallback()),
+            Type::External(def) => match self.external_type(def) {
+                Some(rust_type) => quote!(&#rust_type),
+                None => self.borrowed_rust_type(this_type, def.fallback()),
+            },
         }
     }
 
@@ -397,6 +461,7 @@ impl Context {
             Type::List(_) => quote!(&*#value),
             Type::Set(_) | Type::Map(_) => quote!(&#value),
             Type::Reference(def) => self.borrow_rust_type_ref(value, def),
+            Type::External(def) if self.is_mapped(def) => quote!(&#value),
             Type::External(def) => self.borrow_rust_type(value, def.fallback()),
         }
     }
@@ -527,7 +592,13 @@ impl Context {
                     assign_rhs,
                 }
             }
-            Type::External(def) => self.setter_bounds(this_type, def.fallback(), value_ident),
+            Type::External(def) => match self.external_type(def) {
+                Some(rust_type) => SetterBounds::Simple {
+                    argument_type: rust_type.clone(),
+                    assign_rhs: value_ident,
+                },
+                None => self.setter_bounds(this_type, def.fallback(), value_ident),
+            },
         }
     }
 
@@ -604,9 +675,13 @@ impl Context {
                 argument_type: self.type_path(this_type, def),
                 assign_rhs: value_ident,
             },
-            Type::External(def) => {
-                self.collection_setter_bounds(this_type, def.fallback(), value_ident)
-            }
+            Type::External(def) => match self.external_type(def) {
+                Some(rust_type) => CollectionSetterBounds::Simple {
+                    argument_type: rust_type.clone(),
+                    assign_rhs: value_ident,
+                },
+                None => self.collection_setter_bounds(this_type, def.fallback(), value_ident),
+            },
         }
     }
 
@@ -616,6 +691,7 @@ impl Context {
             Type::Optional(_) => Some(quote!(is_none)),
             Type::List(_) | Type::Set(_) | Type::Map(_) => Some(quote!(is_empty)),
             Type::Reference(def) => self.is_empty_method_ref(def),
+            Type::External(def) if self.is_mapped(def) => None,
             Type::External(def) => self.is_empty_method(def.fallback()),
         }
     }
@@ -638,6 +714,7 @@ impl Context {
             | Type::Set(_)
             | Type::Map(_) => false,
             Type::Reference(def) => self.is_binary_ref(def),
+            Type::External(def) if self.is_mapped(def) => false,
             Type::External(def) => self.is_binary(def.fallback()),
         }
     }
@@ -668,6 +745,7 @@ impl Context {
             },
             Type::Optional(_) | Type::List(_) | Type::Set(_) | Type::Map(_) => false,
             Type::Reference(def) => self.is_plain_ref(def),
+            // the fallback defines the wire format, so mapped types are expected to match its plain encoding
             Type::External(def) => self.is_plain(def.fallback()),
         }
     }
@@ -687,6 +765,7 @@ impl Context {
             Type::Primitive(_) => false,
             Type::Optional(_) | Type::List(_) | Type::Set(_) | Type::Map(_) => true,
             Type::Reference(def) => self.is_iterable_ref(def),
+            Type::External(def) if self.is_mapped(def) => false,
             Type::External(def) => self.is_iterable(def.fallback()),
         }
     }
@@ -705,6 +784,7 @@ impl Context {
             Type::Primitive(_) | Type::List(_) | Type::Set(_) | Type::Map(_) => None,
             Type::Optional(def) => Some(def.item_type()),
             Type::Reference(def) => self.is_optional_ref(def),
+            Type::External(def) if self.is_mapped(def) => None,
             Type::External(def) => self.is_optional(def.fallback()),
         }
     }
@@ -723,6 +803,7 @@ impl Context {
             Type::List(_) => true,
             Type::Primitive(_) | Type::Optional(_) | Type::Set(_) | Type::Map(_) => false,
             Type::Reference(def) => self.is_list_ref(def),
+            Type::External(def) if self.is_mapped(def) => false,
             Type::External(def) => self.is_list(def.fallback()),
         }
     }
@@ -741,6 +822,7 @@ impl Context {
             Type::Set(_) => true,
             Type::Primitive(_) | Type::Optional(_) | Type::List(_) | Type::Map(_) => false,
             Type::Reference(def) => self.is_set_ref(def),
+            Type::External(def) if self.is_mapped(def) => false,
             Type::External(def) => self.is_set(def.fallback()),
         }
     }
@@ -954,6 +1036,45 @@ impl Context {
             _ => false,
         }
     }
+
+    pub fn marker_name(&self, ty: &Type) -> Option<String> {
+        marker_type_name(ty).map(|name| format!("{}.{}", name.package(), name.name()))
+    }
+
+    pub fn marker_attributes(&self, markers: &[Type]) -> TokenStream {
+        let attributes = markers
+            .iter()
+            .filter_map(marker_type_name)
+            .flat_map(|name| self.marker_attributes.get(name))
+            .flatten();
+
+        quote!(#(#[#attributes])*)
+    }
+
+    pub fn marker_bounds(&self, markers: &[Type]) -> Vec<TokenStream> {
+        markers
+            .iter()
+            .filter_map(marker_type_name)
+            .flat_map(|name| self.marker_bounds.get(name))
+            .flatten()
+            .cloned()
+            .collect()
+    }
+}
+
+fn qualified_type_name(name: &str) -> TypeName {
+    match name.rfind('.') {
+        Some(idx) => TypeName::new(&name[idx + 1..], &name[..idx]),
+        None => TypeName::new(name, ""),
+    }
+}
+
+fn marker_type_name(ty: &Type) -> Option<&TypeName> {
+    match ty {
+        Type::Reference(name) => Some(name),
+        Type::External(def) => Some(def.external_reference()),
+        _ => None,
+    }
 }
 
 pub enum SetterBounds {
@@ -16,7 +16,7 @@ use quote::quote;
 
 use crate::context::Context;
 use crate::objects;
-use crate::types::{ErrorDefinition, ObjectDefinition};
+use crate::types::{ErrorDefinition, FieldDefinition, ObjectDefinition, Type};
 
 pub fn generate(ctx: &Context, def: &ErrorDefinition) -> TokenStream {
     let object = ObjectDefinition::builder()
@@ -26,10 +26,12 @@ pub fn generate(ctx: &Context, def: &ErrorDefinition) -> TokenStream {
         .build();
     let object_def = objects::generate(ctx, &object);
     let error_type = generate_error_type(ctx, def);
+    let from_serializable_error = generate_from_serializable_error(ctx, def);
 
     quote! {
         #object_def
         #error_type
+        #from_serializable_error
     }
 }
 
@@ -71,3 +73,82 @@ fn generate_error_type(ctx: &Context, def: &ErrorDefinition) -> TokenStream {
         }
     }
 }
+
+fn generate_from_serializable_error(ctx: &Context, def: &ErrorDefinition) -> TokenStream {
+    let type_name = ctx.type_name(def.error_name().name());
+    let name = format!("{}:{}", def.namespace(), def.error_name().name());
+    let option = ctx.option_ident(def.error_name());
+    let some = ctx.some_ident(def.error_name());
+    let none = ctx.none_ident(def.error_name());
+
+    let fields = def
+        .safe_args()
+        .iter()
+        .chain(def.unsafe_args())
+        .map(|f| decode_field(ctx, def, f))
+        .collect::<Option<Vec<_>>>();
+
+    let body = match fields {
+        Some(fields) => {
+            let parameters = if fields.is_empty() {
+                quote!()
+            } else {
+                quote!(let parameters = error.parameters();)
+            };
+
+            quote! {
+                if error.error_name() != #name {
+                    return #none;
+                }
+
+                #parameters
+                #some(#type_name {
+                    #(#fields,)*
+                })
+            }
+        }
+        // a required parameter without a plain representation is never transmitted
+        None => quote! {
+            let _ = error;
+            #none
+        },
+    };
+
+    quote! {
+        impl conjure_error::FromSerializableError for #type_name {
+            fn from_serializable_error(
+                error: &conjure_error::SerializableError,
+            ) -> #option<#type_name> {
+                #body
+            }
+        }
+    }
+}
+
+fn decode_field(
+    ctx: &Context,
+    def: &ErrorDefinition,
+    field: &FieldDefinition,
+) -> Option<TokenStream> {
+    let name = ctx.field_name(field.field_name());
+    let key = &field.field_name().0;
+    let some = ctx.some_ident(def.error_name());
+    let none = ctx.none_ident(def.error_name());
+    let default = ctx.default_ident(def.error_name());
+
+    let value = match field.type_() {
+        Type::Optional(optional) if ctx.is_plain(optional.item_type()) => quote! {
+            match parameters.get(#key) {
+                #some(v) => #some(conjure_object::FromPlain::from_plain(v).ok()?),
+                #none => #none,
+            }
+        },
+        ty if ctx.is_plain(ty) => quote! {
+            conjure_object::FromPlain::from_plain(parameters.get(#key)?).ok()?
+        },
+        ty if ctx.is_default(ty) => quote!(#default::default()),
+        _ => return None,
+    };
+
+    Some(quote!(#name: #value))
+}
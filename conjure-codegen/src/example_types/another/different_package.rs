@@ -29,6 +29,11 @@ impl Builder {
     pub fn build(&self) -> DifferentPackage {
         DifferentPackage {}
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<DifferentPackage, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<DifferentPackage> for Builder {
     #[inline]
@@ -119,3 +124,13 @@ impl conjure_error::ErrorType for DifferentPackage {
         &[]
     }
 }
+impl conjure_error::FromSerializableError for DifferentPackage {
+    fn from_serializable_error(
+        error: &conjure_error::SerializableError,
+    ) -> Option<DifferentPackage> {
+        if error.error_name() != "Conjure:DifferentPackage" {
+            return None;
+        }
+        Some(DifferentPackage {})
+    }
+}
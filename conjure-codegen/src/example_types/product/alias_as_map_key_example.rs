@@ -245,6 +245,11 @@ impl Builder {
             uuids: self.uuids.clone(),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<AliasAsMapKeyExample, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<AliasAsMapKeyExample> for Builder {
     #[inline]
@@ -52,6 +52,21 @@ impl Builder {
             any: self.any.clone().expect("field any was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<AnyExample, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.any.is_none() {
+            missing_fields.push("any");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "AnyExample",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<AnyExample> for Builder {
     #[inline]
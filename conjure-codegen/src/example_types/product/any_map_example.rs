@@ -68,6 +68,11 @@ impl Builder {
             items: self.items.clone(),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<AnyMapExample, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<AnyMapExample> for Builder {
     #[inline]
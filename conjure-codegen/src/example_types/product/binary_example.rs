@@ -52,6 +52,21 @@ impl Builder {
             binary: self.binary.clone().expect("field binary was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<BinaryExample, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.binary.is_none() {
+            missing_fields.push("binary");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "BinaryExample",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<BinaryExample> for Builder {
     #[inline]
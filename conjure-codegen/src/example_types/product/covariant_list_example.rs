@@ -95,6 +95,11 @@ impl Builder {
             external_items: self.external_items.clone(),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<CovariantListExample, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<CovariantListExample> for Builder {
     #[inline]
@@ -72,6 +72,24 @@ impl Builder {
             rid: self.rid.clone().expect("field rid was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<Dataset, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.file_system_id.is_none() {
+            missing_fields.push("file_system_id");
+        }
+        if self.rid.is_none() {
+            missing_fields.push("rid");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "Dataset",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<Dataset> for Builder {
     #[inline]
@@ -50,6 +50,21 @@ impl Builder {
                 .expect("field double_value was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<DoubleExample, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.double_value.is_none() {
+            missing_fields.push("double_value");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "DoubleExample",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<DoubleExample> for Builder {
     #[inline]
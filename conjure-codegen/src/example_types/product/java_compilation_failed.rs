@@ -29,6 +29,11 @@ impl Builder {
     pub fn build(&self) -> JavaCompilationFailed {
         JavaCompilationFailed {}
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<JavaCompilationFailed, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<JavaCompilationFailed> for Builder {
     #[inline]
@@ -119,3 +124,13 @@ impl conjure_error::ErrorType for JavaCompilationFailed {
         &[]
     }
 }
+impl conjure_error::FromSerializableError for JavaCompilationFailed {
+    fn from_serializable_error(
+        error: &conjure_error::SerializableError,
+    ) -> Option<JavaCompilationFailed> {
+        if error.error_name() != "ConjureJava:JavaCompilationFailed" {
+            return None;
+        }
+        Some(JavaCompilationFailed {})
+    }
+}
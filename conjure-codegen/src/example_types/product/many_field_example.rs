@@ -208,6 +208,30 @@ impl Builder {
             alias: self.alias.clone().expect("field alias was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<ManyFieldExample, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.string.is_none() {
+            missing_fields.push("string");
+        }
+        if self.integer.is_none() {
+            missing_fields.push("integer");
+        }
+        if self.double_value.is_none() {
+            missing_fields.push("double_value");
+        }
+        if self.alias.is_none() {
+            missing_fields.push("alias");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "ManyFieldExample",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<ManyFieldExample> for Builder {
     #[inline]
@@ -65,6 +65,11 @@ impl Builder {
             items: self.items.clone(),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<MapExample, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<MapExample> for Builder {
     #[inline]
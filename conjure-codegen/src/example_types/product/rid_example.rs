@@ -47,6 +47,21 @@ impl Builder {
             rid_value: self.rid_value.clone().expect("field rid_value was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<RidExample, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.rid_value.is_none() {
+            missing_fields.push("rid_value");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "RidExample",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<RidExample> for Builder {
     #[inline]
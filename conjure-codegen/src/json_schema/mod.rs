@@ -0,0 +1,220 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::openapi::{self, annotate, is_required, schema_names, type_name, MAX_SAFE_LONG};
+use crate::types::{
+    ConjureDefinition, FieldDefinition, PrimitiveType, Type, TypeDefinition, TypeName,
+};
+
+#[cfg(test)]
+mod test;
+
+const DRAFT_07: &str = "http://json-schema.org/draft-07/schema#";
+
+/// Generates a JSON Schema document for each type definition in a Conjure definition.
+///
+/// The documents use JSON Schema draft 7, and are self-contained: the definitions of all types referenced by a type
+/// are included in the `definitions` section of its document. The schemas match the strict wire format enforced by
+/// `conjure_serde::json`'s server deserializer - in particular, unknown object fields are not permitted.
+pub fn generate_json_schemas(defs: &ConjureDefinition) -> BTreeMap<TypeName, Value> {
+    let generator = Generator::new(defs);
+
+    defs.types()
+        .iter()
+        .map(|def| {
+            let name = type_name(def);
+            (name.clone(), generator.document(name))
+        })
+        .collect()
+}
+
+struct Generator<'a> {
+    types: HashMap<&'a TypeName, &'a TypeDefinition>,
+    schema_names: HashMap<&'a TypeName, String>,
+}
+
+impl<'a> Generator<'a> {
+    fn new(defs: &'a ConjureDefinition) -> Generator<'a> {
+        let types = defs
+            .types()
+            .iter()
+            .map(|def| (type_name(def), def))
+            .collect();
+
+        Generator {
+            types,
+            schema_names: schema_names(defs),
+        }
+    }
+
+    fn document(&self, name: &'a TypeName) -> Value {
+        let mut definitions = BTreeMap::new();
+        let mut pending = vec![name];
+
+        while let Some(name) = pending.pop() {
+            let schema_name = &self.schema_names[name];
+            if definitions.contains_key(schema_name) {
+                continue;
+            }
+
+            let mut refs = BTreeSet::new();
+            let schema = self.type_definition(self.types[name], &mut refs);
+            definitions.insert(schema_name.clone(), schema);
+            pending.extend(refs);
+        }
+
+        json!({
+            "$schema": DRAFT_07,
+            "title": name.name(),
+            "allOf": [self.reference(name)],
+            "definitions": definitions,
+        })
+    }
+
+    fn type_definition(&self, def: &'a TypeDefinition, refs: &mut BTreeSet<&'a TypeName>) -> Value {
+        let (schema, docs) = match def {
+            TypeDefinition::Alias(def) => (self.schema(def.alias(), refs), def.docs()),
+            TypeDefinition::Enum(def) => {
+                let values = def.values().iter().map(|v| v.value()).collect::<Vec<_>>();
+                let schema = json!({
+                    "type": "string",
+                    "enum": values,
+                });
+                (schema, def.docs())
+            }
+            TypeDefinition::Object(def) => (self.object(def.fields(), refs), def.docs()),
+            TypeDefinition::Union(def) => {
+                let variants = def
+                    .union_()
+                    .iter()
+                    .map(|f| self.union_variant(f, refs))
+                    .collect::<Vec<_>>();
+                (json!({ "oneOf": variants }), def.docs())
+            }
+        };
+
+        match docs {
+            Some(docs) => annotate(schema, "description", json!(docs.0)),
+            None => schema,
+        }
+    }
+
+    fn object(&self, fields: &'a [FieldDefinition], refs: &mut BTreeSet<&'a TypeName>) -> Value {
+        let mut properties = Map::new();
+        let mut required = vec![];
+
+        for field in fields {
+            let mut schema = self.schema(field.type_(), refs);
+            if let Some(docs) = field.docs() {
+                schema = annotate(schema, "description", json!(docs.0));
+            }
+            properties.insert(field.field_name().0.clone(), schema);
+
+            if is_required(&self.types, field.type_()) {
+                required.push(&field.field_name().0);
+            }
+        }
+
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false,
+        })
+    }
+
+    fn union_variant(
+        &self,
+        field: &'a FieldDefinition,
+        refs: &mut BTreeSet<&'a TypeName>,
+    ) -> Value {
+        let name = &field.field_name().0;
+
+        let mut schema = self.schema(field.type_(), refs);
+        if let Some(docs) = field.docs() {
+            schema = annotate(schema, "description", json!(docs.0));
+        }
+
+        json!({
+            "type": "object",
+            "properties": {
+                "type": { "const": name },
+                name: schema,
+            },
+            "required": ["type", name],
+            "additionalProperties": false,
+        })
+    }
+
+    fn schema(&self, ty: &'a Type, refs: &mut BTreeSet<&'a TypeName>) -> Value {
+        match ty {
+            Type::Primitive(primitive) => primitive_schema(primitive),
+            Type::Optional(def) => json!({
+                "anyOf": [self.schema(def.item_type(), refs), { "type": "null" }],
+            }),
+            Type::List(def) => json!({
+                "type": "array",
+                "items": self.schema(def.item_type(), refs),
+            }),
+            Type::Set(def) => json!({
+                "type": "array",
+                "items": self.schema(def.item_type(), refs),
+                "uniqueItems": true,
+            }),
+            Type::Map(def) => json!({
+                "type": "object",
+                "additionalProperties": self.schema(def.value_type(), refs),
+            }),
+            Type::Reference(name) => {
+                refs.insert(name);
+                self.reference(name)
+            }
+            Type::External(def) => self.schema(def.fallback(), refs),
+        }
+    }
+
+    fn reference(&self, name: &TypeName) -> Value {
+        json!({ "$ref": format!("#/definitions/{}", self.schema_names[name]) })
+    }
+}
+
+// JSON Schema can express the ranges and encodings which OpenAPI describes with its own formats
+fn primitive_schema(primitive: &PrimitiveType) -> Value {
+    match primitive {
+        PrimitiveType::Integer => json!({
+            "type": "integer",
+            "minimum": -(1i64 << 31),
+            "maximum": (1i64 << 31) - 1,
+        }),
+        // non-finite values are serialized as strings
+        PrimitiveType::Double => json!({
+            "anyOf": [
+                { "type": "number" },
+                { "enum": ["NaN", "Infinity", "-Infinity"] },
+            ],
+        }),
+        PrimitiveType::Safelong => json!({
+            "type": "integer",
+            "minimum": -MAX_SAFE_LONG,
+            "maximum": MAX_SAFE_LONG,
+        }),
+        PrimitiveType::Binary => json!({
+            "type": "string",
+            "contentEncoding": "base64",
+        }),
+        primitive => openapi::primitive_schema(primitive),
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use serde_json::{json, Value};
+use std::path::Path;
+
+use crate::compile_yaml;
+use crate::json_schema::generate_json_schemas;
+use crate::types::TypeName;
+
+fn schema(name: &str) -> Value {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../conjure-test/test.yml");
+    let defs = compile_yaml(&[path]).unwrap();
+    let mut schemas = generate_json_schemas(&defs);
+    schemas
+        .remove(&TypeName::new(name, "com.palantir.conjure"))
+        .unwrap()
+}
+
+#[test]
+fn document() {
+    let schema = schema("IntegerAlias");
+    assert_eq!(
+        schema,
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "IntegerAlias",
+            "allOf": [{"$ref": "#/definitions/IntegerAlias"}],
+            "definitions": {
+                "IntegerAlias": {
+                    "type": "integer",
+                    "minimum": -2_147_483_648i64,
+                    "maximum": 2_147_483_647,
+                },
+            },
+        })
+    );
+}
+
+#[test]
+fn transitive_definitions() {
+    let schema = schema("SuperpackageObject");
+    let definitions = schema["definitions"].as_object().unwrap();
+    assert_eq!(
+        definitions.keys().collect::<Vec<_>>(),
+        vec!["IntegerAlias", "SubpackageObject", "SuperpackageObject"],
+    );
+}
+
+#[test]
+fn custom_values() {
+    let schema = schema("CustomValueHandling");
+    assert_eq!(
+        schema["definitions"]["CustomValueHandling"],
+        json!({
+            "type": "object",
+            "properties": {
+                "binary": {"type": "string", "contentEncoding": "base64"},
+                "double": {
+                    "anyOf": [
+                        {"type": "number"},
+                        {"enum": ["NaN", "Infinity", "-Infinity"]},
+                    ],
+                },
+            },
+            "required": ["binary", "double"],
+            "additionalProperties": false,
+        })
+    );
+}
+
+#[test]
+fn optional_fields() {
+    let schema = schema("OptionalConstructorFields2");
+    assert_eq!(
+        schema["definitions"]["OptionalConstructorFields2"],
+        json!({
+            "type": "object",
+            "properties": {
+                "object": {
+                    "anyOf": [{"$ref": "#/definitions/TestObject"}, {"type": "null"}],
+                },
+            },
+            "required": [],
+            "additionalProperties": false,
+        })
+    );
+}
+
+#[test]
+fn unions() {
+    let schema = schema("TestUnion");
+    assert_eq!(
+        schema["definitions"]["TestUnion"]["oneOf"][2],
+        json!({
+            "type": "object",
+            "properties": {
+                "type": {"const": "object"},
+                "object": {"$ref": "#/definitions/TestObject"},
+            },
+            "required": ["type", "object"],
+            "additionalProperties": false,
+        })
+    );
+    assert!(schema["definitions"]["TestObject"].is_object());
+}
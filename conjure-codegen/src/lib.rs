@@ -44,6 +44,27 @@
 //! }
 //! ```
 //!
+//! Conjure YAML definitions can also be compiled to IR directly, without the need for the Java-based Conjure
+//! compiler:
+//!
+//! ```no_run
+//! use std::env;
+//! use std::path::Path;
+//!
+//! fn main() {
+//!     let input = "service-api.yml";
+//!     let output = Path::new(&env::var_os("OUT_DIR").unwrap()).join("service_api");
+//!
+//!     println!("cargo:rerun-if-changed={}", input);
+//!     let defs = conjure_codegen::compile_yaml(&[input]).unwrap();
+//!     conjure_codegen::Config::new()
+//!         .run_rustfmt(false)
+//!         .strip_prefix("com.foobar.service".to_string())
+//!         .generate_files_from_definition(&defs, output)
+//!         .unwrap();
+//! }
+//! ```
+//!
 //! # Types
 //!
 //! ## Builtin
@@ -283,11 +304,21 @@ mod clients;
 mod context;
 mod enums;
 mod errors;
+mod json_schema;
 mod objects;
+mod openapi;
 mod servers;
-#[allow(dead_code, clippy::all)]
-mod types;
+#[cfg(test)]
+mod test;
+/// The Conjure IR types.
+#[allow(clippy::all, missing_docs)]
+pub mod types;
 mod unions;
+mod yaml;
+
+pub use crate::json_schema::generate_json_schemas;
+pub use crate::openapi::generate_openapi;
+pub use crate::yaml::compile_yaml;
 
 /// Examples of generated Conjure code.
 ///
@@ -306,8 +337,12 @@ pub struct Config {
     rustfmt: OsString,
     run_rustfmt: bool,
     exhaustive: bool,
+    staged_builders: bool,
     strip_prefix: Option<String>,
     build_crate: Option<CrateInfo>,
+    external_types: BTreeMap<String, String>,
+    marker_attributes: BTreeMap<String, Vec<String>>,
+    marker_bounds: BTreeMap<String, Vec<String>>,
 }
 
 impl Default for Config {
@@ -323,8 +358,12 @@ impl Config {
             rustfmt: env::var_os("RUSTFMT").unwrap_or_else(|| OsString::from("rustfmt")),
             run_rustfmt: true,
             exhaustive: false,
+            staged_builders: false,
             strip_prefix: None,
             build_crate: None,
+            external_types: BTreeMap::new(),
+            marker_attributes: BTreeMap::new(),
+            marker_bounds: BTreeMap::new(),
         }
     }
 
@@ -339,6 +378,22 @@ impl Config {
         self
     }
 
+    /// Controls the generation of staged builders for objects.
+    ///
+    /// Staged builders enforce at compile time that all required fields of an object are set before it can be built.
+    /// Each required field is set in turn, in the order it is defined, with each setter returning the builder for the
+    /// next stage. Once all required fields have been set, the final `Builder` stage can set the remaining fields and
+    /// infallibly build the object.
+    ///
+    /// Otherwise, objects have a single builder with `build` and `try_build` methods which respectively panic or
+    /// return an error if a required field has not been set.
+    ///
+    /// Defaults to `false`.
+    pub fn staged_builders(&mut self, staged_builders: bool) -> &mut Config {
+        self.staged_builders = staged_builders;
+        self
+    }
+
     /// Controls the use of rustfmt to format generated source code.
     ///
     /// Defaults to `true`.
@@ -369,6 +424,53 @@ impl Config {
         self
     }
 
+    /// Maps a Conjure external import to a Rust type.
+    ///
+    /// By default, external imports are treated as their fallback type. With a mapping, references to the import with
+    /// the specified fully qualified Java name will instead use the specified Rust type. The Rust path must be valid
+    /// from any module, e.g. `crate::ids::UserId` or `other_crate::Foo`.
+    ///
+    /// Like other Conjure types, the Rust type must implement `Debug`, `Clone`, `PartialEq`, `Eq`, `PartialOrd`,
+    /// `Ord`, `Hash`, `Serialize` and `Deserialize`, using the same wire format as the fallback type. If the fallback
+    /// type is a plain type (e.g. `string` or `safelong`), it must also implement `conjure_object::Plain` and
+    /// `conjure_object::FromPlain`.
+    pub fn external_type(&mut self, java_type: &str, rust_type: &str) -> &mut Config {
+        self.external_types
+            .insert(java_type.to_string(), rust_type.to_string());
+        self
+    }
+
+    /// Maps an endpoint marker to a Rust attribute.
+    ///
+    /// The attribute will be applied to the methods generated for every endpoint marked with the type with the
+    /// specified fully qualified name, in both service traits and clients. The attribute is specified without the
+    /// surrounding `#[...]`, e.g. `my_crate::idempotent` or `doc(alias = "audited")`. A marker can be mapped to
+    /// multiple attributes by calling this method repeatedly.
+    ///
+    /// Regardless of this mapping, the names of all endpoint and argument markers are included in the generated
+    /// `conjure_http::server::Metadata`.
+    pub fn marker_attribute(&mut self, marker: &str, attribute: &str) -> &mut Config {
+        self.marker_attributes
+            .entry(marker.to_string())
+            .or_default()
+            .push(attribute.to_string());
+        self
+    }
+
+    /// Maps an endpoint marker to a trait bound on the underlying client of generated clients.
+    ///
+    /// The client methods generated for every endpoint marked with the type with the specified fully qualified name
+    /// will require the underlying `Client` or `AsyncClient` to satisfy the bound, e.g. `my_crate::Idempotent`. The
+    /// Rust path must be valid from any module. A marker can be mapped to multiple bounds by calling this method
+    /// repeatedly.
+    pub fn marker_bound(&mut self, marker: &str, bound: &str) -> &mut Config {
+        self.marker_bounds
+            .entry(marker.to_string())
+            .or_default()
+            .push(bound.to_string());
+        self
+    }
+
     /// Switches generation to create a full crate.
     ///
     /// Defaults to just generating a single module.
@@ -386,17 +488,76 @@ impl Config {
         P: AsRef<Path>,
         Q: AsRef<Path>,
     {
-        self.generate_files_inner(ir_file.as_ref(), out_dir.as_ref())
+        let defs = self.parse_ir(ir_file.as_ref())?;
+        self.generate_files_inner(&defs, out_dir.as_ref())
     }
 
-    fn generate_files_inner(&self, ir_file: &Path, out_dir: &Path) -> Result<(), Error> {
-        let defs = self.parse_ir(ir_file)?;
+    /// Generates Rust source files from an in-memory Conjure IR definition.
+    ///
+    /// This can be combined with [`compile_yaml`](fn.compile_yaml.html) to generate code directly from Conjure YAML
+    /// files.
+    pub fn generate_files_from_definition<P>(
+        &self,
+        defs: &ConjureDefinition,
+        out_dir: P,
+    ) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        self.generate_files_inner(defs, out_dir.as_ref())
+    }
 
+    /// Generates Rust source code from an in-memory Conjure IR definition as a single token stream.
+    ///
+    /// Rather than being split across a tree of files, each module is emitted inline. This is intended for use by
+    /// procedural macros.
+    pub fn generate_tokens(&self, defs: &ConjureDefinition) -> Result<TokenStream, Error> {
+        self.validate(defs)?;
+        Ok(self.create_modules(defs).render_inline())
+    }
+
+    fn validate(&self, defs: &ConjureDefinition) -> Result<(), Error> {
         if defs.version() != 1 {
             bail!("unsupported IR version {}", defs.version());
         }
 
-        let modules = self.create_modules(&defs);
+        for (java_type, rust_type) in &self.external_types {
+            if rust_type.parse::<TokenStream>().is_err() {
+                bail!(
+                    "invalid Rust type `{}` for external type `{}`",
+                    rust_type,
+                    java_type
+                );
+            }
+        }
+
+        for (marker, attributes) in &self.marker_attributes {
+            for attribute in attributes {
+                if attribute.parse::<TokenStream>().is_err() {
+                    bail!(
+                        "invalid Rust attribute `{}` for marker `{}`",
+                        attribute,
+                        marker
+                    );
+                }
+            }
+        }
+
+        for (marker, bounds) in &self.marker_bounds {
+            for bound in bounds {
+                if bound.parse::<TokenStream>().is_err() {
+                    bail!("invalid Rust bound `{}` for marker `{}`", bound, marker);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn generate_files_inner(&self, defs: &ConjureDefinition, out_dir: &Path) -> Result<(), Error> {
+        self.validate(defs)?;
+
+        let modules = self.create_modules(defs);
         let (src_dir, lib_root) = if self.build_crate.is_some() {
             (out_dir.join("src"), true)
         } else {
@@ -404,7 +565,7 @@ impl Config {
         };
 
         if let Some(info) = &self.build_crate {
-            self.write_cargo_toml(out_dir, info, &defs)?;
+            self.write_cargo_toml(out_dir, info, defs)?;
         }
 
         modules.render(self, &src_dir, lib_root)?;
@@ -437,7 +598,11 @@ impl Config {
         let context = Context::new(
             &defs,
             self.exhaustive,
+            self.staged_builders,
             self.strip_prefix.as_ref().map(|s| &**s),
+            &self.external_types,
+            &self.marker_attributes,
+            &self.marker_bounds,
         );
 
         let mut root = ModuleTrie::new();
@@ -618,17 +783,7 @@ impl ModuleTrie {
             quote! {}
         };
 
-        let uses = self.types.iter().map(|m| {
-            let module_name = m.module_name.parse::<TokenStream>().unwrap();
-            let type_names = m
-                .type_names
-                .iter()
-                .map(|n| n.parse::<TokenStream>().unwrap());
-            quote! {
-                #[doc(inline)]
-                pub use self::#module_name::{#(#type_names),*};
-            }
-        });
+        let uses = self.create_uses();
 
         let type_mods = self.types.iter().map(|m| {
             let module_name = m.module_name.parse::<TokenStream>().unwrap();
@@ -646,10 +801,59 @@ impl ModuleTrie {
 
         quote! {
             #attrs
-            #(#uses)*
+            #uses
 
             #(#type_mods)*
             #(#sub_mods)*
         }
     }
+
+    fn render_inline(&self) -> TokenStream {
+        let uses = self.create_uses();
+
+        let type_mods = self.types.iter().map(|m| {
+            let module_name = m.module_name.parse::<TokenStream>().unwrap();
+            let contents = &m.contents;
+            quote! {
+                pub mod #module_name {
+                    #contents
+                }
+            }
+        });
+
+        let sub_mods = self.submodules.iter().map(|(name, module)| {
+            let module_name = name.parse::<TokenStream>().unwrap();
+            let contents = module.render_inline();
+            quote! {
+                pub mod #module_name {
+                    #contents
+                }
+            }
+        });
+
+        quote! {
+            #uses
+
+            #(#type_mods)*
+            #(#sub_mods)*
+        }
+    }
+
+    fn create_uses(&self) -> TokenStream {
+        let uses = self.types.iter().map(|m| {
+            let module_name = m.module_name.parse::<TokenStream>().unwrap();
+            let type_names = m
+                .type_names
+                .iter()
+                .map(|n| n.parse::<TokenStream>().unwrap());
+            quote! {
+                #[doc(inline)]
+                pub use self::#module_name::{#(#type_names),*};
+            }
+        });
+
+        quote! {
+            #(#uses)*
+        }
+    }
 }
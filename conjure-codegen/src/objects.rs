@@ -91,7 +91,7 @@ fn generate_object(ctx: &Context, def: &ObjectDefinition) -> TokenStream {
         quote!(builder)
     };
 
-    let builder_type = builder_type(ctx, def);
+    let builder_type = initial_builder_type(ctx, def);
 
     quote! {
         #docs
@@ -131,6 +131,23 @@ fn builder_type(ctx: &Context, def: &ObjectDefinition) -> TokenStream {
     }
 }
 
+fn stage_type(ctx: &Context, def: &ObjectDefinition, stage: usize) -> TokenStream {
+    let stage_type = format!("BuilderStage{}", stage);
+    if ctx.type_name(def.type_name().name()) == stage_type {
+        format!("{}_", stage_type).parse().unwrap()
+    } else {
+        stage_type.parse().unwrap()
+    }
+}
+
+fn initial_builder_type(ctx: &Context, def: &ObjectDefinition) -> TokenStream {
+    if ctx.staged_builders() && def.fields().iter().any(|f| ctx.is_required(f.type_())) {
+        stage_type(ctx, def, 0)
+    } else {
+        builder_type(ctx, def)
+    }
+}
+
 fn generate_constructor(ctx: &Context, def: &ObjectDefinition) -> TokenStream {
     let some = ctx.some_ident(def.type_name());
     let name = ctx.type_name(def.type_name().name());
@@ -217,6 +234,14 @@ fn generate_constructor(ctx: &Context, def: &ObjectDefinition) -> TokenStream {
 }
 
 fn generate_builder(ctx: &Context, def: &ObjectDefinition) -> TokenStream {
+    if ctx.staged_builders() {
+        generate_staged_builder(ctx, def)
+    } else {
+        generate_unstaged_builder(ctx, def)
+    }
+}
+
+fn generate_unstaged_builder(ctx: &Context, def: &ObjectDefinition) -> TokenStream {
     let name = ctx.type_name(def.type_name().name());
     let docs = format!("A builder for the `{}` type.", name);
     let builder_type = builder_type(ctx, def);
@@ -240,11 +265,8 @@ fn generate_builder(ctx: &Context, def: &ObjectDefinition) -> TokenStream {
         .iter()
         .map(|f| generate_setter(ctx, def, f, &field_names));
 
-    let build_method = if fields.iter().any(|f| f == "build") {
-        quote!(build_)
-    } else {
-        quote!(build)
-    };
+    let build_method = build_method(fields);
+    let try_build = generate_try_build(ctx, def, fields, &build_method);
 
     let build_rhs = def.fields().iter().map(|f| {
         let var = ctx.field_name(f.field_name());
@@ -290,6 +312,8 @@ fn generate_builder(ctx: &Context, def: &ObjectDefinition) -> TokenStream {
                     )*
                 }
             }
+
+            #try_build
         }
 
         impl #from<#name> for #builder_type {
@@ -305,6 +329,221 @@ fn generate_builder(ctx: &Context, def: &ObjectDefinition) -> TokenStream {
     }
 }
 
+fn build_method(fields: &[Ident]) -> TokenStream {
+    if fields.iter().any(|f| f == "build") {
+        quote!(build_)
+    } else {
+        quote!(build)
+    }
+}
+
+fn generate_try_build(
+    ctx: &Context,
+    def: &ObjectDefinition,
+    fields: &[Ident],
+    build_method: &TokenStream,
+) -> TokenStream {
+    let name = ctx.type_name(def.type_name().name());
+    let name_str = def.type_name().name();
+    let result = ctx.result_ident(def.type_name());
+    let ok = ctx.ok_ident(def.type_name());
+    let err = ctx.err_ident(def.type_name());
+    let vec = ctx.vec_ident(def.type_name());
+
+    let try_build_method = if fields.iter().any(|f| f == "try_build") {
+        quote!(try_build_)
+    } else {
+        quote!(try_build)
+    };
+
+    let checks = def
+        .fields()
+        .iter()
+        .filter(|f| ctx.is_required(f.type_()))
+        .map(|f| {
+            let var = ctx.field_name(f.field_name());
+            let var_str = var.to_string();
+            quote! {
+                if self.#var.is_none() {
+                    missing_fields.push(#var_str);
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let checks = if checks.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            let mut missing_fields = #vec::new();
+            #(#checks)*
+            if !missing_fields.is_empty() {
+                return #err(conjure_object::private::build_error(#name_str, missing_fields));
+            }
+        }
+    };
+
+    quote! {
+        /// Constructs a new instance of the type, returning an error if a required field was not set.
+        #[inline]
+        pub fn #try_build_method(&self) -> #result<#name, conjure_object::BuildError> {
+            #checks
+            #ok(self.#build_method())
+        }
+    }
+}
+
+fn generate_staged_builder(ctx: &Context, def: &ObjectDefinition) -> TokenStream {
+    let name = ctx.type_name(def.type_name().name());
+    let builder_type = builder_type(ctx, def);
+    let default = ctx.default_ident(def.type_name());
+    let from = ctx.from_ident(def.type_name());
+
+    let required = def
+        .fields()
+        .iter()
+        .filter(|f| ctx.is_required(f.type_()))
+        .collect::<Vec<_>>();
+    let optional = def
+        .fields()
+        .iter()
+        .filter(|f| !ctx.is_required(f.type_()))
+        .map(|f| ctx.field_name(f.field_name()))
+        .collect::<Vec<_>>();
+
+    let stages = required.iter().enumerate().map(|(i, field)| {
+        let docs = format!("A stage of the builder for the `{}` type.", name);
+        let this_type = stage_type(ctx, def, i);
+        let derives = if i == 0 {
+            quote!(Debug, Clone, Default)
+        } else {
+            quote!(Debug, Clone)
+        };
+
+        let set_fields = required[..i]
+            .iter()
+            .map(|f| ctx.field_name(f.field_name()))
+            .collect::<Vec<_>>();
+        let set_types = required[..i]
+            .iter()
+            .map(|f| ctx.boxed_rust_type(def.type_name(), f.type_()));
+
+        let (next_type, defaults) = if i + 1 == required.len() {
+            (builder_type.clone(), &*optional)
+        } else {
+            (stage_type(ctx, def, i + 1), &[][..])
+        };
+
+        let field_docs = ctx.docs(field.docs());
+        let deprecated = ctx.deprecated(field.deprecated());
+        let field_name = ctx.field_name(field.field_name());
+        let (params, argument_type, where_, assign_rhs) =
+            match ctx.setter_bounds(def.type_name(), field.type_(), quote!(#field_name)) {
+                SetterBounds::Simple {
+                    argument_type,
+                    assign_rhs,
+                } => (quote!(), argument_type, quote!(), assign_rhs),
+                SetterBounds::Generic {
+                    argument_bound,
+                    assign_rhs,
+                } => (
+                    quote!(<T>),
+                    quote!(T),
+                    quote!(where T: #argument_bound),
+                    assign_rhs,
+                ),
+                SetterBounds::Collection { .. } => unreachable!("collections are never required"),
+            };
+
+        quote! {
+            #[doc = #docs]
+            #[derive(#derives)]
+            pub struct #this_type {
+                #(
+                    #set_fields: #set_types,
+                )*
+            }
+
+            impl #this_type {
+                #field_docs
+                #deprecated
+                #[inline]
+                pub fn #field_name #params(self, #field_name: #argument_type) -> #next_type
+                #where_
+                {
+                    #next_type {
+                        #(
+                            #set_fields: self.#set_fields,
+                        )*
+                        #field_name: #assign_rhs,
+                        #(
+                            #defaults: #default::default(),
+                        )*
+                    }
+                }
+            }
+        }
+    });
+
+    let docs = format!("A builder for the `{}` type.", name);
+    let derives = if required.is_empty() {
+        quote!(Debug, Clone, Default)
+    } else {
+        quote!(Debug, Clone)
+    };
+
+    let fields = &fields(ctx, def);
+    let boxed_types = def
+        .fields()
+        .iter()
+        .map(|f| ctx.boxed_rust_type(def.type_name(), f.type_()));
+
+    let field_names = fields.iter().map(Ident::to_string).collect();
+    let setters = def
+        .fields()
+        .iter()
+        .map(|f| generate_setter(ctx, def, f, &field_names));
+
+    let build_method = build_method(fields);
+
+    quote! {
+        #(#stages)*
+
+        #[doc = #docs]
+        #[derive(#derives)]
+        pub struct #builder_type {
+            #(
+                #fields: #boxed_types,
+            )*
+        }
+
+        impl #builder_type {
+            #(#setters)*
+
+            /// Constructs a new instance of the type.
+            #[inline]
+            pub fn #build_method(&self) -> #name {
+                #name {
+                    #(
+                        #fields: self.#fields.clone(),
+                    )*
+                }
+            }
+        }
+
+        impl #from<#name> for #builder_type {
+            #[inline]
+            fn from(_v: #name) -> #builder_type {
+                #builder_type {
+                    #(
+                        #fields: _v.#fields,
+                    )*
+                }
+            }
+        }
+    }
+}
+
 fn generate_setter(
     ctx: &Context,
     def: &ObjectDefinition,
@@ -315,7 +554,7 @@ fn generate_setter(
 
     let docs = ctx.docs(field.docs());
 
-    let required = if ctx.is_required(field.type_()) {
+    let required = if ctx.is_required(field.type_()) && !ctx.staged_builders() {
         quote! {
             ///
             /// Required.
@@ -333,7 +572,7 @@ fn generate_setter(
             argument_type,
             mut assign_rhs,
         } => {
-            if ctx.is_required(field.type_()) {
+            if ctx.is_required(field.type_()) && !ctx.staged_builders() {
                 assign_rhs = quote!(#some(#assign_rhs));
             }
             quote! {
@@ -351,7 +590,7 @@ fn generate_setter(
             argument_bound,
             mut assign_rhs,
         } => {
-            if ctx.is_required(field.type_()) {
+            if ctx.is_required(field.type_()) && !ctx.staged_builders() {
                 assign_rhs = quote!(#some(#assign_rhs));
             }
             quote! {
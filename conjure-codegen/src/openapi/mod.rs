@@ -0,0 +1,573 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::types::{
+    ArgumentDefinition, AuthType, ConjureDefinition, Documentation, EndpointDefinition,
+    FieldDefinition, HttpMethod, ParameterType, PrimitiveType, ServiceDefinition, Type,
+    TypeDefinition, TypeName,
+};
+
+#[cfg(test)]
+mod test;
+
+pub const RID_PATTERN: &str =
+    r"^ri\.[a-z][a-z0-9\-]*\.([a-z0-9][a-z0-9\-]*)?\.[a-z][a-z0-9\-]*\.[a-zA-Z0-9_\-\.]+$";
+pub const BEARER_TOKEN_PATTERN: &str = r"^[A-Za-z0-9\-\._~\+/]+=*$";
+pub const MAX_SAFE_LONG: i64 = (1 << 53) - 1;
+
+const BEARER_AUTH: &str = "BearerAuth";
+const SERIALIZABLE_ERROR: &str = "SerializableError";
+
+/// Generates an OpenAPI 3 document describing the services and types of a Conjure definition.
+///
+/// Types are placed in the document's `components.schemas` section, named by their Conjure name, or by their fully
+/// qualified name if multiple types share a name. Schemas match the JSON wire format used by Conjure. Endpoint errors
+/// are described by a default response containing a `SerializableError`.
+pub fn generate_openapi(defs: &ConjureDefinition, title: &str, version: &str) -> Value {
+    OpenApi::new(defs).generate(title, version)
+}
+
+struct OpenApi<'a> {
+    defs: &'a ConjureDefinition,
+    types: HashMap<&'a TypeName, &'a TypeDefinition>,
+    schema_names: HashMap<&'a TypeName, String>,
+    security_schemes: BTreeMap<String, Value>,
+}
+
+impl<'a> OpenApi<'a> {
+    fn new(defs: &'a ConjureDefinition) -> OpenApi<'a> {
+        let types = defs
+            .types()
+            .iter()
+            .map(|def| (type_name(def), def))
+            .collect();
+
+        OpenApi {
+            defs,
+            types,
+            schema_names: schema_names(defs),
+            security_schemes: BTreeMap::new(),
+        }
+    }
+
+    fn generate(mut self, title: &str, version: &str) -> Value {
+        let mut paths = BTreeMap::new();
+        for service in self.defs.services() {
+            for endpoint in service.endpoints() {
+                let path = openapi_path(&endpoint.http_path().0);
+                let method = match endpoint.http_method() {
+                    HttpMethod::Get => "get",
+                    HttpMethod::Post => "post",
+                    HttpMethod::Put => "put",
+                    HttpMethod::Delete => "delete",
+                };
+                let operation = self.operation(service, endpoint);
+                paths
+                    .entry(path)
+                    .or_insert_with(Map::new)
+                    .insert(method.to_string(), operation);
+            }
+        }
+
+        let mut schemas = BTreeMap::new();
+        for def in self.defs.types() {
+            let name = &self.schema_names[type_name(def)];
+            schemas.insert(name.clone(), self.type_definition(def));
+        }
+        schemas.insert(SERIALIZABLE_ERROR.to_string(), serializable_error());
+
+        let mut components = Map::new();
+        components.insert("schemas".to_string(), json!(schemas));
+        if !self.security_schemes.is_empty() {
+            components.insert("securitySchemes".to_string(), json!(self.security_schemes));
+        }
+
+        let mut tags = vec![];
+        for service in self.defs.services() {
+            let mut tag = Map::new();
+            tag.insert("name".to_string(), json!(service.service_name().name()));
+            insert_docs(&mut tag, service.docs());
+            tags.push(Value::Object(tag));
+        }
+
+        json!({
+            "openapi": "3.0.3",
+            "info": {
+                "title": title,
+                "version": version,
+            },
+            "tags": tags,
+            "paths": paths,
+            "components": components,
+        })
+    }
+
+    fn operation(&mut self, service: &ServiceDefinition, endpoint: &EndpointDefinition) -> Value {
+        let mut operation = Map::new();
+        operation.insert(
+            "operationId".to_string(),
+            json!(format!(
+                "{}.{}",
+                service.service_name().name(),
+                endpoint.endpoint_name().0
+            )),
+        );
+        operation.insert("tags".to_string(), json!([service.service_name().name()]));
+        insert_docs(&mut operation, endpoint.docs());
+        if endpoint.deprecated().is_some() {
+            operation.insert("deprecated".to_string(), json!(true));
+        }
+
+        let mut parameters = vec![];
+        let mut body = None;
+        for arg in endpoint.args() {
+            let (location, name) = match arg.param_type() {
+                ParameterType::Body(_) => {
+                    body = Some(arg);
+                    continue;
+                }
+                ParameterType::Path(_) => ("path", &arg.arg_name().0),
+                ParameterType::Query(query) => ("query", &query.param_id().0),
+                ParameterType::Header(header) => ("header", &header.param_id().0),
+            };
+            parameters.push(self.parameter(arg, location, name));
+        }
+        if !parameters.is_empty() {
+            operation.insert("parameters".to_string(), json!(parameters));
+        }
+        if let Some(body) = body {
+            operation.insert("requestBody".to_string(), self.request_body(body));
+        }
+
+        operation.insert("responses".to_string(), self.responses(endpoint));
+
+        if let Some(auth) = endpoint.auth() {
+            let scheme = self.security_scheme(auth);
+            operation.insert("security".to_string(), json!([{ scheme: [] }]));
+        }
+
+        Value::Object(operation)
+    }
+
+    fn parameter(&self, arg: &ArgumentDefinition, location: &str, name: &str) -> Value {
+        let mut parameter = Map::new();
+        parameter.insert("name".to_string(), json!(name));
+        parameter.insert("in".to_string(), json!(location));
+        insert_docs(&mut parameter, arg.docs());
+        parameter.insert(
+            "required".to_string(),
+            json!(is_required(&self.types, arg.type_())),
+        );
+        parameter.insert("schema".to_string(), self.schema(arg.type_()));
+
+        Value::Object(parameter)
+    }
+
+    fn request_body(&self, arg: &ArgumentDefinition) -> Value {
+        let mut body = Map::new();
+        insert_docs(&mut body, arg.docs());
+        body.insert(
+            "required".to_string(),
+            json!(is_required(&self.types, arg.type_())),
+        );
+        body.insert("content".to_string(), self.content(arg.type_()));
+
+        Value::Object(body)
+    }
+
+    fn responses(&self, endpoint: &EndpointDefinition) -> Value {
+        let mut responses = Map::new();
+
+        match endpoint.returns() {
+            Some(ty) => {
+                let (ty, optional) = match self.optional_inner(ty) {
+                    Some(inner) if self.is_binary(inner) => (inner, true),
+                    Some(_) => (ty, true),
+                    None => (ty, false),
+                };
+                responses.insert(
+                    "200".to_string(),
+                    json!({
+                        "description": "Success",
+                        "content": self.content(ty),
+                    }),
+                );
+                if optional {
+                    responses.insert("204".to_string(), json!({ "description": "Empty" }));
+                }
+            }
+            None => {
+                responses.insert("204".to_string(), json!({ "description": "Success" }));
+            }
+        }
+
+        responses.insert(
+            "default".to_string(),
+            json!({
+                "description": "Error",
+                "content": {
+                    "application/json": {
+                        "schema": schema_ref(SERIALIZABLE_ERROR),
+                    },
+                },
+            }),
+        );
+
+        Value::Object(responses)
+    }
+
+    fn content(&self, ty: &Type) -> Value {
+        let binary = match self.optional_inner(ty) {
+            Some(inner) => self.is_binary(inner),
+            None => self.is_binary(ty),
+        };
+
+        if binary {
+            json!({
+                "application/octet-stream": {
+                    "schema": {
+                        "type": "string",
+                        "format": "binary",
+                    },
+                },
+            })
+        } else {
+            json!({
+                "application/json": {
+                    "schema": self.schema(ty),
+                },
+            })
+        }
+    }
+
+    fn security_scheme(&mut self, auth: &AuthType) -> String {
+        let (name, scheme) = match auth {
+            AuthType::Header(_) => (
+                BEARER_AUTH.to_string(),
+                json!({
+                    "type": "http",
+                    "scheme": "bearer",
+                }),
+            ),
+            AuthType::Cookie(cookie) => (
+                format!("CookieAuth.{}", cookie.cookie_name()),
+                json!({
+                    "type": "apiKey",
+                    "in": "cookie",
+                    "name": cookie.cookie_name(),
+                }),
+            ),
+        };
+        self.security_schemes.insert(name.clone(), scheme);
+        name
+    }
+
+    fn type_definition(&self, def: &TypeDefinition) -> Value {
+        let (mut schema, docs) = match def {
+            TypeDefinition::Alias(def) => (self.schema(def.alias()), def.docs()),
+            TypeDefinition::Enum(def) => {
+                let values = def.values().iter().map(|v| v.value()).collect::<Vec<_>>();
+                let schema = json!({
+                    "type": "string",
+                    "enum": values,
+                });
+                (schema, def.docs())
+            }
+            TypeDefinition::Object(def) => (self.object(def.fields()), def.docs()),
+            TypeDefinition::Union(def) => {
+                let variants = def
+                    .union_()
+                    .iter()
+                    .map(|f| self.union_variant(f))
+                    .collect::<Vec<_>>();
+                (json!({ "oneOf": variants }), def.docs())
+            }
+        };
+
+        if let Some(docs) = docs {
+            schema = annotate(schema, "description", json!(docs.0));
+        }
+
+        schema
+    }
+
+    fn object(&self, fields: &[FieldDefinition]) -> Value {
+        let mut properties = Map::new();
+        let mut required = vec![];
+
+        for field in fields {
+            let mut schema = self.schema(field.type_());
+            if let Some(docs) = field.docs() {
+                schema = annotate(schema, "description", json!(docs.0));
+            }
+            if field.deprecated().is_some() {
+                schema = annotate(schema, "deprecated", json!(true));
+            }
+            properties.insert(field.field_name().0.clone(), schema);
+
+            if is_required(&self.types, field.type_()) {
+                required.push(&field.field_name().0);
+            }
+        }
+
+        let mut object = Map::new();
+        object.insert("type".to_string(), json!("object"));
+        object.insert("properties".to_string(), Value::Object(properties));
+        if !required.is_empty() {
+            object.insert("required".to_string(), json!(required));
+        }
+
+        Value::Object(object)
+    }
+
+    fn union_variant(&self, field: &FieldDefinition) -> Value {
+        let name = &field.field_name().0;
+
+        let mut schema = self.schema(field.type_());
+        if let Some(docs) = field.docs() {
+            schema = annotate(schema, "description", json!(docs.0));
+        }
+
+        let mut variant = json!({
+            "type": "object",
+            "properties": {
+                "type": {
+                    "type": "string",
+                    "enum": [name],
+                },
+                name: schema,
+            },
+            "required": ["type", name],
+        });
+        if field.deprecated().is_some() {
+            variant = annotate(variant, "deprecated", json!(true));
+        }
+
+        variant
+    }
+
+    fn schema(&self, ty: &Type) -> Value {
+        match ty {
+            Type::Primitive(primitive) => primitive_schema(primitive),
+            Type::Optional(def) => annotate(self.schema(def.item_type()), "nullable", json!(true)),
+            Type::List(def) => json!({
+                "type": "array",
+                "items": self.schema(def.item_type()),
+            }),
+            Type::Set(def) => json!({
+                "type": "array",
+                "items": self.schema(def.item_type()),
+                "uniqueItems": true,
+            }),
+            Type::Map(def) => json!({
+                "type": "object",
+                "additionalProperties": self.schema(def.value_type()),
+            }),
+            Type::Reference(name) => schema_ref(&self.schema_names[name]),
+            Type::External(def) => self.schema(def.fallback()),
+        }
+    }
+
+    fn is_binary(&self, ty: &Type) -> bool {
+        match ty {
+            Type::Primitive(PrimitiveType::Binary) => true,
+            Type::Reference(name) => match self.types[name] {
+                TypeDefinition::Alias(def) => self.is_binary(def.alias()),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn optional_inner<'b>(&'b self, ty: &'b Type) -> Option<&'b Type> {
+        match ty {
+            Type::Optional(def) => Some(def.item_type()),
+            Type::Reference(name) => match self.types[name] {
+                TypeDefinition::Alias(def) => self.optional_inner(def.alias()),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+// types are named by their simple name unless it is ambiguous
+pub fn schema_names(defs: &ConjureDefinition) -> HashMap<&TypeName, String> {
+    let mut counts = HashMap::new();
+    for def in defs.types() {
+        *counts.entry(type_name(def).name()).or_insert(0) += 1;
+    }
+
+    defs.types()
+        .iter()
+        .map(|def| {
+            let name = type_name(def);
+            let schema_name = if counts[name.name()] > 1 {
+                format!("{}.{}", name.package(), name.name())
+            } else {
+                name.name().to_string()
+            };
+            (name, schema_name)
+        })
+        .collect()
+}
+
+// fields may only be omitted if their type has a natural empty value
+pub fn is_required(types: &HashMap<&TypeName, &TypeDefinition>, ty: &Type) -> bool {
+    match ty {
+        Type::Primitive(_) => true,
+        Type::Optional(_) | Type::List(_) | Type::Set(_) | Type::Map(_) => false,
+        Type::Reference(name) => match types[name] {
+            TypeDefinition::Alias(def) => is_required(types, def.alias()),
+            TypeDefinition::Enum(_) | TypeDefinition::Object(_) | TypeDefinition::Union(_) => true,
+        },
+        Type::External(def) => is_required(types, def.fallback()),
+    }
+}
+
+pub fn type_name(def: &TypeDefinition) -> &TypeName {
+    match def {
+        TypeDefinition::Alias(def) => def.type_name(),
+        TypeDefinition::Enum(def) => def.type_name(),
+        TypeDefinition::Object(def) => def.type_name(),
+        TypeDefinition::Union(def) => def.type_name(),
+    }
+}
+
+// OpenAPI has no equivalent of Conjure's `{name:.*}` multi-segment path parameters, so they're treated like normal
+// parameters.
+fn openapi_path(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            if segment.starts_with('{') && segment.ends_with('}') {
+                let name = segment[1..segment.len() - 1].split(':').next().unwrap();
+                format!("{{{}}}", name)
+            } else {
+                segment.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+pub fn primitive_schema(primitive: &PrimitiveType) -> Value {
+    match primitive {
+        PrimitiveType::String => json!({ "type": "string" }),
+        PrimitiveType::Datetime => json!({
+            "type": "string",
+            "format": "date-time",
+        }),
+        PrimitiveType::Integer => json!({
+            "type": "integer",
+            "format": "int32",
+        }),
+        PrimitiveType::Double => json!({
+            "oneOf": [
+                {
+                    "type": "number",
+                    "format": "double",
+                },
+                {
+                    "type": "string",
+                    "enum": ["NaN", "Infinity", "-Infinity"],
+                },
+            ],
+        }),
+        PrimitiveType::Safelong => json!({
+            "type": "integer",
+            "format": "int64",
+            "minimum": -MAX_SAFE_LONG,
+            "maximum": MAX_SAFE_LONG,
+        }),
+        PrimitiveType::Binary => json!({
+            "type": "string",
+            "format": "byte",
+        }),
+        PrimitiveType::Any => json!({}),
+        PrimitiveType::Boolean => json!({ "type": "boolean" }),
+        PrimitiveType::Uuid => json!({
+            "type": "string",
+            "format": "uuid",
+        }),
+        PrimitiveType::Rid => json!({
+            "type": "string",
+            "pattern": RID_PATTERN,
+        }),
+        PrimitiveType::Bearertoken => json!({
+            "type": "string",
+            "pattern": BEARER_TOKEN_PATTERN,
+        }),
+    }
+}
+
+fn serializable_error() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "errorCode": {
+                "type": "string",
+                "enum": [
+                    "PERMISSION_DENIED",
+                    "INVALID_ARGUMENT",
+                    "NOT_FOUND",
+                    "CONFLICT",
+                    "REQUEST_ENTITY_TOO_LARGE",
+                    "FAILED_PRECONDITION",
+                    "INTERNAL",
+                    "TIMEOUT",
+                    "CUSTOM_CLIENT",
+                    "CUSTOM_SERVER",
+                ],
+            },
+            "errorName": { "type": "string" },
+            "errorInstanceId": {
+                "type": "string",
+                "format": "uuid",
+            },
+            "parameters": {
+                "type": "object",
+                "additionalProperties": { "type": "string" },
+            },
+        },
+        "required": ["errorCode", "errorName", "errorInstanceId"],
+    })
+}
+
+fn schema_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+// both OpenAPI and JSON Schema draft 7 ignore the siblings of a `$ref`, so references are wrapped in an `allOf` before
+// being annotated.
+pub fn annotate(schema: Value, key: &str, value: Value) -> Value {
+    let mut schema = match schema {
+        Value::Object(map) if !map.contains_key("$ref") => map,
+        schema => {
+            let mut map = Map::new();
+            map.insert("allOf".to_string(), json!([schema]));
+            map
+        }
+    };
+    schema.insert(key.to_string(), value);
+
+    Value::Object(schema)
+}
+
+fn insert_docs(map: &mut Map<String, Value>, docs: Option<&Documentation>) {
+    if let Some(docs) = docs {
+        map.insert("description".to_string(), json!(docs.0));
+    }
+}
@@ -0,0 +1,201 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use serde_json::{json, Value};
+use std::path::Path;
+
+use crate::compile_yaml;
+use crate::openapi::{generate_openapi, openapi_path};
+
+fn test_api() -> Value {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../conjure-test/test.yml");
+    let defs = compile_yaml(&[path]).unwrap();
+    generate_openapi(&defs, "Test API", "1.0.0")
+}
+
+#[test]
+fn paths() {
+    assert_eq!(openapi_path("/foo/{bar}/{baz:.*}"), "/foo/{bar}/{baz}");
+}
+
+#[test]
+fn info() {
+    let api = test_api();
+    assert_eq!(api["openapi"], "3.0.3");
+    assert_eq!(
+        api["info"],
+        json!({"title": "Test API", "version": "1.0.0"})
+    );
+}
+
+#[test]
+fn parameters() {
+    let api = test_api();
+
+    let operation = &api["paths"]["/test/queryParams"]["get"];
+    assert_eq!(operation["operationId"], "TestService.queryParams");
+    assert_eq!(
+        operation["parameters"],
+        json!([
+            {
+                "name": "normal",
+                "in": "query",
+                "required": true,
+                "schema": {"type": "string"},
+            },
+            {
+                "name": "custom",
+                "in": "query",
+                "required": false,
+                "schema": {"type": "integer", "format": "int32", "nullable": true},
+            },
+            {
+                "name": "list",
+                "in": "query",
+                "required": false,
+                "schema": {"type": "array", "items": {"type": "integer", "format": "int32"}},
+            },
+            {
+                "name": "set",
+                "in": "query",
+                "required": false,
+                "schema": {"type": "array", "items": {"type": "boolean"}, "uniqueItems": true},
+            },
+        ])
+    );
+
+    let operation = &api["paths"]["/test/pathParams/{foo}/{bar}/raw/{baz}"]["get"];
+    assert_eq!(operation["parameters"][2]["in"], "path");
+    assert_eq!(operation["parameters"][2]["required"], true);
+    assert_eq!(
+        operation["parameters"][2]["schema"]["pattern"],
+        super::RID_PATTERN
+    );
+
+    let operation = &api["paths"]["/test/aliasHeaders"]["get"];
+    assert_eq!(
+        operation["parameters"],
+        json!([{
+            "name": "Some-Optional-Header",
+            "in": "header",
+            "required": false,
+            "schema": {"$ref": "#/components/schemas/OptionalAliasAlias"},
+        }])
+    );
+}
+
+#[test]
+fn bodies() {
+    let api = test_api();
+
+    let operation = &api["paths"]["/test/optionalJsonRequest"]["post"];
+    assert_eq!(
+        operation["requestBody"],
+        json!({
+            "required": false,
+            "content": {
+                "application/json": {
+                    "schema": {"type": "string", "nullable": true},
+                },
+            },
+        })
+    );
+
+    let operation = &api["paths"]["/test/streamingAliasRequest"]["post"];
+    assert_eq!(
+        operation["requestBody"]["content"]["application/octet-stream"]["schema"],
+        json!({"type": "string", "format": "binary"})
+    );
+
+    let operation = &api["paths"]["/test/emptyRequest"]["post"];
+    assert_eq!(operation["responses"]["204"]["description"], "Success");
+    assert_eq!(
+        operation["responses"]["default"]["content"]["application/json"]["schema"],
+        json!({"$ref": "#/components/schemas/SerializableError"})
+    );
+
+    let operation = &api["paths"]["/test/optionalStreamingAliasResponse"]["get"];
+    let responses = operation["responses"].as_object().unwrap();
+    assert!(responses.contains_key("204"));
+    assert_eq!(
+        responses["200"]["content"]["application/octet-stream"]["schema"]["format"],
+        "binary"
+    );
+}
+
+#[test]
+fn auth() {
+    let api = test_api();
+
+    assert_eq!(
+        api["paths"]["/test/headerAuth"]["get"]["security"],
+        json!([{"BearerAuth": []}])
+    );
+    assert_eq!(
+        api["paths"]["/test/cookieAuth"]["get"]["security"],
+        json!([{"CookieAuth.foobar": []}])
+    );
+    assert_eq!(
+        api["components"]["securitySchemes"],
+        json!({
+            "BearerAuth": {"type": "http", "scheme": "bearer"},
+            "CookieAuth.foobar": {"type": "apiKey", "in": "cookie", "name": "foobar"},
+        })
+    );
+    assert!(api["paths"]["/test/deprecated"]["get"]["security"].is_null());
+    assert_eq!(api["paths"]["/test/deprecated"]["get"]["deprecated"], true);
+}
+
+#[test]
+fn types() {
+    let api = test_api();
+    let schemas = &api["components"]["schemas"];
+
+    assert_eq!(
+        schemas["TestEnum"],
+        json!({"type": "string", "enum": ["ONE", "TWO"]})
+    );
+    assert_eq!(
+        schemas["ExternalLongAlias"],
+        json!({
+            "type": "integer",
+            "format": "int64",
+            "minimum": -9_007_199_254_740_991i64,
+            "maximum": 9_007_199_254_740_991i64,
+        })
+    );
+    assert_eq!(
+        schemas["OptionalConstructorFields2"],
+        json!({
+            "type": "object",
+            "properties": {
+                "object": {
+                    "allOf": [{"$ref": "#/components/schemas/TestObject"}],
+                    "nullable": true,
+                },
+            },
+        })
+    );
+    assert_eq!(schemas["TestObject"]["required"], json!(["foo"]));
+    assert_eq!(
+        schemas["TestUnion"]["oneOf"][0],
+        json!({
+            "type": "object",
+            "properties": {
+                "type": {"type": "string", "enum": ["integer"]},
+                "integer": {"type": "integer", "format": "int32"},
+            },
+            "required": ["type", "integer"],
+        })
+    );
+}
@@ -143,6 +143,7 @@ fn generate_trait_endpoint(
     style: Style,
 ) -> TokenStream {
     let docs = ctx.docs(endpoint.docs());
+    let attributes = ctx.marker_attributes(endpoint.markers());
     let name = ctx.field_name(endpoint.endpoint_name());
     let (param, lt) = match style {
         Style::Async => (quote!(<'life0, 'async_trait>), quote!('life0)),
@@ -174,6 +175,7 @@ fn generate_trait_endpoint(
     // ignore deprecation since the endpoint has to be implemented regardless
     quote! {
         #docs
+        #attributes
         fn #name #param(&#lt self #auth_arg #(, #args)*) -> #ret_ty #where_;
     }
 }
@@ -751,6 +753,7 @@ fn generate_endpoint(ctx: &Context, endpoint: &EndpointDefinition, style: Style)
     let handler = handler_name(ctx, endpoint, style);
     let parameters = parameters(ctx, endpoint);
     let deprecated = endpoint.deprecated().is_some();
+    let markers = markers(ctx, endpoint.markers());
 
     quote! {
         conjure_http::server::#endpoint_name {
@@ -760,13 +763,14 @@ fn generate_endpoint(ctx: &Context, endpoint: &EndpointDefinition, style: Style)
                 #path,
                 #parameters,
                 #deprecated,
-            ),
+            )
+            #markers,
             handler: &#handler,
         }
     }
 }
 
-fn parameters(ctx: &Context, endpoint: &EndpointDefinition) -> TokenStream {
+pub fn parameters(ctx: &Context, endpoint: &EndpointDefinition) -> TokenStream {
     let parameters = endpoint
         .args()
         .iter()
@@ -825,8 +829,26 @@ fn parameter(ctx: &Context, argument: &ArgumentDefinition) -> Option<TokenStream
         quote!()
     };
 
+    let markers = markers(ctx, argument.markers());
+
     Some(quote! {
         conjure_http::server::Parameter::new(#name, #type_)
         #safe
+        #markers
     })
 }
+
+pub fn markers(ctx: &Context, markers: &[Type]) -> TokenStream {
+    let markers = markers
+        .iter()
+        .flat_map(|m| ctx.marker_name(m))
+        .collect::<Vec<_>>();
+
+    if markers.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            .with_markers(&[#(#markers),*])
+        }
+    }
+}
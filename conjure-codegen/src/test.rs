@@ -0,0 +1,48 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::types::ConjureDefinition;
+use crate::Config;
+
+fn empty_definition() -> ConjureDefinition {
+    conjure_serde::json::client_from_str(
+        r#"{"version": 1, "errors": [], "types": [], "services": []}"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn invalid_marker_attribute() {
+    let e = Config::new()
+        .marker_attribute("com.foo.Idempotent", "doc(alias = \"idempotent\"")
+        .generate_tokens(&empty_definition())
+        .err()
+        .unwrap();
+    assert_eq!(
+        e.to_string(),
+        "invalid Rust attribute `doc(alias = \"idempotent\"` for marker `com.foo.Idempotent`"
+    );
+}
+
+#[test]
+fn invalid_marker_bound() {
+    let e = Config::new()
+        .marker_bound("com.foo.Idempotent", "(crate::Idempotent")
+        .generate_tokens(&empty_definition())
+        .err()
+        .unwrap();
+    assert_eq!(
+        e.to_string(),
+        "invalid Rust bound `(crate::Idempotent` for marker `com.foo.Idempotent`"
+    );
+}
@@ -81,6 +81,24 @@ impl Builder {
             docs: self.docs.clone(),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<AliasDefinition, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.type_name.is_none() {
+            missing_fields.push("type_name");
+        }
+        if self.alias.is_none() {
+            missing_fields.push("alias");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "AliasDefinition",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<AliasDefinition> for Builder {
     #[inline]
@@ -110,6 +110,27 @@ impl Builder {
             markers: self.markers.clone(),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<ArgumentDefinition, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.arg_name.is_none() {
+            missing_fields.push("arg_name");
+        }
+        if self.type_.is_none() {
+            missing_fields.push("type_");
+        }
+        if self.param_type.is_none() {
+            missing_fields.push("param_type");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "ArgumentDefinition",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<ArgumentDefinition> for Builder {
     #[inline]
@@ -115,6 +115,21 @@ impl Builder {
             services: self.services.clone(),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<ConjureDefinition, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.version.is_none() {
+            missing_fields.push("version");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "ConjureDefinition",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<ConjureDefinition> for Builder {
     #[inline]
@@ -55,6 +55,21 @@ impl Builder {
                 .expect("field cookie_name was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<CookieAuthType, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.cookie_name.is_none() {
+            missing_fields.push("cookie_name");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "CookieAuthType",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<CookieAuthType> for Builder {
     #[inline]
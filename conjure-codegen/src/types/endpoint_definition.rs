@@ -180,6 +180,27 @@ impl Builder {
             markers: self.markers.clone(),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<EndpointDefinition, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.endpoint_name.is_none() {
+            missing_fields.push("endpoint_name");
+        }
+        if self.http_method.is_none() {
+            missing_fields.push("http_method");
+        }
+        if self.http_path.is_none() {
+            missing_fields.push("http_path");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "EndpointDefinition",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<EndpointDefinition> for Builder {
     #[inline]
@@ -86,6 +86,21 @@ impl Builder {
             deprecated: self.deprecated.clone(),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<EnumValueDefinition, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.value.is_none() {
+            missing_fields.push("value");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "EnumValueDefinition",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<EnumValueDefinition> for Builder {
     #[inline]
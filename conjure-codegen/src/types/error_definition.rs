@@ -135,6 +135,27 @@ impl Builder {
             unsafe_args: self.unsafe_args.clone(),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<ErrorDefinition, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.error_name.is_none() {
+            missing_fields.push("error_name");
+        }
+        if self.namespace.is_none() {
+            missing_fields.push("namespace");
+        }
+        if self.code.is_none() {
+            missing_fields.push("code");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "ErrorDefinition",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<ErrorDefinition> for Builder {
     #[inline]
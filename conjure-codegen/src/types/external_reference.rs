@@ -69,6 +69,24 @@ impl Builder {
             fallback: self.fallback.clone().expect("field fallback was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<ExternalReference, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.external_reference.is_none() {
+            missing_fields.push("external_reference");
+        }
+        if self.fallback.is_none() {
+            missing_fields.push("fallback");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "ExternalReference",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<ExternalReference> for Builder {
     #[inline]
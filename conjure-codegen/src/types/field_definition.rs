@@ -85,6 +85,24 @@ impl Builder {
             deprecated: self.deprecated.clone(),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<FieldDefinition, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.field_name.is_none() {
+            missing_fields.push("field_name");
+        }
+        if self.type_.is_none() {
+            missing_fields.push("type_");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "FieldDefinition",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<FieldDefinition> for Builder {
     #[inline]
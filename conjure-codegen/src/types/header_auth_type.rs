@@ -28,6 +28,11 @@ impl Builder {
     pub fn build(&self) -> HeaderAuthType {
         HeaderAuthType {}
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<HeaderAuthType, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<HeaderAuthType> for Builder {
     #[inline]
@@ -47,6 +47,21 @@ impl Builder {
             item_type: self.item_type.clone().expect("field item_type was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<ListType, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.item_type.is_none() {
+            missing_fields.push("item_type");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "ListType",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<ListType> for Builder {
     #[inline]
@@ -65,6 +65,24 @@ impl Builder {
                 .expect("field value_type was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<MapType, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.key_type.is_none() {
+            missing_fields.push("key_type");
+        }
+        if self.value_type.is_none() {
+            missing_fields.push("value_type");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "MapType",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<MapType> for Builder {
     #[inline]
@@ -28,6 +28,11 @@ impl Builder {
     pub fn build(&self) -> PathParameterType {
         PathParameterType {}
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<PathParameterType, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<PathParameterType> for Builder {
     #[inline]
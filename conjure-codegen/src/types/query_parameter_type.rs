@@ -45,6 +45,21 @@ impl Builder {
             param_id: self.param_id.clone().expect("field param_id was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<QueryParameterType, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.param_id.is_none() {
+            missing_fields.push("param_id");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "QueryParameterType",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<QueryParameterType> for Builder {
     #[inline]
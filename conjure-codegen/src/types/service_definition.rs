@@ -98,6 +98,21 @@ impl Builder {
             docs: self.docs.clone(),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<ServiceDefinition, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.service_name.is_none() {
+            missing_fields.push("service_name");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "ServiceDefinition",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<ServiceDefinition> for Builder {
     #[inline]
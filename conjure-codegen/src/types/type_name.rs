@@ -74,6 +74,24 @@ impl Builder {
             package: self.package.clone().expect("field package was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<TypeName, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.name.is_none() {
+            missing_fields.push("name");
+        }
+        if self.package.is_none() {
+            missing_fields.push("package");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "TypeName",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<TypeName> for Builder {
     #[inline]
@@ -0,0 +1,736 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use failure::{bail, format_err, Error, ResultExt};
+use std::collections::{BTreeSet, HashMap};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::types::{
+    AliasDefinition, ArgumentDefinition, ArgumentName, AuthType, BodyParameterType,
+    ConjureDefinition, CookieAuthType, Documentation, EndpointDefinition, EndpointName,
+    EnumDefinition, EnumValueDefinition, ErrorCode, ErrorDefinition, ErrorNamespace,
+    ExternalReference, FieldDefinition, FieldName, HeaderAuthType, HeaderParameterType, HttpMethod,
+    HttpPath, ListType, MapType, ObjectDefinition, OptionalType, ParameterId, ParameterType,
+    PathParameterType, PrimitiveType, QueryParameterType, ServiceDefinition, SetType, Type,
+    TypeDefinition, TypeName, UnionDefinition,
+};
+
+mod raw;
+#[cfg(test)]
+mod test;
+
+/// Compiles Conjure YAML definitions into Conjure IR.
+///
+/// Each input may either be a YAML file or a directory, in which case all `.yml` and `.yaml` files directly inside
+/// of it are compiled. Files referenced via `conjure-imports` are loaded automatically, relative to the importing
+/// file, and their definitions are included in the output.
+pub fn compile_yaml<I, P>(inputs: I) -> Result<ConjureDefinition, Error>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+{
+    let mut compiler = Compiler::new();
+    for input in inputs {
+        compiler.load_input(input.as_ref())?;
+    }
+    compiler.compile()
+}
+
+struct File {
+    path: PathBuf,
+    raw: raw::ConjureFile,
+    conjure_imports: HashMap<String, usize>,
+}
+
+#[derive(Default)]
+struct Scope {
+    types: HashMap<String, TypeName>,
+    externals: HashMap<String, Type>,
+}
+
+struct Compiler {
+    files: Vec<File>,
+    files_by_path: HashMap<PathBuf, usize>,
+    scopes: Vec<Scope>,
+}
+
+impl Compiler {
+    fn new() -> Compiler {
+        Compiler {
+            files: vec![],
+            files_by_path: HashMap::new(),
+            scopes: vec![],
+        }
+    }
+
+    fn load_input(&mut self, input: &Path) -> Result<(), Error> {
+        if !input.is_dir() {
+            self.load_file(input)?;
+            return Ok(());
+        }
+
+        let mut paths = vec![];
+        let entries = fs::read_dir(input)
+            .with_context(|_| format!("error reading directory {}", input.display()))?;
+        for entry in entries {
+            let path = entry
+                .with_context(|_| format!("error reading directory {}", input.display()))?
+                .path();
+            let is_yaml = path.extension() == Some(OsStr::new("yml"))
+                || path.extension() == Some(OsStr::new("yaml"));
+            if is_yaml && path.is_file() {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        for path in paths {
+            self.load_file(&path)?;
+        }
+
+        Ok(())
+    }
+
+    fn load_file(&mut self, path: &Path) -> Result<usize, Error> {
+        let path = path
+            .canonicalize()
+            .with_context(|_| format!("error reading file {}", path.display()))?;
+        if let Some(&idx) = self.files_by_path.get(&path) {
+            return Ok(idx);
+        }
+
+        let contents = fs::read_to_string(&path)
+            .with_context(|_| format!("error reading file {}", path.display()))?;
+        self.load_source(path, &contents)
+    }
+
+    fn load_source(&mut self, path: PathBuf, contents: &str) -> Result<usize, Error> {
+        let raw = serde_yaml::from_str::<raw::ConjureFile>(contents)
+            .with_context(|_| format!("error parsing Conjure YAML file {}", path.display()))?;
+
+        let idx = self.files.len();
+        self.files_by_path.insert(path.clone(), idx);
+        self.files.push(File {
+            path: path.clone(),
+            raw,
+            conjure_imports: HashMap::new(),
+        });
+
+        let imports = self.files[idx]
+            .raw
+            .types
+            .conjure_imports
+            .0
+            .iter()
+            .map(|(namespace, import)| (namespace.clone(), import.clone()))
+            .collect::<Vec<_>>();
+        let base = path.parent().unwrap_or_else(|| Path::new(""));
+        for (namespace, import) in imports {
+            let import_idx = self
+                .load_file(&base.join(&import))
+                .with_context(|_| format!("error importing `{}` in {}", import, path.display()))?;
+            self.files[idx]
+                .conjure_imports
+                .insert(namespace, import_idx);
+        }
+
+        Ok(idx)
+    }
+
+    fn compile(mut self) -> Result<ConjureDefinition, Error> {
+        self.build_scopes()?;
+
+        let mut types = vec![];
+        let mut errors = vec![];
+        let mut services = vec![];
+
+        for (idx, file) in self.files.iter().enumerate() {
+            let definitions = &file.raw.types.definitions;
+            for (name, def) in &definitions.objects.0 {
+                let def = self
+                    .type_definition(idx, name, def)
+                    .with_context(|_| format!("error compiling type `{}`", name))
+                    .with_context(|_| format!("error compiling {}", file.path.display()))?;
+                types.push(def);
+            }
+
+            for (name, def) in &definitions.errors.0 {
+                let def = self
+                    .error_definition(idx, name, def)
+                    .with_context(|_| format!("error compiling error `{}`", name))
+                    .with_context(|_| format!("error compiling {}", file.path.display()))?;
+                errors.push(def);
+            }
+
+            for (name, def) in &file.raw.services.0 {
+                let def = self
+                    .service_definition(idx, name, def)
+                    .with_context(|_| format!("error compiling service `{}`", name))
+                    .with_context(|_| format!("error compiling {}", file.path.display()))?;
+                services.push(def);
+            }
+        }
+
+        types.sort_by(|a, b| {
+            sort_key(type_definition_name(a)).cmp(&sort_key(type_definition_name(b)))
+        });
+        errors.sort_by(|a, b| sort_key(a.error_name()).cmp(&sort_key(b.error_name())));
+        services.sort_by(|a, b| sort_key(a.service_name()).cmp(&sort_key(b.service_name())));
+
+        let mut names = BTreeSet::new();
+        let all_names = types
+            .iter()
+            .map(type_definition_name)
+            .chain(errors.iter().map(|e| e.error_name()))
+            .chain(services.iter().map(|s| s.service_name()));
+        for name in all_names {
+            if !names.insert(name) {
+                bail!(
+                    "type `{}` is defined more than once in package `{}`",
+                    name.name(),
+                    name.package()
+                );
+            }
+        }
+
+        Ok(ConjureDefinition::builder()
+            .version(1)
+            .types(types)
+            .errors(errors)
+            .services(services)
+            .build())
+    }
+
+    fn build_scopes(&mut self) -> Result<(), Error> {
+        for file in &self.files {
+            let definitions = &file.raw.types.definitions;
+            let mut scope = Scope::default();
+
+            for (name, def) in &definitions.objects.0 {
+                let package = def
+                    .package()
+                    .or(definitions.default_package.as_deref())
+                    .ok_or_else(|| {
+                        format_err!(
+                            "type `{}` in {} has no package and no default-package is set",
+                            name,
+                            file.path.display(),
+                        )
+                    })?;
+                scope
+                    .types
+                    .insert(name.clone(), TypeName::new(name.clone(), package));
+            }
+
+            self.scopes.push(scope);
+        }
+
+        // external imports may only fall back to types which don't themselves depend on external imports
+        for idx in 0..self.files.len() {
+            let mut externals = HashMap::new();
+            for (name, import) in &self.files[idx].raw.types.imports.0 {
+                let fallback = match &import.base_type {
+                    Some(base_type) => self.resolve_type(idx, base_type).with_context(|_| {
+                        format!(
+                            "error compiling import `{}` in {}",
+                            name,
+                            self.files[idx].path.display()
+                        )
+                    })?,
+                    None => Type::Primitive(PrimitiveType::Any),
+                };
+                let reference = external_reference(&import.external.java)
+                    .with_context(|_| format!("error compiling import `{}`", name))?;
+                externals.insert(
+                    name.clone(),
+                    Type::External(ExternalReference::new(reference, fallback)),
+                );
+            }
+            self.scopes[idx].externals = externals;
+        }
+
+        Ok(())
+    }
+
+    fn type_definition(
+        &self,
+        file: usize,
+        name: &str,
+        def: &raw::TypeDefinition,
+    ) -> Result<TypeDefinition, Error> {
+        let type_name = self.scopes[file].types[name].clone();
+
+        let def = match def {
+            raw::TypeDefinition::Alias(def) => TypeDefinition::Alias(
+                AliasDefinition::builder()
+                    .type_name(type_name)
+                    .alias(self.resolve_type(file, &def.alias)?)
+                    .docs(docs(&def.docs))
+                    .build(),
+            ),
+            raw::TypeDefinition::Enum(def) => {
+                let mut values = vec![];
+                for value in &def.values {
+                    let value = match value {
+                        raw::EnumValueDefinition::Simple(value) => {
+                            EnumValueDefinition::builder().value(&**value).build()
+                        }
+                        raw::EnumValueDefinition::Full(value) => EnumValueDefinition::builder()
+                            .value(&*value.value)
+                            .docs(docs(&value.docs))
+                            .deprecated(docs(&value.deprecated))
+                            .build(),
+                    };
+                    values.push(value);
+                }
+
+                TypeDefinition::Enum(
+                    EnumDefinition::builder()
+                        .type_name(type_name)
+                        .values(values)
+                        .docs(docs(&def.docs))
+                        .build(),
+                )
+            }
+            raw::TypeDefinition::Union(def) => TypeDefinition::Union(
+                UnionDefinition::builder()
+                    .type_name(type_name)
+                    .union_(self.fields(file, &def.union)?)
+                    .docs(docs(&def.docs))
+                    .build(),
+            ),
+            raw::TypeDefinition::Object(def) => TypeDefinition::Object(
+                ObjectDefinition::builder()
+                    .type_name(type_name)
+                    .fields(self.fields(file, &def.fields)?)
+                    .docs(docs(&def.docs))
+                    .build(),
+            ),
+        };
+
+        Ok(def)
+    }
+
+    fn error_definition(
+        &self,
+        file: usize,
+        name: &str,
+        def: &raw::ErrorDefinition,
+    ) -> Result<ErrorDefinition, Error> {
+        let package = def
+            .package
+            .as_ref()
+            .or_else(|| {
+                self.files[file]
+                    .raw
+                    .types
+                    .definitions
+                    .default_package
+                    .as_ref()
+            })
+            .ok_or_else(|| format_err!("error has no package and no default-package is set"))?;
+        let code = def
+            .code
+            .parse::<ErrorCode>()
+            .map_err(|_| format_err!("invalid error code `{}`", def.code))?;
+
+        Ok(ErrorDefinition::builder()
+            .error_name(TypeName::new(name, &**package))
+            .docs(docs(&def.docs))
+            .namespace(ErrorNamespace(def.namespace.clone()))
+            .code(code)
+            .safe_args(self.fields(file, &def.safe_args)?)
+            .unsafe_args(self.fields(file, &def.unsafe_args)?)
+            .build())
+    }
+
+    fn fields(
+        &self,
+        file: usize,
+        fields: &raw::OrderedMap<raw::FieldDefinition>,
+    ) -> Result<Vec<FieldDefinition>, Error> {
+        let mut out = vec![];
+
+        for (name, field) in &fields.0 {
+            let mut builder = FieldDefinition::builder();
+            builder.field_name(FieldName(name.clone()));
+            let type_ = match field {
+                raw::FieldDefinition::Simple(type_) => type_,
+                raw::FieldDefinition::Full(field) => {
+                    builder
+                        .docs(docs(&field.docs))
+                        .deprecated(docs(&field.deprecated));
+                    &field.type_
+                }
+            };
+            let type_ = self
+                .resolve_type(file, type_)
+                .with_context(|_| format!("error compiling field `{}`", name))?;
+            out.push(builder.type_(type_).build());
+        }
+
+        Ok(out)
+    }
+
+    fn service_definition(
+        &self,
+        file: usize,
+        name: &str,
+        def: &raw::ServiceDefinition,
+    ) -> Result<ServiceDefinition, Error> {
+        let default_auth = match &def.default_auth {
+            Some(auth) => parse_auth(auth)?,
+            None => None,
+        };
+
+        let mut endpoints = vec![];
+        for (endpoint_name, endpoint) in &def.endpoints.0 {
+            let endpoint = self
+                .endpoint_definition(file, def, &default_auth, endpoint_name, endpoint)
+                .with_context(|_| format!("error compiling endpoint `{}`", endpoint_name))?;
+            endpoints.push(endpoint);
+        }
+
+        Ok(ServiceDefinition::builder()
+            .service_name(TypeName::new(name, &*def.package))
+            .endpoints(endpoints)
+            .docs(docs(&def.docs))
+            .build())
+    }
+
+    fn endpoint_definition(
+        &self,
+        file: usize,
+        service: &raw::ServiceDefinition,
+        default_auth: &Option<AuthType>,
+        name: &str,
+        def: &raw::EndpointDefinition,
+    ) -> Result<EndpointDefinition, Error> {
+        let mut http = def.http.split_whitespace();
+        let (method, path) = match (http.next(), http.next(), http.next()) {
+            (Some(method), Some(path), None) => (method, path),
+            _ => bail!("invalid http definition `{}`", def.http),
+        };
+        let method = method
+            .parse::<HttpMethod>()
+            .map_err(|_| format_err!("invalid HTTP method `{}`", method))?;
+        if !path.starts_with('/') {
+            bail!("endpoint path `{}` must begin with `/`", path);
+        }
+        let path = join_paths(&service.base_path, path);
+        let mut path_params = path_params(&path)?;
+
+        let auth = match &def.auth {
+            Some(auth) => parse_auth(auth)?,
+            None => default_auth.clone(),
+        };
+
+        let mut args = vec![];
+        let mut has_body = false;
+        for (arg_name, arg) in &def.args.0 {
+            let arg = self
+                .argument_definition(file, &mut path_params, arg_name, arg)
+                .with_context(|_| format!("error compiling argument `{}`", arg_name))?;
+            if let ParameterType::Body(_) = arg.param_type() {
+                if has_body {
+                    bail!("endpoint has multiple body arguments");
+                }
+                if let HttpMethod::Get = method {
+                    bail!("GET endpoints cannot have a body argument");
+                }
+                has_body = true;
+            }
+            args.push(arg);
+        }
+
+        if let Some(param) = path_params.iter().next() {
+            bail!("path parameter `{}` has no corresponding argument", param);
+        }
+
+        let returns = match &def.returns {
+            Some(returns) => Some(self.resolve_type(file, returns)?),
+            None => None,
+        };
+
+        let markers = def
+            .markers
+            .iter()
+            .map(|m| self.resolve_type(file, m))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(EndpointDefinition::builder()
+            .endpoint_name(EndpointName(name.to_string()))
+            .http_method(method)
+            .http_path(HttpPath(path))
+            .auth(auth)
+            .args(args)
+            .returns(returns)
+            .docs(docs(&def.docs))
+            .deprecated(docs(&def.deprecated))
+            .markers(markers)
+            .build())
+    }
+
+    fn argument_definition(
+        &self,
+        file: usize,
+        path_params: &mut BTreeSet<String>,
+        name: &str,
+        def: &raw::ArgumentDefinition,
+    ) -> Result<ArgumentDefinition, Error> {
+        let mut builder = ArgumentDefinition::builder();
+        builder.arg_name(ArgumentName(name.to_string()));
+
+        let (type_, param_type, param_id) = match def {
+            raw::ArgumentDefinition::Simple(type_) => (type_, None, None),
+            raw::ArgumentDefinition::Full(def) => {
+                let markers = def
+                    .markers
+                    .iter()
+                    .map(|m| self.resolve_type(file, m))
+                    .collect::<Result<Vec<_>, _>>()?;
+                builder.docs(docs(&def.docs)).markers(markers);
+                (&def.type_, def.param_type.as_deref(), def.param_id.as_ref())
+            }
+        };
+
+        let param_type = match param_type {
+            None | Some("auto") => {
+                if path_params.contains(name) {
+                    "path"
+                } else {
+                    "body"
+                }
+            }
+            Some(param_type) => param_type,
+        };
+
+        let param_type = match param_type {
+            "path" => {
+                if !path_params.remove(name) {
+                    bail!("path parameter is not present in the endpoint's path");
+                }
+                if param_id.is_some() {
+                    bail!("path parameters cannot have a param-id");
+                }
+                ParameterType::Path(PathParameterType::new())
+            }
+            "body" => {
+                if param_id.is_some() {
+                    bail!("body parameters cannot have a param-id");
+                }
+                ParameterType::Body(BodyParameterType::new())
+            }
+            "query" => {
+                let param_id = param_id.map_or(name, |s| &**s);
+                ParameterType::Query(QueryParameterType::new(ParameterId(param_id.to_string())))
+            }
+            "header" => {
+                let param_id =
+                    param_id.ok_or_else(|| format_err!("header parameters require a param-id"))?;
+                ParameterType::Header(HeaderParameterType::new(ParameterId(param_id.clone())))
+            }
+            param_type => bail!("invalid param-type `{}`", param_type),
+        };
+
+        Ok(builder
+            .type_(self.resolve_type(file, type_)?)
+            .param_type(param_type)
+            .build())
+    }
+
+    fn resolve_type(&self, file: usize, type_: &str) -> Result<Type, Error> {
+        let type_ = type_.trim();
+
+        if let Some(inner) = generic(type_, "optional") {
+            return Ok(Type::Optional(OptionalType::new(
+                self.resolve_type(file, inner)?,
+            )));
+        }
+
+        if let Some(inner) = generic(type_, "list") {
+            return Ok(Type::List(ListType::new(self.resolve_type(file, inner)?)));
+        }
+
+        if let Some(inner) = generic(type_, "set") {
+            return Ok(Type::Set(SetType::new(self.resolve_type(file, inner)?)));
+        }
+
+        if let Some(inner) = generic(type_, "map") {
+            let (key, value) =
+                split_map(inner).ok_or_else(|| format_err!("invalid map type `{}`", type_))?;
+            return Ok(Type::Map(MapType::new(
+                self.resolve_type(file, key)?,
+                self.resolve_type(file, value)?,
+            )));
+        }
+
+        if let Some(primitive) = primitive(type_) {
+            return Ok(Type::Primitive(primitive));
+        }
+
+        let scope = &self.scopes[file];
+        let mut parts = type_.splitn(2, '.');
+        let (namespace, name) = match (parts.next(), parts.next()) {
+            (Some(namespace), Some(name)) => (Some(namespace), name),
+            _ => (None, type_),
+        };
+
+        match namespace {
+            Some(namespace) => {
+                let imported =
+                    self.files[file]
+                        .conjure_imports
+                        .get(namespace)
+                        .ok_or_else(|| {
+                            format_err!("unknown conjure-import namespace `{}`", namespace)
+                        })?;
+                let imported = &self.scopes[*imported];
+                if let Some(type_name) = imported.types.get(name) {
+                    Ok(Type::Reference(type_name.clone()))
+                } else if let Some(external) = imported.externals.get(name) {
+                    Ok(external.clone())
+                } else {
+                    bail!("unknown type `{}`", type_)
+                }
+            }
+            None => {
+                if let Some(type_name) = scope.types.get(name) {
+                    Ok(Type::Reference(type_name.clone()))
+                } else if let Some(external) = scope.externals.get(name) {
+                    Ok(external.clone())
+                } else {
+                    bail!("unknown type `{}`", type_)
+                }
+            }
+        }
+    }
+}
+
+fn sort_key(name: &TypeName) -> (&str, &str) {
+    (name.package(), name.name())
+}
+
+fn type_definition_name(def: &TypeDefinition) -> &TypeName {
+    match def {
+        TypeDefinition::Alias(def) => def.type_name(),
+        TypeDefinition::Enum(def) => def.type_name(),
+        TypeDefinition::Object(def) => def.type_name(),
+        TypeDefinition::Union(def) => def.type_name(),
+    }
+}
+
+fn docs(docs: &Option<String>) -> Option<Documentation> {
+    docs.as_ref().map(|d| Documentation(d.clone()))
+}
+
+fn external_reference(java: &str) -> Result<TypeName, Error> {
+    match java.rfind('.') {
+        Some(idx) => Ok(TypeName::new(&java[idx + 1..], &java[..idx])),
+        None => bail!("external type `{}` must be fully qualified", java),
+    }
+}
+
+fn parse_auth(auth: &str) -> Result<Option<AuthType>, Error> {
+    let auth = match auth {
+        "none" => None,
+        "header" => Some(AuthType::Header(HeaderAuthType::new())),
+        _ if auth.starts_with("cookie:") => Some(AuthType::Cookie(CookieAuthType::new(
+            &auth["cookie:".len()..],
+        ))),
+        _ => bail!("invalid auth type `{}`", auth),
+    };
+
+    Ok(auth)
+}
+
+fn join_paths(base: &str, path: &str) -> String {
+    let base = base.trim_end_matches('/');
+    if path == "/" && !base.is_empty() {
+        base.to_string()
+    } else {
+        format!("{}{}", base, path)
+    }
+}
+
+fn path_params(path: &str) -> Result<BTreeSet<String>, Error> {
+    let mut params = BTreeSet::new();
+
+    for segment in path.split('/') {
+        if !segment.starts_with('{') {
+            continue;
+        }
+        if !segment.ends_with('}') {
+            bail!("invalid path segment `{}`", segment);
+        }
+
+        let param = &segment[1..segment.len() - 1];
+        let param = match param.find(':') {
+            Some(idx) => &param[..idx],
+            None => param,
+        };
+        if !params.insert(param.to_string()) {
+            bail!("path parameter `{}` appears more than once", param);
+        }
+    }
+
+    Ok(params)
+}
+
+fn generic<'a>(type_: &'a str, name: &str) -> Option<&'a str> {
+    if !type_.starts_with(name) {
+        return None;
+    }
+
+    let rest = type_[name.len()..].trim_start();
+    if rest.starts_with('<') && rest.ends_with('>') {
+        Some(&rest[1..rest.len() - 1])
+    } else {
+        None
+    }
+}
+
+fn split_map(inner: &str) -> Option<(&str, &str)> {
+    let mut depth = 0;
+    for (idx, c) in inner.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            ',' if depth == 0 => return Some((&inner[..idx], &inner[idx + 1..])),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn primitive(type_: &str) -> Option<PrimitiveType> {
+    let primitive = match type_ {
+        "string" => PrimitiveType::String,
+        "datetime" => PrimitiveType::Datetime,
+        "integer" => PrimitiveType::Integer,
+        "double" => PrimitiveType::Double,
+        "safelong" => PrimitiveType::Safelong,
+        "binary" => PrimitiveType::Binary,
+        "any" => PrimitiveType::Any,
+        "boolean" => PrimitiveType::Boolean,
+        "uuid" => PrimitiveType::Uuid,
+        "rid" => PrimitiveType::Rid,
+        "bearertoken" => PrimitiveType::Bearertoken,
+        _ => return None,
+    };
+
+    Some(primitive)
+}
@@ -0,0 +1,277 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use serde::de::{self, Deserialize, Deserializer, IgnoredAny, MapAccess, Visitor};
+use serde::Deserialize as DeriveDeserialize;
+use std::collections::HashSet;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A string-keyed map which preserves the order of its entries and rejects duplicate keys.
+pub struct OrderedMap<V>(pub Vec<(String, V)>);
+
+impl<V> Default for OrderedMap<V> {
+    fn default() -> OrderedMap<V> {
+        OrderedMap(vec![])
+    }
+}
+
+impl<'de, V> Deserialize<'de> for OrderedMap<V>
+where
+    V: Deserialize<'de>,
+{
+    fn deserialize<D>(d: D) -> Result<OrderedMap<V>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        d.deserialize_map(OrderedMapVisitor(PhantomData))
+    }
+}
+
+struct OrderedMapVisitor<V>(PhantomData<V>);
+
+impl<'de, V> Visitor<'de> for OrderedMapVisitor<V>
+where
+    V: Deserialize<'de>,
+{
+    type Value = OrderedMap<V>;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("a map")
+    }
+
+    fn visit_unit<E>(self) -> Result<OrderedMap<V>, E>
+    where
+        E: de::Error,
+    {
+        Ok(OrderedMap::default())
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<OrderedMap<V>, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut keys = HashSet::new();
+        let mut entries = vec![];
+        while let Some(key) = map.next_key::<String>()? {
+            if !keys.insert(key.clone()) {
+                return Err(de::Error::custom(format_args!("duplicate key `{}`", key)));
+            }
+            let value = map.next_value()?;
+            entries.push((key, value));
+        }
+
+        Ok(OrderedMap(entries))
+    }
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ConjureFile {
+    #[serde(default)]
+    pub types: TypesDefinition,
+    #[serde(default)]
+    pub services: OrderedMap<ServiceDefinition>,
+    #[serde(default, rename = "extensions")]
+    _extensions: IgnoredAny,
+}
+
+#[derive(DeriveDeserialize, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct TypesDefinition {
+    #[serde(default)]
+    pub conjure_imports: OrderedMap<String>,
+    #[serde(default)]
+    pub imports: OrderedMap<ExternalTypeDefinition>,
+    #[serde(default)]
+    pub definitions: NamedTypesDefinition,
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ExternalTypeDefinition {
+    pub base_type: Option<String>,
+    pub external: ExternalImport,
+    #[serde(default, rename = "docs")]
+    _docs: IgnoredAny,
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExternalImport {
+    pub java: String,
+}
+
+#[derive(DeriveDeserialize, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct NamedTypesDefinition {
+    pub default_package: Option<String>,
+    #[serde(default)]
+    pub objects: OrderedMap<TypeDefinition>,
+    #[serde(default)]
+    pub errors: OrderedMap<ErrorDefinition>,
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(untagged)]
+pub enum TypeDefinition {
+    Alias(AliasDefinition),
+    Enum(EnumDefinition),
+    Union(UnionDefinition),
+    Object(ObjectDefinition),
+}
+
+impl TypeDefinition {
+    pub fn package(&self) -> Option<&str> {
+        let package = match self {
+            TypeDefinition::Alias(def) => &def.package,
+            TypeDefinition::Enum(def) => &def.package,
+            TypeDefinition::Union(def) => &def.package,
+            TypeDefinition::Object(def) => &def.package,
+        };
+        package.as_ref().map(|s| &**s)
+    }
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AliasDefinition {
+    pub alias: String,
+    pub package: Option<String>,
+    pub docs: Option<String>,
+    #[serde(default, rename = "safety")]
+    _safety: IgnoredAny,
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnumDefinition {
+    pub values: Vec<EnumValueDefinition>,
+    pub package: Option<String>,
+    pub docs: Option<String>,
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(untagged)]
+pub enum EnumValueDefinition {
+    Simple(String),
+    Full(FullEnumValueDefinition),
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FullEnumValueDefinition {
+    pub value: String,
+    pub docs: Option<String>,
+    pub deprecated: Option<String>,
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UnionDefinition {
+    pub union: OrderedMap<FieldDefinition>,
+    pub package: Option<String>,
+    pub docs: Option<String>,
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ObjectDefinition {
+    pub fields: OrderedMap<FieldDefinition>,
+    pub package: Option<String>,
+    pub docs: Option<String>,
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(untagged)]
+pub enum FieldDefinition {
+    Simple(String),
+    Full(FullFieldDefinition),
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FullFieldDefinition {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub docs: Option<String>,
+    pub deprecated: Option<String>,
+    #[serde(default, rename = "safety")]
+    _safety: IgnoredAny,
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ErrorDefinition {
+    pub namespace: String,
+    pub code: String,
+    pub package: Option<String>,
+    pub docs: Option<String>,
+    #[serde(default)]
+    pub safe_args: OrderedMap<FieldDefinition>,
+    #[serde(default)]
+    pub unsafe_args: OrderedMap<FieldDefinition>,
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ServiceDefinition {
+    #[serde(default, rename = "name")]
+    _name: IgnoredAny,
+    pub package: String,
+    #[serde(default)]
+    pub base_path: String,
+    pub default_auth: Option<String>,
+    pub docs: Option<String>,
+    #[serde(default)]
+    pub endpoints: OrderedMap<EndpointDefinition>,
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct EndpointDefinition {
+    pub http: String,
+    pub auth: Option<String>,
+    #[serde(default)]
+    pub args: OrderedMap<ArgumentDefinition>,
+    pub returns: Option<String>,
+    pub docs: Option<String>,
+    pub deprecated: Option<String>,
+    #[serde(default)]
+    pub markers: Vec<String>,
+    #[serde(default, rename = "tags")]
+    _tags: IgnoredAny,
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(untagged)]
+pub enum ArgumentDefinition {
+    Simple(String),
+    Full(FullArgumentDefinition),
+}
+
+#[derive(DeriveDeserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct FullArgumentDefinition {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub param_type: Option<String>,
+    pub param_id: Option<String>,
+    pub docs: Option<String>,
+    #[serde(default)]
+    pub markers: Vec<String>,
+    #[serde(default, rename = "tags")]
+    _tags: IgnoredAny,
+    #[serde(default, rename = "safety")]
+    _safety: IgnoredAny,
+}
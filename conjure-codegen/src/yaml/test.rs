@@ -0,0 +1,207 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::types::{ConjureDefinition, PrimitiveType, Type, TypeDefinition};
+use crate::yaml::{compile_yaml, Compiler};
+use failure::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+fn compile(yaml: &str) -> Result<ConjureDefinition, Error> {
+    let mut compiler = Compiler::new();
+    compiler.load_source(PathBuf::from("test.yml"), yaml)?;
+    compiler.compile()
+}
+
+fn compile_err(yaml: &str) -> String {
+    let e = compile(yaml).err().unwrap();
+    e.iter_chain()
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>()
+        .join(": ")
+}
+
+#[test]
+fn matches_java_compiler() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../conjure-test");
+
+    let actual = compile_yaml(&[dir.join("test.yml")]).unwrap();
+
+    let ir = fs::read_to_string(dir.join("test-ir.json")).unwrap();
+    let expected = conjure_serde::json::client_from_str::<ConjureDefinition>(&ir).unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn unknown_type() {
+    let e = compile_err(
+        r#"
+types:
+  definitions:
+    default-package: com.foo
+    objects:
+      Foo:
+        fields:
+          bar: optional<Bar>
+"#,
+    );
+    assert!(e.contains("unknown type `Bar`"), "{}", e);
+}
+
+#[test]
+fn imported_external_type() {
+    let dir = std::env::temp_dir().join(format!("conjure-yaml-test-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("imported.yml"),
+        r#"
+types:
+  imports:
+    ExternalLong:
+      base-type: safelong
+      external:
+        java: java.lang.Long
+"#,
+    )
+    .unwrap();
+    fs::write(
+        dir.join("main.yml"),
+        r#"
+types:
+  conjure-imports:
+    imported: imported.yml
+  definitions:
+    default-package: com.foo
+    objects:
+      Foo:
+        fields:
+          bar: imported.ExternalLong
+"#,
+    )
+    .unwrap();
+
+    let definition = compile_yaml(&[dir.join("main.yml")]);
+    fs::remove_dir_all(&dir).unwrap();
+
+    let definition = definition.unwrap();
+    let object = match &definition.types()[0] {
+        TypeDefinition::Object(object) => object,
+        def => panic!("unexpected type definition {:?}", def),
+    };
+    match object.fields()[0].type_() {
+        Type::External(external) => {
+            assert_eq!(external.external_reference().package(), "java.lang");
+            assert_eq!(external.external_reference().name(), "Long");
+            assert_eq!(
+                *external.fallback(),
+                Type::Primitive(PrimitiveType::Safelong)
+            );
+        }
+        type_ => panic!("unexpected type {:?}", type_),
+    }
+}
+
+#[test]
+fn missing_package() {
+    let e = compile_err(
+        r#"
+types:
+  definitions:
+    objects:
+      Foo:
+        alias: string
+"#,
+    );
+    assert!(e.contains("no default-package"), "{}", e);
+}
+
+#[test]
+fn path_param_mismatch() {
+    let e = compile_err(
+        r#"
+services:
+  FooService:
+    package: com.foo
+    endpoints:
+      foo:
+        http: GET /foo/{bar}
+"#,
+    );
+    assert!(
+        e.contains("path parameter `bar` has no corresponding argument"),
+        "{}",
+        e
+    );
+
+    let e = compile_err(
+        r#"
+services:
+  FooService:
+    package: com.foo
+    endpoints:
+      foo:
+        http: GET /foo
+        args:
+          bar:
+            type: string
+            param-type: path
+"#,
+    );
+    assert!(e.contains("not present in the endpoint's path"), "{}", e);
+}
+
+#[test]
+fn multiple_bodies() {
+    let e = compile_err(
+        r#"
+services:
+  FooService:
+    package: com.foo
+    endpoints:
+      foo:
+        http: POST /foo
+        args:
+          bar: string
+          baz: string
+"#,
+    );
+    assert!(e.contains("multiple body arguments"), "{}", e);
+}
+
+#[test]
+fn default_auth() {
+    let defs = compile(
+        r#"
+services:
+  FooService:
+    package: com.foo
+    base-path: /foo
+    default-auth: header
+    endpoints:
+      authed:
+        http: GET /
+      unauthed:
+        http: GET /unauthed
+        auth: none
+"#,
+    )
+    .unwrap();
+
+    let endpoints = defs.services()[0].endpoints();
+    assert_eq!(endpoints[0].http_path().0, "/foo");
+    assert!(endpoints[0].auth().is_some());
+    assert_eq!(endpoints[1].http_path().0, "/foo/unauthed");
+    assert!(endpoints[1].auth().is_none());
+}
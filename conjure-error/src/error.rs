@@ -22,7 +22,7 @@ use std::fmt;
 use std::ops::Index;
 use std::time::Duration;
 
-use crate::{ErrorType, Internal, SerializableError};
+use crate::{ErrorType, FromSerializableError, Internal, SerializableError};
 
 /// Information about a throttle error.
 #[derive(Debug)]
@@ -274,6 +274,19 @@ impl Error {
         &self.0.kind
     }
 
+    /// Decodes the error's serialized service error information into a typed error.
+    ///
+    /// Returns `None` if the error is not a service error, or if it does not correspond to the type `T`.
+    pub fn service_error<T>(&self) -> Option<T>
+    where
+        T: FromSerializableError,
+    {
+        match &self.0.kind {
+            ErrorKind::Service(error) => T::from_serializable_error(error),
+            _ => None,
+        }
+    }
+
     /// Adds a new safe parameter to the error.
     ///
     /// # Panics
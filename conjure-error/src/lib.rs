@@ -111,6 +111,17 @@ where
     }
 }
 
+/// A trait implemented by Conjure error types which can be decoded from their serialized form.
+///
+/// Parameters are transmitted as strings, so they are parsed via `FromPlain`. Parameters which do not have a plain
+/// representation are never included in a serialized error, so they decode to their default value if they have one.
+pub trait FromSerializableError: Sized {
+    /// Attempts to decode the error from a `SerializableError`.
+    ///
+    /// Returns `None` if the error's name does not match, or if a parameter is missing or fails to parse.
+    fn from_serializable_error(error: &SerializableError) -> Option<Self>;
+}
+
 /// An `ErrorType` which wraps another and overrides its instance ID.
 pub struct WithInstanceId<T> {
     error: T,
@@ -29,6 +29,11 @@ impl Builder {
     pub fn build(&self) -> Conflict {
         Conflict {}
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<Conflict, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<Conflict> for Builder {
     #[inline]
@@ -119,3 +124,11 @@ impl conjure_error::ErrorType for Conflict {
         &[]
     }
 }
+impl conjure_error::FromSerializableError for Conflict {
+    fn from_serializable_error(error: &conjure_error::SerializableError) -> Option<Conflict> {
+        if error.error_name() != "Default:Conflict" {
+            return None;
+        }
+        Some(Conflict {})
+    }
+}
@@ -29,6 +29,11 @@ impl Builder {
     pub fn build(&self) -> FailedPrecondition {
         FailedPrecondition {}
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<FailedPrecondition, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<FailedPrecondition> for Builder {
     #[inline]
@@ -119,3 +124,13 @@ impl conjure_error::ErrorType for FailedPrecondition {
         &[]
     }
 }
+impl conjure_error::FromSerializableError for FailedPrecondition {
+    fn from_serializable_error(
+        error: &conjure_error::SerializableError,
+    ) -> Option<FailedPrecondition> {
+        if error.error_name() != "Default:FailedPrecondition" {
+            return None;
+        }
+        Some(FailedPrecondition {})
+    }
+}
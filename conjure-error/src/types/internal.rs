@@ -29,6 +29,11 @@ impl Builder {
     pub fn build(&self) -> Internal {
         Internal {}
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<Internal, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<Internal> for Builder {
     #[inline]
@@ -119,3 +124,11 @@ impl conjure_error::ErrorType for Internal {
         &[]
     }
 }
+impl conjure_error::FromSerializableError for Internal {
+    fn from_serializable_error(error: &conjure_error::SerializableError) -> Option<Internal> {
+        if error.error_name() != "Default:Internal" {
+            return None;
+        }
+        Some(Internal {})
+    }
+}
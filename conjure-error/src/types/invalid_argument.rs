@@ -29,6 +29,11 @@ impl Builder {
     pub fn build(&self) -> InvalidArgument {
         InvalidArgument {}
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<InvalidArgument, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<InvalidArgument> for Builder {
     #[inline]
@@ -119,3 +124,13 @@ impl conjure_error::ErrorType for InvalidArgument {
         &[]
     }
 }
+impl conjure_error::FromSerializableError for InvalidArgument {
+    fn from_serializable_error(
+        error: &conjure_error::SerializableError,
+    ) -> Option<InvalidArgument> {
+        if error.error_name() != "Default:InvalidArgument" {
+            return None;
+        }
+        Some(InvalidArgument {})
+    }
+}
@@ -29,6 +29,11 @@ impl Builder {
     pub fn build(&self) -> NotFound {
         NotFound {}
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<NotFound, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<NotFound> for Builder {
     #[inline]
@@ -119,3 +124,11 @@ impl conjure_error::ErrorType for NotFound {
         &[]
     }
 }
+impl conjure_error::FromSerializableError for NotFound {
+    fn from_serializable_error(error: &conjure_error::SerializableError) -> Option<NotFound> {
+        if error.error_name() != "Default:NotFound" {
+            return None;
+        }
+        Some(NotFound {})
+    }
+}
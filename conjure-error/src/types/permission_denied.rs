@@ -29,6 +29,11 @@ impl Builder {
     pub fn build(&self) -> PermissionDenied {
         PermissionDenied {}
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<PermissionDenied, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<PermissionDenied> for Builder {
     #[inline]
@@ -119,3 +124,13 @@ impl conjure_error::ErrorType for PermissionDenied {
         &[]
     }
 }
+impl conjure_error::FromSerializableError for PermissionDenied {
+    fn from_serializable_error(
+        error: &conjure_error::SerializableError,
+    ) -> Option<PermissionDenied> {
+        if error.error_name() != "Default:PermissionDenied" {
+            return None;
+        }
+        Some(PermissionDenied {})
+    }
+}
@@ -29,6 +29,11 @@ impl Builder {
     pub fn build(&self) -> RequestEntityTooLarge {
         RequestEntityTooLarge {}
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<RequestEntityTooLarge, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<RequestEntityTooLarge> for Builder {
     #[inline]
@@ -119,3 +124,13 @@ impl conjure_error::ErrorType for RequestEntityTooLarge {
         &[]
     }
 }
+impl conjure_error::FromSerializableError for RequestEntityTooLarge {
+    fn from_serializable_error(
+        error: &conjure_error::SerializableError,
+    ) -> Option<RequestEntityTooLarge> {
+        if error.error_name() != "Default:RequestEntityTooLarge" {
+            return None;
+        }
+        Some(RequestEntityTooLarge {})
+    }
+}
@@ -133,6 +133,27 @@ impl Builder {
             parameters: self.parameters.clone(),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<SerializableError, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.error_code.is_none() {
+            missing_fields.push("error_code");
+        }
+        if self.error_name.is_none() {
+            missing_fields.push("error_name");
+        }
+        if self.error_instance_id.is_none() {
+            missing_fields.push("error_instance_id");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "SerializableError",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<SerializableError> for Builder {
     #[inline]
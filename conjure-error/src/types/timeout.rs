@@ -29,6 +29,11 @@ impl Builder {
     pub fn build(&self) -> Timeout {
         Timeout {}
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<Timeout, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<Timeout> for Builder {
     #[inline]
@@ -119,3 +124,11 @@ impl conjure_error::ErrorType for Timeout {
         &[]
     }
 }
+impl conjure_error::FromSerializableError for Timeout {
+    fn from_serializable_error(error: &conjure_error::SerializableError) -> Option<Timeout> {
+        if error.error_name() != "Default:Timeout" {
+            return None;
+        }
+        Some(Timeout {})
+    }
+}
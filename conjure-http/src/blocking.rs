@@ -0,0 +1,309 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! A blocking client adapter over an async client.
+//!
+//! The `BlockingClient` implements `Client` on top of any `AsyncClient`, so that both the blocking and async generated
+//! clients can share a single transport implementation.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use conjure_http::blocking::{BlockingClient, Executor};
+//! use std::future::Future;
+//!
+//! #[derive(Clone)]
+//! struct FuturesExecutor;
+//!
+//! impl Executor for FuturesExecutor {
+//!     fn block_on<F>(&self, future: F) -> F::Output
+//!     where
+//!         F: Future,
+//!     {
+//!         futures::executor::block_on(future)
+//!     }
+//! }
+//!
+//! let client = BlockingClient::new(HyperClient::new(uri), FuturesExecutor);
+//! let client = MyServiceClient::new(client);
+//!
+//! let response = client.my_endpoint("hello world")?;
+//! ```
+use crate::client::{
+    Accept, AsyncClient, AsyncRequestBody, AsyncVisitRequestBody, AsyncWriteBody, Client, Endpoint,
+    RequestBody, VisitRequestBody, VisitResponse, WriteBody,
+};
+use crate::value::Value;
+use crate::{PathParams, QueryParams};
+use async_trait::async_trait;
+use conjure_error::Error;
+use http::HeaderMap;
+use serde::{Deserializer, Serialize};
+use std::cmp;
+use std::error;
+use std::fmt;
+use std::future::Future;
+use std::io::{self, Read};
+use std::pin::Pin;
+
+/// An executor which can run futures to completion from synchronous code.
+///
+/// This is typically implemented by a handle to the async runtime the inner client's IO is driven by.
+pub trait Executor {
+    /// Runs a future to completion, blocking the current thread until it does.
+    fn block_on<F>(&self, future: F) -> F::Output
+    where
+        F: Future;
+}
+
+/// A trait implemented by async binary request body writers which the `BlockingClient` can write to.
+#[async_trait]
+pub trait AsyncWriteBytes {
+    /// Writes the entire buffer.
+    async fn write_bytes(self: Pin<&mut Self>, buf: &[u8]) -> Result<(), Error>;
+}
+
+#[async_trait]
+impl AsyncWriteBytes for Vec<u8> {
+    async fn write_bytes(mut self: Pin<&mut Self>, buf: &[u8]) -> Result<(), Error> {
+        self.extend_from_slice(buf);
+        Ok(())
+    }
+}
+
+/// A trait implemented by async binary response bodies which the `BlockingClient` can read from.
+#[async_trait]
+pub trait AsyncReadBytes {
+    /// Reads bytes into the buffer, returning the number of bytes read.
+    ///
+    /// A return value of 0 indicates the end of the body.
+    async fn read_bytes(self: Pin<&mut Self>, buf: &mut [u8]) -> io::Result<usize>;
+}
+
+#[async_trait]
+impl AsyncReadBytes for Vec<u8> {
+    async fn read_bytes(mut self: Pin<&mut Self>, buf: &mut [u8]) -> io::Result<usize> {
+        let len = cmp::min(buf.len(), self.len());
+        buf[..len].copy_from_slice(&self[..len]);
+        self.drain(..len);
+        Ok(len)
+    }
+}
+
+/// A blocking client which makes requests with an async client.
+///
+/// Each request is driven to completion by the `Executor`, and binary response bodies are read by blocking on the
+/// `Executor` as well. The executor must not be one which is already driving the calling thread.
+///
+/// Serializable request bodies are captured in memory so that they can be handed to the async client, and responses are
+/// passed directly to the caller's response visitor. The async client's binary writer must implement
+/// `AsyncWriteBytes`, and its binary response body must implement `AsyncReadBytes`. conjure-hyper's body types
+/// implement both, so a `HyperClient` can be wrapped directly.
+///
+/// Binary request bodies are written in full into an in-memory buffer before the request is made, so they are not
+/// streamed and their entire contents must fit in memory. Binary response bodies are streamed.
+#[derive(Clone, Debug)]
+pub struct BlockingClient<C, E> {
+    inner: C,
+    executor: E,
+}
+
+impl<C, E> BlockingClient<C, E> {
+    /// Creates a new client wrapping an async client and the executor used to drive it.
+    pub fn new(inner: C, executor: E) -> BlockingClient<C, E> {
+        BlockingClient { inner, executor }
+    }
+
+    /// Returns a shared reference to the inner client.
+    pub fn get_ref(&self) -> &C {
+        &self.inner
+    }
+
+    /// Returns a shared reference to the executor.
+    pub fn executor(&self) -> &E {
+        &self.executor
+    }
+}
+
+impl<C, E> Client for BlockingClient<C, E>
+where
+    C: AsyncClient,
+    C::BinaryWriter: AsyncWriteBytes + Send,
+    E: Executor + Clone + Send,
+{
+    type BinaryWriter = Vec<u8>;
+    type BinaryBody = BlockingBody<C::BinaryBody, E>;
+
+    fn request<'a, T, U>(
+        &self,
+        endpoint: &'static Endpoint,
+        path_params: PathParams,
+        query_params: QueryParams,
+        headers: HeaderMap,
+        body: T,
+        response_visitor: U,
+    ) -> Result<U::Output, Error>
+    where
+        T: RequestBody<'a, Vec<u8>>,
+        U: VisitResponse<Self::BinaryBody> + Send,
+    {
+        let body = body.accept(BufferBodyVisitor)?;
+        let response_visitor = BlockingResponseVisitor {
+            visitor: response_visitor,
+            executor: self.executor.clone(),
+        };
+
+        self.executor.block_on(self.inner.request(
+            endpoint,
+            path_params,
+            query_params,
+            headers,
+            body,
+            response_visitor,
+        ))
+    }
+}
+
+/// A blocking binary response body, read from an async response body.
+pub struct BlockingBody<B, E> {
+    body: Pin<Box<B>>,
+    executor: E,
+}
+
+impl<B, E> fmt::Debug for BlockingBody<B, E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("BlockingBody").finish()
+    }
+}
+
+impl<B, E> BlockingBody<B, E> {
+    /// Returns the inner async body.
+    pub fn into_inner(self) -> Pin<Box<B>> {
+        self.body
+    }
+}
+
+impl<B, E> Read for BlockingBody<B, E>
+where
+    B: AsyncReadBytes,
+    E: Executor,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.executor.block_on(self.body.as_mut().read_bytes(buf))
+    }
+}
+
+// a request body which owns its contents so it can be handed to the async client
+enum Body {
+    Empty,
+    Serializable(Value),
+    Binary(Vec<u8>),
+}
+
+impl<'a, W> AsyncRequestBody<'a, W> for Body
+where
+    W: AsyncWriteBytes + Send,
+{
+    fn accept<V>(self, visitor: V) -> V::Output
+    where
+        V: AsyncVisitRequestBody<'a, W>,
+    {
+        match self {
+            Body::Empty => visitor.visit_empty(),
+            Body::Serializable(body) => visitor.visit_serializable(body),
+            Body::Binary(body) => visitor.visit_binary(BufferedBody(body)),
+        }
+    }
+}
+
+struct BufferBodyVisitor;
+
+impl<'a> VisitRequestBody<'a, Vec<u8>> for BufferBodyVisitor {
+    type Output = Result<Body, Error>;
+
+    fn visit_empty(self) -> Self::Output {
+        Ok(Body::Empty)
+    }
+
+    fn visit_serializable<T>(self, body: T) -> Self::Output
+    where
+        T: Serialize + 'a,
+    {
+        let body = Value::new(body).map_err(Error::internal)?;
+        Ok(Body::Serializable(body))
+    }
+
+    fn visit_binary<T>(self, mut body: T) -> Self::Output
+    where
+        T: WriteBody<Vec<u8>> + 'a,
+    {
+        let mut buf = vec![];
+        body.write_body(&mut buf)?;
+        Ok(Body::Binary(buf))
+    }
+}
+
+struct BufferedBody(Vec<u8>);
+
+#[async_trait]
+impl<W> AsyncWriteBody<W> for BufferedBody
+where
+    W: AsyncWriteBytes + Send,
+{
+    async fn write_body(self: Pin<&mut Self>, w: Pin<&mut W>) -> Result<(), Error> {
+        w.write_bytes(&self.0).await
+    }
+
+    async fn reset(self: Pin<&mut Self>) -> bool
+    where
+        W: 'async_trait,
+    {
+        true
+    }
+}
+
+// a response visitor which wraps binary response bodies so they can be read from blocking code
+struct BlockingResponseVisitor<U, E> {
+    visitor: U,
+    executor: E,
+}
+
+impl<B, U, E> VisitResponse<B> for BlockingResponseVisitor<U, E>
+where
+    U: VisitResponse<BlockingBody<B, E>>,
+{
+    type Output = U::Output;
+
+    fn accept(&self) -> Accept {
+        self.visitor.accept()
+    }
+
+    fn visit_empty(self) -> Result<U::Output, Error> {
+        self.visitor.visit_empty()
+    }
+
+    fn visit_serializable<'de, D>(self, deserializer: D) -> Result<U::Output, Error>
+    where
+        D: Deserializer<'de>,
+        D::Error: Into<Box<dyn error::Error + Sync + Send>>,
+    {
+        self.visitor.visit_serializable(deserializer)
+    }
+
+    fn visit_binary(self, body: B) -> Result<U::Output, Error> {
+        self.visitor.visit_binary(BlockingBody {
+            body: Box::pin(body),
+            executor: self.executor,
+        })
+    }
+}
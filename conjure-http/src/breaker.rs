@@ -0,0 +1,479 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! A client decorator which stops sending requests to failing endpoints.
+//!
+//! The `CircuitBreakingClient` wraps another `Client` or `AsyncClient`, and tracks the rate at which requests to each
+//! endpoint fail. Once it is too high, the endpoint's circuit opens and requests fail immediately rather than adding
+//! load to a struggling service.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use conjure_http::breaker::CircuitBreakingClient;
+//!
+//! let client = CircuitBreakingClient::new(HyperClient::new(uri))
+//!     .target("my-service")
+//!     .on_transition(|transition| {
+//!         log::info!(
+//!             "circuit breaker transitioned: endpoint={}, state={:?}",
+//!             transition.endpoint().name(),
+//!             transition.to(),
+//!         )
+//!     });
+//! let client = MyServiceClient::new(client);
+//!
+//! let response = client.my_endpoint("hello world")?;
+//! ```
+use crate::client::{AsyncClient, AsyncRequestBody, Client, Endpoint, RequestBody, VisitResponse};
+use crate::{PathParams, QueryParams};
+use conjure_error::{Error, ErrorCode, ErrorKind};
+use http::{HeaderMap, Method};
+use std::cmp;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// The state of an endpoint's circuit.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests are sent normally.
+    Closed,
+    /// Requests fail immediately.
+    Open,
+    /// A single trial request is sent to determine if the circuit should close again.
+    HalfOpen,
+}
+
+/// A change in the state of an endpoint's circuit.
+#[derive(Debug)]
+pub struct Transition<'a> {
+    target: Option<&'a str>,
+    endpoint: &'static Endpoint,
+    from: CircuitState,
+    to: CircuitState,
+}
+
+impl<'a> Transition<'a> {
+    /// Returns the name of the client's target, if one was configured.
+    pub fn target(&self) -> Option<&'a str> {
+        self.target
+    }
+
+    /// Returns the endpoint whose circuit changed state.
+    pub fn endpoint(&self) -> &'static Endpoint {
+        self.endpoint
+    }
+
+    /// Returns the previous state of the circuit.
+    pub fn from(&self) -> CircuitState {
+        self.from
+    }
+
+    /// Returns the new state of the circuit.
+    pub fn to(&self) -> CircuitState {
+        self.to
+    }
+}
+
+/// A client which stops sending requests to endpoints which are failing.
+///
+/// Each endpoint, identified by its HTTP method and path template, has its own circuit. The outcomes of the most recent
+/// requests to a closed circuit are tracked, and it opens once the fraction which failed reaches the failure threshold.
+/// Only `Unavailable` errors and service errors with the `INTERNAL` error code count as failures.
+///
+/// While a circuit is open, requests fail immediately with an `Unavailable` error. After the cool-down period has
+/// elapsed, the circuit half-opens and a single trial request is sent. The circuit closes if it succeeds, and opens
+/// again if it fails.
+///
+/// Each client tracks a single target, and clones of the client share their circuits. Setting the target of a clone
+/// with `target` gives it its own circuits.
+#[derive(Clone)]
+pub struct CircuitBreakingClient<C> {
+    inner: C,
+    target: Option<String>,
+    failure_threshold: f64,
+    minimum_requests: usize,
+    window_size: usize,
+    cool_down: Duration,
+    on_transition: Option<Arc<OnTransition>>,
+    breakers: Arc<Mutex<Breakers>>,
+}
+
+type OnTransition = dyn Fn(&Transition<'_>) + Sync + Send;
+
+type Breakers = HashMap<(Method, &'static str), Breaker>;
+
+impl<C> fmt::Debug for CircuitBreakingClient<C>
+where
+    C: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("CircuitBreakingClient")
+            .field("inner", &self.inner)
+            .field("target", &self.target)
+            .field("failure_threshold", &self.failure_threshold)
+            .field("minimum_requests", &self.minimum_requests)
+            .field("window_size", &self.window_size)
+            .field("cool_down", &self.cool_down)
+            .finish()
+    }
+}
+
+impl<C> CircuitBreakingClient<C> {
+    /// Creates a new client wrapping another.
+    ///
+    /// By default, a circuit opens once half of its last 20 requests have failed, as long as at least 10 have been
+    /// made, and half-opens after 10 seconds.
+    pub fn new(inner: C) -> CircuitBreakingClient<C> {
+        CircuitBreakingClient {
+            inner,
+            target: None,
+            failure_threshold: 0.5,
+            minimum_requests: 10,
+            window_size: 20,
+            cool_down: Duration::from_secs(10),
+            on_transition: None,
+            breakers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Sets the name of the client's target, which is included in transitions and errors.
+    ///
+    /// The client's circuits are reset, so they are not shared with any client it was cloned from.
+    pub fn target(mut self, target: &str) -> CircuitBreakingClient<C> {
+        self.target = Some(target.to_string());
+        self.breakers = Arc::new(Mutex::new(HashMap::new()));
+        self
+    }
+
+    /// Sets the fraction of tracked requests which must fail for a circuit to open.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the threshold is not greater than 0 and at most 1.
+    pub fn failure_threshold(mut self, failure_threshold: f64) -> CircuitBreakingClient<C> {
+        assert!(
+            failure_threshold > 0. && failure_threshold <= 1.,
+            "failure threshold must be greater than 0 and at most 1"
+        );
+        self.failure_threshold = failure_threshold;
+        self
+    }
+
+    /// Sets the number of requests which must be tracked before a circuit can open.
+    ///
+    /// A circuit never tracks more requests than the window size, so a larger value is treated as the window size.
+    pub fn minimum_requests(mut self, minimum_requests: usize) -> CircuitBreakingClient<C> {
+        self.minimum_requests = minimum_requests;
+        self
+    }
+
+    /// Sets the number of most recent requests tracked for each closed circuit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the window size is 0.
+    pub fn window_size(mut self, window_size: usize) -> CircuitBreakingClient<C> {
+        assert!(window_size > 0, "window size must be greater than 0");
+        self.window_size = window_size;
+        self
+    }
+
+    /// Sets the time a circuit stays open before half-opening.
+    pub fn cool_down(mut self, cool_down: Duration) -> CircuitBreakingClient<C> {
+        self.cool_down = cool_down;
+        self
+    }
+
+    /// Sets a callback invoked whenever a circuit changes state.
+    ///
+    /// The callback is invoked synchronously, on the thread making the request which triggered the transition.
+    pub fn on_transition<F>(mut self, on_transition: F) -> CircuitBreakingClient<C>
+    where
+        F: Fn(&Transition<'_>) + 'static + Sync + Send,
+    {
+        self.on_transition = Some(Arc::new(on_transition));
+        self
+    }
+
+    /// Returns a shared reference to the inner client.
+    pub fn get_ref(&self) -> &C {
+        &self.inner
+    }
+
+    /// Returns the current state of an endpoint's circuit.
+    pub fn state(&self, endpoint: &Endpoint) -> CircuitState {
+        match self
+            .breakers
+            .lock()
+            .unwrap()
+            .get(&(endpoint.method().clone(), endpoint.path()))
+        {
+            Some(breaker) => breaker.state,
+            None => CircuitState::Closed,
+        }
+    }
+
+    fn start(&self, endpoint: &'static Endpoint) -> Result<Call<'_, C>, Error> {
+        let mut breakers = self.breakers.lock().unwrap();
+        let breaker = breakers
+            .entry((endpoint.method().clone(), endpoint.path()))
+            .or_insert_with(Breaker::new);
+
+        let mut transition = None;
+        if breaker.state == CircuitState::Open && breaker.opened_at.elapsed() >= self.cool_down {
+            transition = Some(breaker.transition(CircuitState::HalfOpen));
+        }
+
+        let trial = match breaker.state {
+            CircuitState::Closed => Some(false),
+            CircuitState::HalfOpen if !breaker.trial_in_flight => {
+                breaker.trial_in_flight = true;
+                Some(true)
+            }
+            _ => None,
+        };
+        drop(breakers);
+
+        self.notify(endpoint, transition);
+
+        match trial {
+            Some(trial) => Ok(Call {
+                client: self,
+                endpoint,
+                trial,
+                finished: false,
+            }),
+            None => {
+                let mut error = Error::unavailable_safe("circuit breaker open")
+                    .with_safe_param("service", endpoint.service())
+                    .with_safe_param("endpoint", endpoint.name());
+                if let Some(target) = &self.target {
+                    error = error.with_safe_param("target", target);
+                }
+                Err(error)
+            }
+        }
+    }
+
+    fn notify(
+        &self,
+        endpoint: &'static Endpoint,
+        transition: Option<(CircuitState, CircuitState)>,
+    ) {
+        if let (Some(on_transition), Some((from, to))) = (&self.on_transition, transition) {
+            on_transition(&Transition {
+                target: self.target.as_deref(),
+                endpoint,
+                from,
+                to,
+            });
+        }
+    }
+}
+
+impl<C> Client for CircuitBreakingClient<C>
+where
+    C: Client,
+{
+    type BinaryWriter = C::BinaryWriter;
+    type BinaryBody = C::BinaryBody;
+
+    fn request<'a, T, U>(
+        &self,
+        endpoint: &'static Endpoint,
+        path_params: PathParams,
+        query_params: QueryParams,
+        headers: HeaderMap,
+        body: T,
+        response_visitor: U,
+    ) -> Result<U::Output, Error>
+    where
+        T: RequestBody<'a, C::BinaryWriter>,
+        U: VisitResponse<C::BinaryBody> + Send,
+    {
+        let call = self.start(endpoint)?;
+        let result = self.inner.request(
+            endpoint,
+            path_params,
+            query_params,
+            headers,
+            body,
+            response_visitor,
+        );
+        call.finish(&result);
+        result
+    }
+}
+
+impl<C> AsyncClient for CircuitBreakingClient<C>
+where
+    C: AsyncClient + Sync + Send,
+{
+    type BinaryWriter = C::BinaryWriter;
+    type BinaryBody = C::BinaryBody;
+
+    fn request<'a, T, U>(
+        &'a self,
+        endpoint: &'static Endpoint,
+        path_params: PathParams,
+        query_params: QueryParams,
+        headers: HeaderMap,
+        body: T,
+        response_visitor: U,
+    ) -> Pin<Box<dyn Future<Output = Result<U::Output, Error>> + Send + 'a>>
+    where
+        T: AsyncRequestBody<'a, C::BinaryWriter> + Send + 'a,
+        U: VisitResponse<C::BinaryBody> + Send + 'a,
+    {
+        Box::pin(async move {
+            let call = self.start(endpoint)?;
+            let result = self
+                .inner
+                .request(
+                    endpoint,
+                    path_params,
+                    query_params,
+                    headers,
+                    body,
+                    response_visitor,
+                )
+                .await;
+            call.finish(&result);
+            result
+        })
+    }
+}
+
+// the circuit of a single endpoint
+struct Breaker {
+    state: CircuitState,
+    // the outcomes of the most recent requests while closed, where `true` is a failure
+    outcomes: VecDeque<bool>,
+    failures: usize,
+    opened_at: Instant,
+    trial_in_flight: bool,
+}
+
+impl Breaker {
+    fn new() -> Breaker {
+        Breaker {
+            state: CircuitState::Closed,
+            outcomes: VecDeque::new(),
+            failures: 0,
+            opened_at: Instant::now(),
+            trial_in_flight: false,
+        }
+    }
+
+    fn transition(&mut self, to: CircuitState) -> (CircuitState, CircuitState) {
+        let from = self.state;
+        self.state = to;
+        self.outcomes.clear();
+        self.failures = 0;
+        self.trial_in_flight = false;
+        if to == CircuitState::Open {
+            self.opened_at = Instant::now();
+        }
+        (from, to)
+    }
+}
+
+// a request allowed through a circuit
+struct Call<'a, C> {
+    client: &'a CircuitBreakingClient<C>,
+    endpoint: &'static Endpoint,
+    trial: bool,
+    finished: bool,
+}
+
+impl<C> Call<'_, C> {
+    fn finish<T>(mut self, result: &Result<T, Error>) {
+        self.finished = true;
+        let failure = match result {
+            Ok(_) => false,
+            Err(error) => is_failure(error),
+        };
+
+        let mut breakers = self.client.breakers.lock().unwrap();
+        let breaker =
+            match breakers.get_mut(&(self.endpoint.method().clone(), self.endpoint.path())) {
+                Some(breaker) => breaker,
+                None => return,
+            };
+
+        let transition = match breaker.state {
+            CircuitState::HalfOpen if self.trial => {
+                if failure {
+                    Some(breaker.transition(CircuitState::Open))
+                } else {
+                    Some(breaker.transition(CircuitState::Closed))
+                }
+            }
+            CircuitState::Closed => {
+                breaker.outcomes.push_back(failure);
+                if failure {
+                    breaker.failures += 1;
+                }
+                while breaker.outcomes.len() > self.client.window_size {
+                    if breaker.outcomes.pop_front() == Some(true) {
+                        breaker.failures -= 1;
+                    }
+                }
+
+                let requests = breaker.outcomes.len();
+                if requests > 0
+                    && requests >= cmp::min(self.client.minimum_requests, self.client.window_size)
+                    && breaker.failures as f64 / requests as f64 >= self.client.failure_threshold
+                {
+                    Some(breaker.transition(CircuitState::Open))
+                } else {
+                    None
+                }
+            }
+            // a request which started before the circuit last changed state says nothing about its current state
+            _ => None,
+        };
+        drop(breakers);
+
+        self.client.notify(self.endpoint, transition);
+    }
+}
+
+impl<C> Drop for Call<'_, C> {
+    fn drop(&mut self) {
+        // a cancelled trial request frees the circuit up to send another
+        if self.trial && !self.finished {
+            let mut breakers = self.client.breakers.lock().unwrap();
+            if let Some(breaker) =
+                breakers.get_mut(&(self.endpoint.method().clone(), self.endpoint.path()))
+            {
+                if breaker.state == CircuitState::HalfOpen {
+                    breaker.trial_in_flight = false;
+                }
+            }
+        }
+    }
+}
+
+fn is_failure(error: &Error) -> bool {
+    match error.kind() {
+        ErrorKind::Unavailable(_) => true,
+        ErrorKind::Service(e) => *e.error_code() == ErrorCode::Internal,
+        _ => false,
+    }
+}
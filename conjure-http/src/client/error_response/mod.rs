@@ -0,0 +1,87 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Decoding of error responses into errors.
+use conjure_error::{Error, SerializableError};
+use conjure_serde::json;
+use http::header::RETRY_AFTER;
+use http::{HeaderMap, StatusCode};
+use std::error;
+use std::fmt;
+use std::time::Duration;
+
+#[cfg(test)]
+mod test;
+
+// the maximum number of bytes of an unrecognized body to include in an error
+const MAX_BODY_PARAM_LEN: usize = 1024;
+
+/// Decodes a non-2xx HTTP response into the error a client should return for it.
+///
+/// * `429 Too Many Requests` responses produce a throttle error, honoring the `Retry-After` header if present.
+/// * `503 Service Unavailable` responses produce an unavailable error.
+/// * Responses with a `SerializableError` body produce a propagated service error.
+/// * Any other response produces an internal error, with the body included as an unsafe `body` parameter, truncated
+///   to 1KB.
+///
+/// All errors include the response's status code as a safe `status` parameter.
+pub fn decode_error(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Error {
+    let cause = RemoteError { status };
+
+    let error = match status {
+        StatusCode::TOO_MANY_REQUESTS => {
+            let retry_after = headers
+                .get(RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.trim().parse().ok())
+                .map(Duration::from_secs);
+            match retry_after {
+                Some(duration) => Error::throttle_for_safe(cause, duration),
+                None => Error::throttle_safe(cause),
+            }
+        }
+        StatusCode::SERVICE_UNAVAILABLE => Error::unavailable_safe(cause),
+        _ => match json::client_from_slice::<SerializableError>(body) {
+            Ok(error) => Error::propagated_service_safe(cause, error),
+            Err(_) => Error::internal_safe(cause).with_unsafe_param("body", truncate(body)),
+        },
+    };
+
+    error.with_safe_param("status", status.as_u16())
+}
+
+fn truncate(body: &[u8]) -> String {
+    let body = String::from_utf8_lossy(body);
+    if body.len() <= MAX_BODY_PARAM_LEN {
+        return body.into_owned();
+    }
+
+    let mut end = MAX_BODY_PARAM_LEN;
+    while !body.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}...", &body[..end])
+}
+
+#[derive(Debug)]
+struct RemoteError {
+    status: StatusCode,
+}
+
+impl fmt::Display for RemoteError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "remote error: {}", self.status)
+    }
+}
+
+impl error::Error for RemoteError {}
@@ -0,0 +1,103 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::client::decode_error;
+use conjure_error::{ErrorCode, ErrorKind, SerializableError};
+use conjure_object::{Any, Uuid};
+use conjure_serde::json;
+use http::header::RETRY_AFTER;
+use http::{HeaderMap, HeaderValue, StatusCode};
+use std::time::Duration;
+
+fn param(params: conjure_error::Params<'_>, key: &str) -> Option<Any> {
+    params
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v.clone())
+}
+
+#[test]
+fn service_error() {
+    let remote = SerializableError::builder()
+        .error_code(ErrorCode::NotFound)
+        .error_name("Test:NotFound")
+        .error_instance_id(Uuid::new_v4())
+        .insert_parameters("foo", "bar")
+        .build();
+    let body = json::to_vec(&remote).unwrap();
+
+    let error = decode_error(StatusCode::NOT_FOUND, &HeaderMap::new(), &body);
+    match error.kind() {
+        ErrorKind::Service(e) => assert_eq!(*e, remote),
+        kind => panic!("unexpected error kind {:?}", kind),
+    }
+    assert_eq!(
+        param(error.safe_params(), "status"),
+        Some(Any::new(404).unwrap())
+    );
+}
+
+#[test]
+fn throttle() {
+    let error = decode_error(StatusCode::TOO_MANY_REQUESTS, &HeaderMap::new(), b"");
+    match error.kind() {
+        ErrorKind::Throttle(e) => assert_eq!(e.duration(), None),
+        kind => panic!("unexpected error kind {:?}", kind),
+    }
+
+    let mut headers = HeaderMap::new();
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("5"));
+    let error = decode_error(StatusCode::TOO_MANY_REQUESTS, &headers, b"");
+    match error.kind() {
+        ErrorKind::Throttle(e) => assert_eq!(e.duration(), Some(Duration::from_secs(5))),
+        kind => panic!("unexpected error kind {:?}", kind),
+    }
+}
+
+#[test]
+fn unavailable() {
+    let error = decode_error(StatusCode::SERVICE_UNAVAILABLE, &HeaderMap::new(), b"");
+    match error.kind() {
+        ErrorKind::Unavailable(_) => {}
+        kind => panic!("unexpected error kind {:?}", kind),
+    }
+}
+
+#[test]
+fn unknown_error() {
+    let error = decode_error(StatusCode::BAD_GATEWAY, &HeaderMap::new(), b"bad gateway");
+    match error.kind() {
+        ErrorKind::Service(e) => assert_eq!(*e.error_code(), ErrorCode::Internal),
+        kind => panic!("unexpected error kind {:?}", kind),
+    }
+    assert_eq!(
+        param(error.safe_params(), "status"),
+        Some(Any::new(502).unwrap())
+    );
+    assert_eq!(
+        param(error.unsafe_params(), "body"),
+        Some(Any::new("bad gateway").unwrap())
+    );
+}
+
+#[test]
+fn truncated_body() {
+    let body = "é".repeat(1000);
+    let error = decode_error(StatusCode::BAD_GATEWAY, &HeaderMap::new(), body.as_bytes());
+
+    let expected = format!("{}...", "é".repeat(512));
+    assert_eq!(
+        param(error.unsafe_params(), "body"),
+        Some(Any::new(expected).unwrap())
+    );
+}
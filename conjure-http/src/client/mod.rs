@@ -14,6 +14,7 @@
 
 //! The Conjure HTTP client API.
 
+use crate::server::Parameter;
 use crate::{PathParams, QueryParams};
 use async_trait::async_trait;
 use conjure_error::Error;
@@ -23,6 +24,11 @@ use std::error;
 use std::future::Future;
 use std::io::Write;
 use std::pin::Pin;
+use std::time::Duration;
+
+pub use self::error_response::decode_error;
+
+mod error_response;
 
 /// A trait implemented by HTTP client implementations.
 pub trait Client {
@@ -33,17 +39,16 @@ pub trait Client {
 
     /// Makes an HTTP request.
     ///
-    /// The client is responsible for assembling the request URI. It is provided with the path template, unencoded path
-    /// parameters, unencoded query parameters, header parameters, and request body.
+    /// The client is responsible for assembling the request URI. It is provided with static metadata about the
+    /// endpoint, including its method and path template, along with unencoded path parameters, unencoded query
+    /// parameters, header parameters, and request body.
     ///
     /// A response must only be returned if it has a 2xx status code. The client is responsible for handling all other
-    /// status codes (for example, converting a 5xx response into a service error). The client is also responsible for
-    /// decoding the response body if necessary.
-    #[allow(clippy::too_many_arguments)]
+    /// status codes (for example, converting a 5xx response into a service error), which can be done with
+    /// `decode_error`. The client is also responsible for decoding the response body if necessary.
     fn request<'a, T, U>(
         &self,
-        method: Method,
-        path: &'static str,
+        endpoint: &'static Endpoint,
         path_params: PathParams,
         query_params: QueryParams,
         headers: HeaderMap,
@@ -52,7 +57,7 @@ pub trait Client {
     ) -> Result<U::Output, Error>
     where
         T: RequestBody<'a, Self::BinaryWriter>,
-        U: VisitResponse<Self::BinaryBody>;
+        U: VisitResponse<Self::BinaryBody> + Send;
 }
 
 /// A trait implemented by async HTTP client implementations.
@@ -64,17 +69,16 @@ pub trait AsyncClient {
 
     /// Makes an async HTTP request.
     ///
-    /// The client is responsible for assembling the request URI. It is provided with the path template, unencoded path
-    /// parameters, unencoded query parameters, header parameters, and request body.
+    /// The client is responsible for assembling the request URI. It is provided with static metadata about the
+    /// endpoint, including its method and path template, along with unencoded path parameters, unencoded query
+    /// parameters, header parameters, and request body.
     ///
     /// A response must only be returned if it has a 2xx status code. The client is responsible for handling all other
-    /// status codes (for example, converting a 5xx response into a service error). The client is also responsible for
-    /// decoding the response body if necessary.
-    #[allow(clippy::too_many_arguments)]
+    /// status codes (for example, converting a 5xx response into a service error), which can be done with
+    /// `decode_error`. The client is also responsible for decoding the response body if necessary.
     fn request<'a, T, U>(
         &'a self,
-        method: Method,
-        path: &'static str,
+        endpoint: &'static Endpoint,
         path_params: PathParams,
         query_params: QueryParams,
         headers: HeaderMap,
@@ -86,6 +90,90 @@ pub trait AsyncClient {
         U: VisitResponse<Self::BinaryBody> + Send + 'a;
 }
 
+/// Information about an endpoint of a service.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Endpoint {
+    service: &'static str,
+    name: &'static str,
+    method: Method,
+    path: &'static str,
+    parameters: &'static [Parameter],
+    deprecated: bool,
+    markers: &'static [&'static str],
+}
+
+impl Endpoint {
+    /// Creates a new endpoint object.
+    #[inline]
+    pub const fn new(
+        service: &'static str,
+        name: &'static str,
+        method: Method,
+        path: &'static str,
+        parameters: &'static [Parameter],
+        deprecated: bool,
+    ) -> Endpoint {
+        Endpoint {
+            service,
+            name,
+            method,
+            path,
+            parameters,
+            deprecated,
+            markers: &[],
+        }
+    }
+
+    /// Sets the endpoint's markers.
+    #[inline]
+    pub const fn with_markers(mut self, markers: &'static [&'static str]) -> Endpoint {
+        self.markers = markers;
+        self
+    }
+
+    /// Returns the name of the endpoint's service.
+    #[inline]
+    pub const fn service(&self) -> &'static str {
+        self.service
+    }
+
+    /// Returns the endpoint's name.
+    #[inline]
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the endpoint's HTTP method.
+    #[inline]
+    pub const fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// Returns the endpoint's HTTP path template.
+    #[inline]
+    pub const fn path(&self) -> &'static str {
+        self.path
+    }
+
+    /// Returns the endpoint's path, query, and header parameters.
+    #[inline]
+    pub const fn parameters(&self) -> &'static [Parameter] {
+        self.parameters
+    }
+
+    /// Returns if the endpoint is deprecated.
+    #[inline]
+    pub const fn deprecated(&self) -> bool {
+        self.deprecated
+    }
+
+    /// Returns the fully qualified names of the endpoint's marker types.
+    #[inline]
+    pub const fn markers(&self) -> &'static [&'static str] {
+        self.markers
+    }
+}
+
 /// A trait implemented by request bodies.
 pub trait RequestBody<'a, W> {
     /// Accepts a visitor, calling the correct method corresponding to this body type.
@@ -255,3 +343,23 @@ pub trait AsyncWriteBody<W> {
     where
         W: 'async_trait;
 }
+
+/// A source of asynchronous delays.
+///
+/// Client decorators which need to wait without blocking a thread, like the `RetryingClient`, use this to stay
+/// independent of any particular async runtime. It is implemented for functions returning futures, so a runtime's sleep
+/// function can be used directly.
+pub trait Sleep: Sync + Send {
+    /// Returns a future which completes after the specified duration has elapsed.
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+}
+
+impl<F, R> Sleep for F
+where
+    F: Fn(Duration) -> R + Sync + Send,
+    R: Future<Output = ()> + Send + 'static,
+{
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(self(duration))
+    }
+}
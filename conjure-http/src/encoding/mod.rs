@@ -0,0 +1,208 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Content negotiation for serializable request and response bodies.
+use conjure_error::Error;
+use conjure_serde::{json, smile};
+use http::header::{HeaderValue, ACCEPT};
+use http::HeaderMap;
+use serde::Serialize;
+
+#[cfg(test)]
+mod test;
+
+/// An encoding of serializable request and response bodies.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// JSON, as implemented by `conjure_serde::json`.
+    Json,
+    /// SMILE, as implemented by `conjure_serde::smile`.
+    Smile,
+}
+
+impl Encoding {
+    /// Returns the encoding's MIME type.
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Encoding::Json => "application/json",
+            Encoding::Smile => "application/x-jackson-smile",
+        }
+    }
+
+    /// Returns a `Content-Type` header value for bodies in this encoding.
+    pub fn content_type(self) -> HeaderValue {
+        HeaderValue::from_static(self.mime_type())
+    }
+
+    /// Determines the encoding of a body from its `Content-Type` header value.
+    ///
+    /// Parameters like `charset` are ignored. Returns `None` if the content type is not a supported encoding.
+    pub fn from_content_type(content_type: &HeaderValue) -> Option<Encoding> {
+        let (essence, _) = parse_media_type(content_type.to_str().ok()?);
+        [Encoding::Json, Encoding::Smile]
+            .iter()
+            .cloned()
+            .find(|e| essence.eq_ignore_ascii_case(e.mime_type()))
+    }
+
+    /// Serializes a value with this encoding.
+    pub fn serialize<T>(self, value: &T) -> Result<Vec<u8>, Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        match self {
+            Encoding::Json => json::to_vec(value).map_err(Error::internal),
+            Encoding::Smile => smile::to_vec(value).map_err(Error::internal),
+        }
+    }
+}
+
+/// Creates an `Accept` header value requesting any of the encodings, in decreasing order of preference.
+///
+/// # Panics
+///
+/// Panics if `encodings` is empty.
+pub fn accept(encodings: &[Encoding]) -> HeaderValue {
+    assert!(!encodings.is_empty(), "no encodings provided");
+
+    let mut value = String::new();
+    let mut seen = vec![];
+    for &encoding in encodings {
+        if seen.contains(&encoding) {
+            continue;
+        }
+
+        if !value.is_empty() {
+            value.push_str(", ");
+        }
+        value.push_str(encoding.mime_type());
+        // equal qualities don't imply an order, so each subsequent encoding is marked as less preferred
+        if !seen.is_empty() {
+            value.push_str(&format!(";q=0.{}", 10 - seen.len().min(9)));
+        }
+        seen.push(encoding);
+    }
+
+    HeaderValue::from_str(&value).expect("MIME types are valid header values")
+}
+
+/// Selects the encoding of a serializable response from a request's `Accept` headers.
+///
+/// The encoding accepted with the highest quality is selected, with ties broken by the order of `supported`. A
+/// request without an `Accept` header accepts every encoding. Returns `None` if none of the supported encodings are
+/// acceptable.
+pub fn negotiate(headers: &HeaderMap, supported: &[Encoding]) -> Option<Encoding> {
+    if headers.get(ACCEPT).is_none() {
+        return supported.first().cloned();
+    }
+
+    let ranges = headers
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .filter_map(MediaRange::parse)
+        .collect::<Vec<_>>();
+
+    let mut best = None;
+    for &encoding in supported {
+        let quality = ranges
+            .iter()
+            .filter(|r| r.matches(encoding.mime_type()))
+            .max_by_key(|r| r.specificity())
+            .map_or(0, |r| r.quality);
+
+        if quality > best.map_or(0, |(_, best_quality)| best_quality) {
+            best = Some((encoding, quality));
+        }
+    }
+
+    best.map(|(encoding, _)| encoding)
+}
+
+struct MediaRange<'a> {
+    type_: &'a str,
+    subtype: &'a str,
+    // in thousandths
+    quality: u16,
+}
+
+impl<'a> MediaRange<'a> {
+    fn parse(s: &'a str) -> Option<MediaRange<'a>> {
+        let (essence, params) = parse_media_type(s);
+
+        let mut parts = essence.splitn(2, '/');
+        let type_ = parts.next().filter(|s| !s.is_empty())?;
+        let subtype = parts.next().filter(|s| !s.is_empty())?;
+
+        let mut quality = 1000;
+        for param in params.split(';') {
+            let mut parts = param.splitn(2, '=');
+            let name = parts.next().unwrap_or("").trim();
+            if name.eq_ignore_ascii_case("q") {
+                quality = parse_quality(parts.next().unwrap_or("").trim())?;
+            }
+        }
+
+        Some(MediaRange {
+            type_,
+            subtype,
+            quality,
+        })
+    }
+
+    fn matches(&self, mime_type: &str) -> bool {
+        let mut parts = mime_type.splitn(2, '/');
+        let type_ = parts.next().unwrap_or("");
+        let subtype = parts.next().unwrap_or("");
+
+        match (self.type_, self.subtype) {
+            ("*", "*") => true,
+            (t, "*") => t.eq_ignore_ascii_case(type_),
+            (t, s) => t.eq_ignore_ascii_case(type_) && s.eq_ignore_ascii_case(subtype),
+        }
+    }
+
+    fn specificity(&self) -> u8 {
+        match (self.type_, self.subtype) {
+            ("*", _) => 0,
+            (_, "*") => 1,
+            _ => 2,
+        }
+    }
+}
+
+// splits a media type into its `type/subtype` essence and its parameters
+fn parse_media_type(s: &str) -> (&str, &str) {
+    let mut parts = s.splitn(2, ';');
+    let essence = parts.next().unwrap_or("").trim();
+    let params = parts.next().unwrap_or("");
+    (essence, params)
+}
+
+// qvalues have at most 3 decimal digits and range from 0 to 1
+fn parse_quality(s: &str) -> Option<u16> {
+    let mut parts = s.splitn(2, '.');
+    let whole = parts.next()?;
+    let fraction = parts.next().unwrap_or("");
+    if fraction.len() > 3 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let fraction = format!("{:0<3}", fraction).parse::<u16>().ok()?;
+    match whole {
+        "0" => Some(fraction),
+        "1" if fraction == 0 => Some(1000),
+        _ => None,
+    }
+}
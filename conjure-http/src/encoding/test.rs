@@ -0,0 +1,112 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use http::header::{HeaderValue, ACCEPT};
+use http::HeaderMap;
+
+use crate::encoding::{self, Encoding};
+
+fn negotiate(accept: &[&str], supported: &[Encoding]) -> Option<Encoding> {
+    let mut headers = HeaderMap::new();
+    for value in accept {
+        headers.append(ACCEPT, HeaderValue::from_str(value).unwrap());
+    }
+    encoding::negotiate(&headers, supported)
+}
+
+#[test]
+fn content_type() {
+    for &encoding in &[Encoding::Json, Encoding::Smile] {
+        assert_eq!(
+            Encoding::from_content_type(&encoding.content_type()),
+            Some(encoding)
+        );
+    }
+
+    assert_eq!(
+        Encoding::from_content_type(&HeaderValue::from_static("Application/JSON; charset=utf-8")),
+        Some(Encoding::Json)
+    );
+    assert_eq!(
+        Encoding::from_content_type(&HeaderValue::from_static("application/octet-stream")),
+        None
+    );
+}
+
+#[test]
+fn accept() {
+    assert_eq!(encoding::accept(&[Encoding::Json]), "application/json");
+    assert_eq!(
+        encoding::accept(&[Encoding::Smile, Encoding::Json, Encoding::Smile]),
+        "application/x-jackson-smile, application/json;q=0.9"
+    );
+}
+
+#[test]
+fn negotiate_quality() {
+    let supported = &[Encoding::Json, Encoding::Smile];
+
+    assert_eq!(negotiate(&[], supported), Some(Encoding::Json));
+    assert_eq!(
+        negotiate(&["application/json"], supported),
+        Some(Encoding::Json)
+    );
+    assert_eq!(
+        negotiate(
+            &["application/x-jackson-smile, application/json;q=0.9"],
+            supported
+        ),
+        Some(Encoding::Smile)
+    );
+    assert_eq!(
+        negotiate(
+            &[
+                "application/json;q=0.5",
+                "application/x-jackson-smile;q=0.8"
+            ],
+            supported
+        ),
+        Some(Encoding::Smile)
+    );
+    // ties go to the server's preference
+    assert_eq!(
+        negotiate(
+            &["application/x-jackson-smile, application/json"],
+            supported
+        ),
+        Some(Encoding::Json)
+    );
+    assert_eq!(negotiate(&["application/octet-stream"], supported), None);
+}
+
+#[test]
+fn negotiate_wildcards() {
+    let supported = &[Encoding::Json, Encoding::Smile];
+
+    assert_eq!(negotiate(&["*/*"], supported), Some(Encoding::Json));
+    assert_eq!(
+        negotiate(&["application/*"], supported),
+        Some(Encoding::Json)
+    );
+    // the most specific range determines an encoding's quality
+    assert_eq!(
+        negotiate(&["application/*, application/json;q=0"], supported),
+        Some(Encoding::Smile)
+    );
+    assert_eq!(negotiate(&["text/*"], supported), None);
+    // invalid ranges are ignored
+    assert_eq!(
+        negotiate(&["application/json;q=2, */*;q=0.1"], supported),
+        Some(Encoding::Json)
+    );
+}
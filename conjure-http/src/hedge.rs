@@ -0,0 +1,320 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! A client decorator which hedges slow requests to idempotent endpoints.
+//!
+//! The `HedgingClient` wraps another `AsyncClient`, and sends a second attempt of an idempotent request if the first
+//! has not completed after a delay. Whichever attempt completes first is used, which cuts the tail latency caused by
+//! a few slow replicas of a service.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use conjure_http::hedge::HedgingClient;
+//! use std::time::Duration;
+//!
+//! let client = HedgingClient::new(HyperClient::new(uri))
+//!     .delay(Duration::from_millis(50))
+//!     .sleep(tokio::time::delay_for);
+//! let client = MyServiceAsyncClient::new(client);
+//!
+//! let response = client.my_endpoint("hello world").await?;
+//! ```
+use crate::client::{Accept, AsyncClient, AsyncRequestBody, Endpoint, Sleep, VisitResponse};
+use crate::retry::{AsyncBinaryBody, Body, CaptureBodyVisitor};
+use crate::{PathParams, QueryParams};
+use conjure_error::Error;
+use http::{HeaderMap, Method};
+use serde::Deserializer;
+use std::error;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// A client which hedges slow requests to idempotent endpoints.
+///
+/// Requests to endpoints with the `GET` or `HEAD` methods, or with the configured idempotency marker, are hedged. If
+/// the first attempt of such a request has not completed after the hedging delay, a second attempt is sent, and the
+/// result of whichever completes first is returned while the other is cancelled. If the first attempt to complete
+/// fails after the other has started processing its response, the other's result is returned instead.
+///
+/// Serializable request bodies are captured in memory so they can be sent twice, and serialize identically on both
+/// attempts. Binary request bodies must be released by the first attempt and then successfully reset before the second
+/// attempt is sent; otherwise the request is not hedged.
+///
+/// The client implements `AsyncClient` for any `AsyncClient` once a `Sleep` implementation has been provided with the
+/// `sleep` method.
+#[derive(Clone, Debug)]
+pub struct HedgingClient<C, S = ()> {
+    inner: C,
+    sleep: S,
+    delay: Duration,
+    idempotency_marker: Option<&'static str>,
+}
+
+impl<C> HedgingClient<C> {
+    /// Creates a new client wrapping another.
+    ///
+    /// By default, requests are hedged after 100 milliseconds, and no idempotency marker is configured.
+    pub fn new(inner: C) -> HedgingClient<C> {
+        HedgingClient {
+            inner,
+            sleep: (),
+            delay: Duration::from_millis(100),
+            idempotency_marker: None,
+        }
+    }
+}
+
+impl<C, S> HedgingClient<C, S> {
+    /// Sets the source of delays used to wait before hedging a request.
+    pub fn sleep<T>(self, sleep: T) -> HedgingClient<C, T>
+    where
+        T: Sleep,
+    {
+        HedgingClient {
+            inner: self.inner,
+            sleep,
+            delay: self.delay,
+            idempotency_marker: self.idempotency_marker,
+        }
+    }
+
+    /// Sets the time to wait for the first attempt of a request to complete before sending a second.
+    pub fn delay(mut self, delay: Duration) -> HedgingClient<C, S> {
+        self.delay = delay;
+        self
+    }
+
+    /// Sets the fully qualified name of a marker type which identifies idempotent endpoints.
+    ///
+    /// Requests to endpoints with this marker are hedged regardless of their HTTP method.
+    pub fn idempotency_marker(mut self, idempotency_marker: &'static str) -> HedgingClient<C, S> {
+        self.idempotency_marker = Some(idempotency_marker);
+        self
+    }
+
+    /// Returns a shared reference to the inner client.
+    pub fn get_ref(&self) -> &C {
+        &self.inner
+    }
+
+    fn hedged(&self, endpoint: &Endpoint) -> bool {
+        if *endpoint.method() == Method::GET || *endpoint.method() == Method::HEAD {
+            return true;
+        }
+
+        match self.idempotency_marker {
+            Some(marker) => endpoint.markers().contains(&marker),
+            None => false,
+        }
+    }
+}
+
+impl<C, S> AsyncClient for HedgingClient<C, S>
+where
+    C: AsyncClient + Sync + Send,
+    C::BinaryWriter: Send + 'static,
+    S: Sleep,
+{
+    type BinaryWriter = C::BinaryWriter;
+    type BinaryBody = C::BinaryBody;
+
+    fn request<'a, T, U>(
+        &'a self,
+        endpoint: &'static Endpoint,
+        path_params: PathParams,
+        query_params: QueryParams,
+        headers: HeaderMap,
+        body: T,
+        response_visitor: U,
+    ) -> Pin<Box<dyn Future<Output = Result<U::Output, Error>> + Send + 'a>>
+    where
+        T: AsyncRequestBody<'a, C::BinaryWriter> + Send + 'a,
+        U: VisitResponse<C::BinaryBody> + Send + 'a,
+    {
+        if !self.hedged(endpoint) {
+            return self.inner.request(
+                endpoint,
+                path_params,
+                query_params,
+                headers,
+                body,
+                response_visitor,
+            );
+        }
+
+        Box::pin(async move {
+            let body: Body<AsyncBinaryBody<'a, C::BinaryWriter>> =
+                body.accept(CaptureBodyVisitor)?;
+            let response_visitor = Arc::new(Mutex::new(Some(response_visitor)));
+
+            let first_visitor = AttemptVisitor::new(&response_visitor);
+            let first_started = first_visitor.started.clone();
+            let first = self.inner.request(
+                endpoint,
+                path_params.clone(),
+                query_params.clone(),
+                headers.clone(),
+                body.clone(),
+                first_visitor,
+            );
+
+            let first = match select(first, self.sleep.sleep(self.delay)).await {
+                Either::Left((result, _)) => return result,
+                Either::Right(((), first)) => first,
+            };
+
+            // there's no point in hedging once the first attempt has started processing its response
+            if first_started.load(Ordering::SeqCst) {
+                return first.await;
+            }
+
+            if let Body::Binary(slot) = &body {
+                // the body is only back in its slot if the first attempt is done with it
+                let taken = slot.lock().unwrap().take();
+                let mut binary = match taken {
+                    Some(binary) => binary,
+                    None => return first.await,
+                };
+                let reset = binary.as_mut().reset().await;
+                *slot.lock().unwrap() = Some(binary);
+                if !reset {
+                    return first.await;
+                }
+            }
+
+            let second_visitor = AttemptVisitor::new(&response_visitor);
+            let second_started = second_visitor.started.clone();
+            let second = self.inner.request(
+                endpoint,
+                path_params,
+                query_params,
+                headers,
+                body,
+                second_visitor,
+            );
+
+            // the other attempt is still the one to wait for if it has started processing its response
+            let other = match select(first, second).await {
+                Either::Left((Err(_), second)) if second_started.load(Ordering::SeqCst) => second,
+                Either::Left((result, _)) => return result,
+                Either::Right((Err(_), first)) if first_started.load(Ordering::SeqCst) => first,
+                Either::Right((result, _)) => return result,
+            };
+            other.await
+        })
+    }
+}
+
+// a response visitor shared between attempts, which records if its attempt was the one to receive a response
+struct AttemptVisitor<U> {
+    visitor: Arc<Mutex<Option<U>>>,
+    started: Arc<AtomicBool>,
+}
+
+impl<U> AttemptVisitor<U> {
+    fn new(visitor: &Arc<Mutex<Option<U>>>) -> AttemptVisitor<U> {
+        AttemptVisitor {
+            visitor: visitor.clone(),
+            started: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn take(&self) -> Result<U, Error> {
+        match self.visitor.lock().unwrap().take() {
+            Some(visitor) => {
+                self.started.store(true, Ordering::SeqCst);
+                Ok(visitor)
+            }
+            None => Err(Error::internal_safe("response visitor already consumed")),
+        }
+    }
+}
+
+impl<T, U> VisitResponse<T> for AttemptVisitor<U>
+where
+    U: VisitResponse<T>,
+{
+    type Output = U::Output;
+
+    fn accept(&self) -> Accept {
+        match &*self.visitor.lock().unwrap() {
+            Some(visitor) => visitor.accept(),
+            None => Accept::Empty,
+        }
+    }
+
+    fn visit_empty(self) -> Result<U::Output, Error> {
+        self.take()?.visit_empty()
+    }
+
+    fn visit_serializable<'de, D>(self, deserializer: D) -> Result<U::Output, Error>
+    where
+        D: Deserializer<'de>,
+        D::Error: Into<Box<dyn error::Error + Sync + Send>>,
+    {
+        self.take()?.visit_serializable(deserializer)
+    }
+
+    fn visit_binary(self, body: T) -> Result<U::Output, Error> {
+        self.take()?.visit_binary(body)
+    }
+}
+
+enum Either<A, B> {
+    Left(A),
+    Right(B),
+}
+
+// waits for either of two futures to complete, returning its output along with the other future
+fn select<A, B>(a: A, b: B) -> Select<A, B>
+where
+    A: Future + Unpin,
+    B: Future + Unpin,
+{
+    Select {
+        inner: Some((a, b)),
+    }
+}
+
+struct Select<A, B> {
+    inner: Option<(A, B)>,
+}
+
+impl<A, B> Future for Select<A, B>
+where
+    A: Future + Unpin,
+    B: Future + Unpin,
+{
+    type Output = Either<(A::Output, B), (B::Output, A)>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let (a, b) = self.inner.as_mut().expect("select polled after completion");
+
+        if let Poll::Ready(output) = Pin::new(a).poll(cx) {
+            let (_, b) = self.inner.take().unwrap();
+            return Poll::Ready(Either::Left((output, b)));
+        }
+
+        if let Poll::Ready(output) = Pin::new(b).poll(cx) {
+            let (a, _) = self.inner.take().unwrap();
+            return Poll::Ready(Either::Right((output, a)));
+        }
+
+        Poll::Pending
+    }
+}
@@ -25,10 +25,18 @@ pub use crate::path_params::PathParams;
 #[doc(inline)]
 pub use crate::query_params::QueryParams;
 
+pub mod blocking;
+pub mod breaker;
 pub mod client;
+pub mod encoding;
+pub mod hedge;
+pub mod limit;
+pub mod loopback;
 pub mod path_params;
 pub mod query_params;
+pub mod retry;
 pub mod server;
+mod value;
 
 #[doc(hidden)]
 pub mod private;
@@ -0,0 +1,382 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! A client decorator which adaptively limits the number of concurrent requests to each endpoint.
+//!
+//! The `LimitingClient` wraps another `AsyncClient`, and uses an additive-increase/multiplicative-decrease (AIMD)
+//! algorithm to find the concurrency each endpoint of a degraded service can sustain, rather than piling more requests
+//! onto it.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use conjure_http::limit::LimitingClient;
+//!
+//! let client = LimitingClient::new(HyperClient::new(uri)).initial_limit(10);
+//! let client = MyServiceAsyncClient::new(client);
+//!
+//! let response = client.my_endpoint("hello world").await?;
+//! ```
+use crate::client::{AsyncClient, AsyncRequestBody, Endpoint, VisitResponse};
+use crate::{PathParams, QueryParams};
+use conjure_error::{Error, ErrorKind};
+use http::{HeaderMap, Method};
+use std::collections::{HashMap, VecDeque};
+use std::error;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// A client which adaptively limits the number of concurrent requests to each endpoint.
+///
+/// Each endpoint, identified by its HTTP method and path template, has its own concurrency limit. The limit grows by
+/// roughly one for every `limit` successful requests, and shrinks by the backoff ratio whenever a request fails with a
+/// `Throttle` or `Unavailable` error, or an error caused by an IO timeout. Other errors leave the limit unchanged.
+///
+/// Requests made while an endpoint is at its limit wait in a queue until a slot frees up. If the client is configured
+/// to fail fast, they instead fail immediately with a `Throttle` error.
+///
+/// Clones of the client share their limits.
+#[derive(Clone, Debug)]
+pub struct LimitingClient<C> {
+    inner: C,
+    initial_limit: usize,
+    min_limit: usize,
+    max_limit: usize,
+    backoff_ratio: f64,
+    fail_fast: bool,
+    limiters: Arc<Mutex<Limiters>>,
+}
+
+type Limiters = HashMap<(Method, &'static str), Arc<Limiter>>;
+
+impl<C> LimitingClient<C> {
+    /// Creates a new client wrapping another.
+    ///
+    /// By default, each endpoint starts with a limit of 20 concurrent requests, which is kept between 1 and 1000, and
+    /// is multiplied by 0.9 when backing off. Requests are queued when an endpoint is at its limit.
+    pub fn new(inner: C) -> LimitingClient<C> {
+        LimitingClient {
+            inner,
+            initial_limit: 20,
+            min_limit: 1,
+            max_limit: 1000,
+            backoff_ratio: 0.9,
+            fail_fast: false,
+            limiters: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Sets the limit each endpoint starts with.
+    pub fn initial_limit(mut self, initial_limit: usize) -> LimitingClient<C> {
+        self.initial_limit = initial_limit;
+        self
+    }
+
+    /// Sets the lower bound of each endpoint's limit.
+    ///
+    /// Values less than 1 are treated as 1.
+    pub fn min_limit(mut self, min_limit: usize) -> LimitingClient<C> {
+        self.min_limit = min_limit;
+        self
+    }
+
+    /// Sets the upper bound of each endpoint's limit.
+    pub fn max_limit(mut self, max_limit: usize) -> LimitingClient<C> {
+        self.max_limit = max_limit;
+        self
+    }
+
+    /// Sets the factor an endpoint's limit is multiplied by when a request fails with a QoS error or times out.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ratio is not between 0 and 1.
+    pub fn backoff_ratio(mut self, backoff_ratio: f64) -> LimitingClient<C> {
+        assert!(
+            backoff_ratio > 0. && backoff_ratio < 1.,
+            "backoff ratio must be between 0 and 1"
+        );
+        self.backoff_ratio = backoff_ratio;
+        self
+    }
+
+    /// Sets whether requests should fail immediately with a `Throttle` error rather than waiting when an endpoint is at
+    /// its limit.
+    pub fn fail_fast(mut self, fail_fast: bool) -> LimitingClient<C> {
+        self.fail_fast = fail_fast;
+        self
+    }
+
+    /// Returns a shared reference to the inner client.
+    pub fn get_ref(&self) -> &C {
+        &self.inner
+    }
+
+    /// Returns the current concurrency limit of an endpoint.
+    pub fn limit(&self, endpoint: &Endpoint) -> usize {
+        self.limiter(endpoint).state.lock().unwrap().limit()
+    }
+
+    fn limiter(&self, endpoint: &Endpoint) -> Arc<Limiter> {
+        let min_limit = self.min_limit.max(1);
+        let max_limit = self.max_limit.max(min_limit);
+        let initial_limit = self.initial_limit.max(min_limit).min(max_limit);
+
+        self.limiters
+            .lock()
+            .unwrap()
+            .entry((endpoint.method().clone(), endpoint.path()))
+            .or_insert_with(|| {
+                Arc::new(Limiter {
+                    state: Mutex::new(State {
+                        limit: initial_limit as f64,
+                        min_limit: min_limit as f64,
+                        max_limit: max_limit as f64,
+                        backoff_ratio: self.backoff_ratio,
+                        in_flight: 0,
+                        next_id: 0,
+                        waiters: VecDeque::new(),
+                    }),
+                })
+            })
+            .clone()
+    }
+}
+
+impl<C> AsyncClient for LimitingClient<C>
+where
+    C: AsyncClient + Sync + Send,
+{
+    type BinaryWriter = C::BinaryWriter;
+    type BinaryBody = C::BinaryBody;
+
+    fn request<'a, T, U>(
+        &'a self,
+        endpoint: &'static Endpoint,
+        path_params: PathParams,
+        query_params: QueryParams,
+        headers: HeaderMap,
+        body: T,
+        response_visitor: U,
+    ) -> Pin<Box<dyn Future<Output = Result<U::Output, Error>> + Send + 'a>>
+    where
+        T: AsyncRequestBody<'a, C::BinaryWriter> + Send + 'a,
+        U: VisitResponse<C::BinaryBody> + Send + 'a,
+    {
+        Box::pin(async move {
+            let limiter = self.limiter(endpoint);
+            let permit = if self.fail_fast {
+                match limiter.try_acquire() {
+                    Some(permit) => permit,
+                    None => {
+                        return Err(Error::throttle_safe("endpoint concurrency limit reached")
+                            .with_safe_param("service", endpoint.service())
+                            .with_safe_param("endpoint", endpoint.name()))
+                    }
+                }
+            } else {
+                Acquire {
+                    limiter: &limiter,
+                    id: None,
+                }
+                .await
+            };
+
+            let result = self
+                .inner
+                .request(
+                    endpoint,
+                    path_params,
+                    query_params,
+                    headers,
+                    body,
+                    response_visitor,
+                )
+                .await;
+
+            let outcome = match &result {
+                Ok(_) => Outcome::Success,
+                Err(error) => match error.kind() {
+                    ErrorKind::Throttle(_) | ErrorKind::Unavailable(_) => Outcome::Dropped,
+                    _ if is_timeout(error) => Outcome::Dropped,
+                    _ => Outcome::Ignored,
+                },
+            };
+            permit.release(outcome);
+
+            result
+        })
+    }
+}
+
+fn is_timeout(error: &Error) -> bool {
+    let mut cause: Option<&(dyn error::Error + 'static)> = Some(error.cause());
+    while let Some(error) = cause {
+        if let Some(error) = error.downcast_ref::<io::Error>() {
+            if error.kind() == io::ErrorKind::TimedOut {
+                return true;
+            }
+        }
+        cause = error.source();
+    }
+    false
+}
+
+enum Outcome {
+    Success,
+    Dropped,
+    Ignored,
+}
+
+// the concurrency limit of a single endpoint
+#[derive(Debug)]
+struct Limiter {
+    state: Mutex<State>,
+}
+
+impl Limiter {
+    fn try_acquire(self: &Arc<Self>) -> Option<Permit> {
+        let mut state = self.state.lock().unwrap();
+        if state.waiters.len() < state.available() {
+            state.in_flight += 1;
+            Some(Permit {
+                limiter: self.clone(),
+                released: false,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    limit: f64,
+    min_limit: f64,
+    max_limit: f64,
+    backoff_ratio: f64,
+    in_flight: usize,
+    next_id: u64,
+    waiters: VecDeque<(u64, Waker)>,
+}
+
+impl State {
+    fn limit(&self) -> usize {
+        self.limit as usize
+    }
+
+    fn available(&self) -> usize {
+        self.limit().saturating_sub(self.in_flight)
+    }
+
+    // wakes the waiters which are now able to acquire a permit
+    fn notify(&self) {
+        for (_, waker) in self.waiters.iter().take(self.available()) {
+            waker.wake_by_ref();
+        }
+    }
+}
+
+// a future which waits in line for a permit
+struct Acquire<'a> {
+    limiter: &'a Arc<Limiter>,
+    id: Option<u64>,
+}
+
+impl Future for Acquire<'_> {
+    type Output = Permit;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Permit> {
+        let mut state = self.limiter.state.lock().unwrap();
+
+        let position = match self.id {
+            Some(id) => state.waiters.iter().position(|w| w.0 == id),
+            None => None,
+        };
+        let ahead = position.unwrap_or_else(|| state.waiters.len());
+
+        if ahead < state.available() {
+            if let Some(position) = position {
+                state.waiters.remove(position);
+            }
+            state.in_flight += 1;
+            drop(state);
+            self.id = None;
+            return Poll::Ready(Permit {
+                limiter: self.limiter.clone(),
+                released: false,
+            });
+        }
+
+        match position {
+            Some(position) => state.waiters[position].1 = cx.waker().clone(),
+            None => {
+                let id = state.next_id;
+                state.next_id += 1;
+                state.waiters.push_back((id, cx.waker().clone()));
+                drop(state);
+                self.id = Some(id);
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for Acquire<'_> {
+    fn drop(&mut self) {
+        if let Some(id) = self.id {
+            let mut state = self.limiter.state.lock().unwrap();
+            if let Some(position) = state.waiters.iter().position(|w| w.0 == id) {
+                state.waiters.remove(position);
+            }
+            state.notify();
+        }
+    }
+}
+
+// a slot in an endpoint's limit, held for the duration of a request
+struct Permit {
+    limiter: Arc<Limiter>,
+    released: bool,
+}
+
+impl Permit {
+    fn release(mut self, outcome: Outcome) {
+        self.released = true;
+        let mut state = self.limiter.state.lock().unwrap();
+        state.in_flight -= 1;
+        match outcome {
+            Outcome::Success => state.limit = (state.limit + 1. / state.limit).min(state.max_limit),
+            Outcome::Dropped => {
+                state.limit = (state.limit * state.backoff_ratio).max(state.min_limit)
+            }
+            Outcome::Ignored => {}
+        }
+        state.notify();
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        // the request was cancelled, so it says nothing about the endpoint's capacity
+        if !self.released {
+            let mut state = self.limiter.state.lock().unwrap();
+            state.in_flight -= 1;
+            state.notify();
+        }
+    }
+}
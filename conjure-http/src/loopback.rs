@@ -0,0 +1,458 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An in-memory client which dispatches requests directly to server resources.
+//!
+//! The `LoopbackClient` allows generated clients to be tested against generated resources end-to-end without an HTTP
+//! stack.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use conjure_http::loopback::LoopbackClient;
+//!
+//! let client = LoopbackClient::new().resource(MyServiceResource::new(MyServiceHandler));
+//! let client = MyServiceClient::new(client);
+//!
+//! let response = client.my_endpoint("hello world")?;
+//! ```
+use crate::client::{
+    self, AsyncClient, AsyncRequestBody, AsyncVisitRequestBody, Client, Endpoint, VisitRequestBody,
+};
+use crate::server::{
+    self, encode_error, AsyncHandler, AsyncResource, AsyncVisitResponse, Handler, Resource,
+    VisitResponse,
+};
+use crate::{PathParams, QueryParams};
+use conjure_error::{Error, NotFound};
+use conjure_serde::json;
+use http::{HeaderMap, Method};
+use serde::Serialize;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// A client which dispatches requests to resources registered with it rather than over the network.
+///
+/// The client implements both `Client` and `AsyncClient`. Requests made through the `Client` implementation are
+/// dispatched to resources registered with the `resource` method, and requests made through the `AsyncClient`
+/// implementation are dispatched to resources registered with the `async_resource` method. Endpoints are matched by
+/// their HTTP method and path template.
+///
+/// Serializable request and response bodies are encoded and decoded as Conjure JSON, and binary bodies are buffered
+/// in memory as `Vec<u8>`s. Errors returned by a resource are converted into the HTTP status code and
+/// `SerializableError` a server would respond with, and then back into an `Error` in the same way as a client
+/// receiving that response.
+#[derive(Clone, Default, Debug)]
+pub struct LoopbackClient {
+    routes: Vec<Route<Arc<dyn SyncRoute>>>,
+    async_routes: Vec<Route<Arc<dyn AsyncRoute>>>,
+}
+
+impl LoopbackClient {
+    /// Creates a new client with no registered resources.
+    pub fn new() -> LoopbackClient {
+        LoopbackClient::default()
+    }
+
+    /// Registers a resource which will handle requests made through the `Client` implementation.
+    pub fn resource<T>(mut self, resource: T) -> LoopbackClient
+    where
+        T: Resource<Vec<u8>, Vec<u8>> + 'static + Sync + Send,
+    {
+        let resource = Arc::new(resource);
+        for endpoint in T::endpoints::<LoopbackRequestBody, LoopbackResponseVisitor>() {
+            self.routes.push(Route {
+                method: endpoint.metadata.method().clone(),
+                path: endpoint.metadata.path(),
+                handler: Arc::new(SyncEndpoint {
+                    resource: resource.clone(),
+                    handler: endpoint.handler,
+                }),
+            });
+        }
+        self
+    }
+
+    /// Registers a resource which will handle requests made through the `AsyncClient` implementation.
+    pub fn async_resource<T>(mut self, resource: T) -> LoopbackClient
+    where
+        T: AsyncResource<Vec<u8>, Vec<u8>> + 'static,
+    {
+        let resource = Arc::new(resource);
+        for endpoint in T::endpoints::<LoopbackRequestBody, LoopbackAsyncResponseVisitor>() {
+            self.async_routes.push(Route {
+                method: endpoint.metadata.method().clone(),
+                path: endpoint.metadata.path(),
+                handler: Arc::new(AsyncEndpoint {
+                    resource: resource.clone(),
+                    handler: endpoint.handler,
+                }),
+            });
+        }
+        self
+    }
+}
+
+impl Client for LoopbackClient {
+    type BinaryWriter = Vec<u8>;
+    type BinaryBody = Vec<u8>;
+
+    fn request<'a, T, U>(
+        &self,
+        endpoint: &'static Endpoint,
+        path_params: PathParams,
+        query_params: QueryParams,
+        headers: HeaderMap,
+        body: T,
+        response_visitor: U,
+    ) -> Result<U::Output, Error>
+    where
+        T: client::RequestBody<'a, Vec<u8>>,
+        U: client::VisitResponse<Vec<u8>> + Send,
+    {
+        let body = body.accept(LoopbackRequestBodyVisitor)?;
+
+        let response = match find(&self.routes, endpoint) {
+            Some(handler) => handler.handle(&path_params, &query_params, &headers, body),
+            None => Err(not_found(endpoint)),
+        };
+
+        visit_response(response, response_visitor)
+    }
+}
+
+impl AsyncClient for LoopbackClient {
+    type BinaryWriter = Vec<u8>;
+    type BinaryBody = Vec<u8>;
+
+    fn request<'a, T, U>(
+        &'a self,
+        endpoint: &'static Endpoint,
+        path_params: PathParams,
+        query_params: QueryParams,
+        headers: HeaderMap,
+        body: T,
+        response_visitor: U,
+    ) -> Pin<Box<dyn Future<Output = Result<U::Output, Error>> + Send + 'a>>
+    where
+        T: AsyncRequestBody<'a, Vec<u8>> + Send + 'a,
+        U: client::VisitResponse<Vec<u8>> + Send + 'a,
+    {
+        Box::pin(async move {
+            let body = match body.accept(LoopbackAsyncRequestBodyVisitor) {
+                LoopbackAsyncRequestBody::Empty => LoopbackRequestBody::Empty,
+                LoopbackAsyncRequestBody::Serializable(body) => {
+                    LoopbackRequestBody::Serializable(body?)
+                }
+                LoopbackAsyncRequestBody::Binary(mut body) => {
+                    let mut buf = vec![];
+                    body.as_mut().write_body(Pin::new(&mut buf)).await?;
+                    LoopbackRequestBody::Binary(buf)
+                }
+            };
+
+            let response = match find(&self.async_routes, endpoint) {
+                Some(handler) => {
+                    handler
+                        .handle(&path_params, &query_params, &headers, body)
+                        .await
+                }
+                None => Err(not_found(endpoint)),
+            };
+
+            visit_response(response, response_visitor)
+        })
+    }
+}
+
+#[derive(Clone)]
+struct Route<T> {
+    method: Method,
+    path: &'static str,
+    handler: T,
+}
+
+impl<T> fmt::Debug for Route<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{} {}", self.method, self.path)
+    }
+}
+
+fn find<'a, T>(routes: &'a [Route<T>], endpoint: &Endpoint) -> Option<&'a T> {
+    routes
+        .iter()
+        .find(|r| r.method == *endpoint.method() && r.path == endpoint.path())
+        .map(|r| &r.handler)
+}
+
+fn not_found(endpoint: &Endpoint) -> Error {
+    Error::service_safe("no resource registered for endpoint", NotFound::new())
+        .with_safe_param("method", endpoint.method().as_str())
+        .with_safe_param("path", endpoint.path())
+}
+
+trait SyncRoute: Sync + Send {
+    fn handle(
+        &self,
+        path_params: &PathParams,
+        query_params: &QueryParams,
+        headers: &HeaderMap,
+        body: LoopbackRequestBody,
+    ) -> Result<LoopbackResponse, Error>;
+}
+
+struct SyncEndpoint<T>
+where
+    T: 'static,
+{
+    resource: Arc<T>,
+    handler: &'static (dyn Handler<T, LoopbackRequestBody, LoopbackResponseVisitor> + Sync + Send),
+}
+
+impl<T> SyncRoute for SyncEndpoint<T>
+where
+    T: Sync + Send,
+{
+    fn handle(
+        &self,
+        path_params: &PathParams,
+        query_params: &QueryParams,
+        headers: &HeaderMap,
+        body: LoopbackRequestBody,
+    ) -> Result<LoopbackResponse, Error> {
+        self.handler.handle(
+            &self.resource,
+            path_params,
+            query_params,
+            headers,
+            body,
+            LoopbackResponseVisitor,
+        )
+    }
+}
+
+trait AsyncRoute: Sync + Send {
+    fn handle<'a>(
+        &'a self,
+        path_params: &'a PathParams,
+        query_params: &'a QueryParams,
+        headers: &'a HeaderMap,
+        body: LoopbackRequestBody,
+    ) -> Pin<Box<dyn Future<Output = Result<LoopbackResponse, Error>> + Send + 'a>>;
+}
+
+struct AsyncEndpoint<T>
+where
+    T: 'static + Sync + Send,
+{
+    resource: Arc<T>,
+    handler: &'static (dyn AsyncHandler<T, LoopbackRequestBody, LoopbackAsyncResponseVisitor>
+                  + Sync
+                  + Send),
+}
+
+impl<T> AsyncRoute for AsyncEndpoint<T>
+where
+    T: Sync + Send,
+{
+    fn handle<'a>(
+        &'a self,
+        path_params: &'a PathParams,
+        query_params: &'a QueryParams,
+        headers: &'a HeaderMap,
+        body: LoopbackRequestBody,
+    ) -> Pin<Box<dyn Future<Output = Result<LoopbackResponse, Error>> + Send + 'a>> {
+        Box::pin(async move {
+            let response = self
+                .handler
+                .handle(
+                    &self.resource,
+                    path_params,
+                    query_params,
+                    headers,
+                    body,
+                    LoopbackAsyncResponseVisitor,
+                )
+                .await?;
+            response.await
+        })
+    }
+}
+
+struct LoopbackRequestBodyVisitor;
+
+impl<'a> VisitRequestBody<'a, Vec<u8>> for LoopbackRequestBodyVisitor {
+    type Output = Result<LoopbackRequestBody, Error>;
+
+    fn visit_empty(self) -> Result<LoopbackRequestBody, Error> {
+        Ok(LoopbackRequestBody::Empty)
+    }
+
+    fn visit_serializable<T>(self, body: T) -> Result<LoopbackRequestBody, Error>
+    where
+        T: Serialize + 'a,
+    {
+        let body = json::to_vec(&body).map_err(Error::internal)?;
+        Ok(LoopbackRequestBody::Serializable(body))
+    }
+
+    fn visit_binary<T>(self, mut body: T) -> Result<LoopbackRequestBody, Error>
+    where
+        T: client::WriteBody<Vec<u8>> + 'a,
+    {
+        let mut buf = vec![];
+        body.write_body(&mut buf)?;
+        Ok(LoopbackRequestBody::Binary(buf))
+    }
+}
+
+enum LoopbackAsyncRequestBody<'a> {
+    Empty,
+    Serializable(Result<Vec<u8>, Error>),
+    Binary(Pin<Box<dyn client::AsyncWriteBody<Vec<u8>> + Sync + Send + 'a>>),
+}
+
+struct LoopbackAsyncRequestBodyVisitor;
+
+impl<'a> AsyncVisitRequestBody<'a, Vec<u8>> for LoopbackAsyncRequestBodyVisitor {
+    type Output = LoopbackAsyncRequestBody<'a>;
+
+    fn visit_empty(self) -> LoopbackAsyncRequestBody<'a> {
+        LoopbackAsyncRequestBody::Empty
+    }
+
+    fn visit_serializable<T>(self, body: T) -> LoopbackAsyncRequestBody<'a>
+    where
+        T: Serialize + 'a,
+    {
+        LoopbackAsyncRequestBody::Serializable(json::to_vec(&body).map_err(Error::internal))
+    }
+
+    fn visit_binary<T>(self, body: T) -> LoopbackAsyncRequestBody<'a>
+    where
+        T: client::AsyncWriteBody<Vec<u8>> + Sync + Send + 'a,
+    {
+        LoopbackAsyncRequestBody::Binary(Box::pin(body))
+    }
+}
+
+enum LoopbackRequestBody {
+    Empty,
+    Serializable(Vec<u8>),
+    Binary(Vec<u8>),
+}
+
+impl server::RequestBody for LoopbackRequestBody {
+    type BinaryBody = Vec<u8>;
+
+    fn accept<V>(self, visitor: V) -> Result<V::Output, Error>
+    where
+        V: server::VisitRequestBody<Vec<u8>>,
+    {
+        match self {
+            LoopbackRequestBody::Empty => visitor.visit_empty(),
+            LoopbackRequestBody::Serializable(body) => {
+                visitor.visit_serializable(&mut json::ServerDeserializer::from_slice(&body))
+            }
+            LoopbackRequestBody::Binary(body) => visitor.visit_binary(body),
+        }
+    }
+}
+
+enum LoopbackResponse {
+    Empty,
+    Serializable(Vec<u8>),
+    Binary(Vec<u8>),
+}
+
+struct LoopbackResponseVisitor;
+
+impl VisitResponse for LoopbackResponseVisitor {
+    type BinaryWriter = Vec<u8>;
+    type Output = LoopbackResponse;
+
+    fn visit_empty(self) -> Result<LoopbackResponse, Error> {
+        Ok(LoopbackResponse::Empty)
+    }
+
+    fn visit_serializable<T>(self, body: T) -> Result<LoopbackResponse, Error>
+    where
+        T: Serialize + 'static,
+    {
+        let body = json::to_vec(&body).map_err(Error::internal)?;
+        Ok(LoopbackResponse::Serializable(body))
+    }
+
+    fn visit_binary<T>(self, body: T) -> Result<LoopbackResponse, Error>
+    where
+        T: server::WriteBody<Vec<u8>> + 'static,
+    {
+        let mut buf = vec![];
+        body.write_body(&mut buf)?;
+        Ok(LoopbackResponse::Binary(buf))
+    }
+}
+
+struct LoopbackAsyncResponseVisitor;
+
+impl AsyncVisitResponse for LoopbackAsyncResponseVisitor {
+    type BinaryWriter = Vec<u8>;
+    type Output = Pin<Box<dyn Future<Output = Result<LoopbackResponse, Error>> + Send>>;
+
+    fn visit_empty(self) -> Result<Self::Output, Error> {
+        Ok(Box::pin(async { Ok(LoopbackResponse::Empty) }))
+    }
+
+    fn visit_serializable<T>(self, body: T) -> Result<Self::Output, Error>
+    where
+        T: Serialize + 'static + Send,
+    {
+        let body = json::to_vec(&body).map_err(Error::internal)?;
+        Ok(Box::pin(async { Ok(LoopbackResponse::Serializable(body)) }))
+    }
+
+    fn visit_binary<T>(self, body: T) -> Result<Self::Output, Error>
+    where
+        T: server::AsyncWriteBody<Vec<u8>> + 'static + Send,
+    {
+        Ok(Box::pin(async move {
+            let mut buf = vec![];
+            body.write_body(Pin::new(&mut buf)).await?;
+            Ok(LoopbackResponse::Binary(buf))
+        }))
+    }
+}
+
+fn visit_response<U>(
+    response: Result<LoopbackResponse, Error>,
+    visitor: U,
+) -> Result<U::Output, Error>
+where
+    U: client::VisitResponse<Vec<u8>>,
+{
+    match response {
+        Ok(LoopbackResponse::Empty) => visitor.visit_empty(),
+        Ok(LoopbackResponse::Serializable(body)) => {
+            visitor.visit_serializable(&mut json::ClientDeserializer::from_slice(&body))
+        }
+        Ok(LoopbackResponse::Binary(body)) => visitor.visit_binary(body),
+        // mirror the response a server would send and the error a client would produce from it
+        Err(error) => {
+            let (status, headers, body) = encode_error(&error).into_parts();
+            Err(client::decode_error(status, &headers, &body))
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use conjure_error::{Error, InvalidArgument, PermissionDenied};
+use conjure_error::{Error, InvalidArgument, PermissionDenied, RequestEntityTooLarge};
 use conjure_object::{BearerToken, FromPlain};
 use http::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, COOKIE};
 use serde::de::DeserializeOwned;
@@ -7,6 +7,7 @@ use std::collections::BTreeSet;
 use std::error;
 use std::marker::PhantomData;
 
+use crate::private::json;
 use crate::server::{
     AsyncResponse, AsyncVisitResponse, AsyncWriteBody, Response, VisitRequestBody, VisitResponse,
     WriteBody,
@@ -236,7 +237,7 @@ where
         D: Deserializer<'de>,
         D::Error: Into<Box<dyn error::Error + Sync + Send>>,
     {
-        T::deserialize(deserializer).map_err(|e| Error::service(e, InvalidArgument::new()))
+        T::deserialize(deserializer).map_err(deserialize_error)
     }
 }
 
@@ -267,10 +268,29 @@ where
         D: Deserializer<'de>,
         D::Error: Into<Box<dyn error::Error + Sync + Send>>,
     {
-        T::deserialize(deserializer).map_err(|e| Error::service(e, InvalidArgument::new()))
+        T::deserialize(deserializer).map_err(deserialize_error)
     }
 }
 
+fn deserialize_error<E>(e: E) -> Error
+where
+    E: Into<Box<dyn error::Error + Sync + Send>>,
+{
+    let e = e.into();
+    let (path, limit) = match e.downcast_ref::<json::Error>() {
+        Some(e) => (e.safe_path().map(ToString::to_string), e.limit()),
+        None => (None, None),
+    };
+    let mut error = match limit {
+        Some(json::Limit::Bytes) => Error::service(e, RequestEntityTooLarge::new()),
+        _ => Error::service(e, InvalidArgument::new()),
+    };
+    if let Some(path) = path {
+        error = error.with_safe_param("path", path);
+    }
+    error
+}
+
 pub struct BinaryRequestBodyVisitor;
 
 impl<T> VisitRequestBody<T> for BinaryRequestBodyVisitor {
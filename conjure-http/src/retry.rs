@@ -0,0 +1,540 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! A client decorator which retries requests failing with QoS errors.
+//!
+//! The `RetryingClient` wraps another `Client` or `AsyncClient`, and retries requests with exponential backoff when
+//! they fail with `Throttle` or `Unavailable` errors.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use conjure_http::retry::RetryingClient;
+//!
+//! let client = RetryingClient::new(HyperClient::new(uri))
+//!     .max_attempts(3)
+//!     .sleep(tokio::time::delay_for);
+//! let client = MyServiceAsyncClient::new(client);
+//!
+//! let response = client.my_endpoint("hello world").await?;
+//! ```
+use crate::client::{
+    Accept, AsyncClient, AsyncRequestBody, AsyncVisitRequestBody, AsyncWriteBody, Client, Endpoint,
+    RequestBody, Sleep, VisitRequestBody, VisitResponse, WriteBody,
+};
+use crate::value::Value;
+use crate::{PathParams, QueryParams};
+use async_trait::async_trait;
+use conjure_error::{Error, ErrorKind};
+use http::HeaderMap;
+use serde::{Deserializer, Serialize};
+use std::collections::hash_map::RandomState;
+use std::error;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// A client which retries requests that fail with QoS errors.
+///
+/// Requests failing with `Throttle` or `Unavailable` errors are retried up to a maximum number of attempts. The client
+/// waits between attempts for the duration requested by a `Throttle` error if present, and otherwise for a random
+/// duration up to an exponentially increasing limit. Other errors are returned immediately.
+///
+/// Serializable request bodies are captured in memory so they can be sent again, and serialize identically on every
+/// attempt. Binary request bodies are reset between attempts, and the last error is returned if a body cannot be
+/// reset.
+///
+/// The client implements `Client` for any `Client`, sleeping the calling thread between attempts. It implements
+/// `AsyncClient` for any `AsyncClient` once a `Sleep` implementation has been provided with the `sleep` method.
+#[derive(Clone, Debug)]
+pub struct RetryingClient<C, S = ()> {
+    inner: C,
+    sleep: S,
+    max_attempts: u32,
+    backoff_slot_size: Duration,
+    max_backoff: Duration,
+    retry_budget: Option<Duration>,
+}
+
+impl<C> RetryingClient<C> {
+    /// Creates a new client wrapping another.
+    ///
+    /// By default, requests are attempted up to 4 times with a backoff slot size of 250 milliseconds and a maximum
+    /// backoff of 30 seconds.
+    pub fn new(inner: C) -> RetryingClient<C> {
+        RetryingClient {
+            inner,
+            sleep: (),
+            max_attempts: 4,
+            backoff_slot_size: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(30),
+            retry_budget: None,
+        }
+    }
+}
+
+impl<C, S> RetryingClient<C, S> {
+    /// Sets the source of delays used by the `AsyncClient` implementation.
+    pub fn sleep<T>(self, sleep: T) -> RetryingClient<C, T>
+    where
+        T: Sleep,
+    {
+        RetryingClient {
+            inner: self.inner,
+            sleep,
+            max_attempts: self.max_attempts,
+            backoff_slot_size: self.backoff_slot_size,
+            max_backoff: self.max_backoff,
+            retry_budget: self.retry_budget,
+        }
+    }
+
+    /// Sets the maximum number of times a request will be attempted, including the initial attempt.
+    pub fn max_attempts(mut self, max_attempts: u32) -> RetryingClient<C, S> {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Sets the base duration of the exponential backoff between attempts.
+    ///
+    /// The delay before the `n`th retry is chosen uniformly at random between zero and `backoff_slot_size * 2^(n - 1)`.
+    pub fn backoff_slot_size(mut self, backoff_slot_size: Duration) -> RetryingClient<C, S> {
+        self.backoff_slot_size = backoff_slot_size;
+        self
+    }
+
+    /// Sets the upper bound of the exponential backoff between attempts.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> RetryingClient<C, S> {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Sets the maximum total time a single request may spend waiting between attempts.
+    ///
+    /// The last error is returned if waiting for the next attempt would exceed the budget. There is no budget by
+    /// default.
+    pub fn retry_budget(mut self, retry_budget: Duration) -> RetryingClient<C, S> {
+        self.retry_budget = Some(retry_budget);
+        self
+    }
+
+    /// Returns a shared reference to the inner client.
+    pub fn get_ref(&self) -> &C {
+        &self.inner
+    }
+
+    fn attempts(&self) -> Attempts<'_, C, S> {
+        Attempts {
+            client: self,
+            attempt: 1,
+            waited: Duration::from_secs(0),
+        }
+    }
+}
+
+impl<C, S> Client for RetryingClient<C, S>
+where
+    C: Client,
+    C::BinaryWriter: 'static,
+{
+    type BinaryWriter = C::BinaryWriter;
+    type BinaryBody = C::BinaryBody;
+
+    fn request<'a, T, U>(
+        &self,
+        endpoint: &'static Endpoint,
+        path_params: PathParams,
+        query_params: QueryParams,
+        headers: HeaderMap,
+        body: T,
+        response_visitor: U,
+    ) -> Result<U::Output, Error>
+    where
+        T: RequestBody<'a, C::BinaryWriter>,
+        U: VisitResponse<C::BinaryBody> + Send,
+    {
+        let body = body.accept(CaptureBodyVisitor)?;
+        let response_visitor = SharedVisitor(Arc::new(Mutex::new(Some(response_visitor))));
+        let mut attempts = self.attempts();
+
+        loop {
+            let error = match self.inner.request(
+                endpoint,
+                path_params.clone(),
+                query_params.clone(),
+                headers.clone(),
+                body.clone(),
+                response_visitor.clone(),
+            ) {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+
+            let backoff = match attempts.next(&error, &response_visitor) {
+                Some(backoff) => backoff,
+                None => return Err(error),
+            };
+
+            if let Body::Binary(body) = &body {
+                let reset = match &mut *body.lock().unwrap() {
+                    Some(body) => body.reset(),
+                    None => false,
+                };
+                if !reset {
+                    return Err(error);
+                }
+            }
+
+            thread::sleep(backoff);
+        }
+    }
+}
+
+impl<C, S> AsyncClient for RetryingClient<C, S>
+where
+    C: AsyncClient + Sync + Send,
+    C::BinaryWriter: Send + 'static,
+    S: Sleep,
+{
+    type BinaryWriter = C::BinaryWriter;
+    type BinaryBody = C::BinaryBody;
+
+    fn request<'a, T, U>(
+        &'a self,
+        endpoint: &'static Endpoint,
+        path_params: PathParams,
+        query_params: QueryParams,
+        headers: HeaderMap,
+        body: T,
+        response_visitor: U,
+    ) -> Pin<Box<dyn Future<Output = Result<U::Output, Error>> + Send + 'a>>
+    where
+        T: AsyncRequestBody<'a, C::BinaryWriter> + Send + 'a,
+        U: VisitResponse<C::BinaryBody> + Send + 'a,
+    {
+        Box::pin(async move {
+            let body = body.accept(CaptureBodyVisitor)?;
+            let response_visitor = SharedVisitor(Arc::new(Mutex::new(Some(response_visitor))));
+            let mut attempts = self.attempts();
+
+            loop {
+                let error = match self
+                    .inner
+                    .request(
+                        endpoint,
+                        path_params.clone(),
+                        query_params.clone(),
+                        headers.clone(),
+                        body.clone(),
+                        response_visitor.clone(),
+                    )
+                    .await
+                {
+                    Ok(response) => return Ok(response),
+                    Err(error) => error,
+                };
+
+                let backoff = match attempts.next(&error, &response_visitor) {
+                    Some(backoff) => backoff,
+                    None => return Err(error),
+                };
+
+                if let Body::Binary(slot) = &body {
+                    // the body can't be locked across the await, so take it out of the slot while resetting
+                    let taken = slot.lock().unwrap().take();
+                    let mut body = match taken {
+                        Some(body) => body,
+                        None => return Err(error),
+                    };
+                    let reset = body.as_mut().reset().await;
+                    *slot.lock().unwrap() = Some(body);
+                    if !reset {
+                        return Err(error);
+                    }
+                }
+
+                self.sleep.sleep(backoff).await;
+            }
+        })
+    }
+}
+
+// the retry state of a single request
+struct Attempts<'a, C, S> {
+    client: &'a RetryingClient<C, S>,
+    attempt: u32,
+    waited: Duration,
+}
+
+impl<C, S> Attempts<'_, C, S> {
+    // returns the time to wait before the next attempt, or `None` if the request shouldn't be retried
+    fn next<U>(&mut self, error: &Error, response_visitor: &SharedVisitor<U>) -> Option<Duration> {
+        if self.attempt >= self.client.max_attempts {
+            return None;
+        }
+
+        // the response visitor is consumed if the request failed while processing a successful response
+        if response_visitor.0.lock().unwrap().is_none() {
+            return None;
+        }
+
+        let backoff = match error.kind() {
+            ErrorKind::Throttle(e) => match e.duration() {
+                Some(duration) => duration,
+                None => self.backoff(),
+            },
+            ErrorKind::Unavailable(_) => self.backoff(),
+            _ => return None,
+        };
+
+        let waited = self.waited + backoff;
+        if let Some(retry_budget) = self.client.retry_budget {
+            if waited > retry_budget {
+                return None;
+            }
+        }
+
+        self.attempt += 1;
+        self.waited = waited;
+        Some(backoff)
+    }
+
+    fn backoff(&self) -> Duration {
+        let limit = 1u32
+            .checked_shl(self.attempt - 1)
+            .and_then(|scale| self.client.backoff_slot_size.checked_mul(scale))
+            .unwrap_or(self.client.max_backoff)
+            .min(self.client.max_backoff);
+        jitter(limit)
+    }
+}
+
+// picks a duration uniformly at random between zero and the limit, using std's randomly keyed hasher as a source of
+// randomness
+fn jitter(limit: Duration) -> Duration {
+    let random = RandomState::new().build_hasher().finish();
+    limit.mul_f64((random >> 11) as f64 / (1u64 << 53) as f64)
+}
+
+// a request body which can be sent more than once
+pub(crate) enum Body<B> {
+    Empty,
+    Serializable(Value),
+    Binary(Arc<Mutex<Option<B>>>),
+}
+
+impl<B> Clone for Body<B> {
+    fn clone(&self) -> Self {
+        match self {
+            Body::Empty => Body::Empty,
+            Body::Serializable(body) => Body::Serializable(body.clone()),
+            Body::Binary(body) => Body::Binary(body.clone()),
+        }
+    }
+}
+
+type BinaryBody<'a, W> = Box<dyn WriteBody<W> + 'a>;
+
+pub(crate) type AsyncBinaryBody<'a, W> = Pin<Box<dyn AsyncWriteBody<W> + Sync + Send + 'a>>;
+
+impl<'a, W> RequestBody<'a, W> for Body<BinaryBody<'a, W>>
+where
+    W: 'a,
+{
+    fn accept<V>(self, visitor: V) -> V::Output
+    where
+        V: VisitRequestBody<'a, W>,
+    {
+        match self {
+            Body::Empty => visitor.visit_empty(),
+            Body::Serializable(body) => visitor.visit_serializable(body),
+            Body::Binary(slot) => visitor.visit_binary(Lease::new(slot)),
+        }
+    }
+}
+
+impl<'a, W> AsyncRequestBody<'a, W> for Body<AsyncBinaryBody<'a, W>>
+where
+    W: Send + 'a,
+{
+    fn accept<V>(self, visitor: V) -> V::Output
+    where
+        V: AsyncVisitRequestBody<'a, W>,
+    {
+        match self {
+            Body::Empty => visitor.visit_empty(),
+            Body::Serializable(body) => visitor.visit_serializable(body),
+            Body::Binary(slot) => visitor.visit_binary(Lease::new(slot)),
+        }
+    }
+}
+
+pub(crate) struct CaptureBodyVisitor;
+
+impl<'a, W> VisitRequestBody<'a, W> for CaptureBodyVisitor {
+    type Output = Result<Body<BinaryBody<'a, W>>, Error>;
+
+    fn visit_empty(self) -> Self::Output {
+        Ok(Body::Empty)
+    }
+
+    fn visit_serializable<T>(self, body: T) -> Self::Output
+    where
+        T: Serialize + 'a,
+    {
+        let body = Value::new(body).map_err(Error::internal)?;
+        Ok(Body::Serializable(body))
+    }
+
+    fn visit_binary<T>(self, body: T) -> Self::Output
+    where
+        T: WriteBody<W> + 'a,
+    {
+        Ok(Body::Binary(Arc::new(Mutex::new(Some(Box::new(body))))))
+    }
+}
+
+impl<'a, W> AsyncVisitRequestBody<'a, W> for CaptureBodyVisitor {
+    type Output = Result<Body<AsyncBinaryBody<'a, W>>, Error>;
+
+    fn visit_empty(self) -> Self::Output {
+        Ok(Body::Empty)
+    }
+
+    fn visit_serializable<T>(self, body: T) -> Self::Output
+    where
+        T: Serialize + 'a,
+    {
+        let body = Value::new(body).map_err(Error::internal)?;
+        Ok(Body::Serializable(body))
+    }
+
+    fn visit_binary<T>(self, body: T) -> Self::Output
+    where
+        T: AsyncWriteBody<W> + Sync + Send + 'a,
+    {
+        Ok(Body::Binary(Arc::new(Mutex::new(Some(Box::pin(body))))))
+    }
+}
+
+// a binary body lent to a single attempt, which is returned to its slot when the attempt is done with it
+struct Lease<B> {
+    body: Option<B>,
+    slot: Arc<Mutex<Option<B>>>,
+}
+
+impl<B> Lease<B> {
+    fn new(slot: Arc<Mutex<Option<B>>>) -> Lease<B> {
+        let body = slot.lock().unwrap().take();
+        Lease { body, slot }
+    }
+}
+
+impl<B> Drop for Lease<B> {
+    fn drop(&mut self) {
+        if let Some(body) = self.body.take() {
+            *self.slot.lock().unwrap() = Some(body);
+        }
+    }
+}
+
+impl<'a, W> WriteBody<W> for Lease<BinaryBody<'a, W>> {
+    fn write_body(&mut self, w: &mut W) -> Result<(), Error> {
+        match &mut self.body {
+            Some(body) => body.write_body(w),
+            None => Err(Error::internal_safe("request body is not available")),
+        }
+    }
+
+    fn reset(&mut self) -> bool {
+        match &mut self.body {
+            Some(body) => body.reset(),
+            None => false,
+        }
+    }
+}
+
+#[async_trait]
+impl<'a, W> AsyncWriteBody<W> for Lease<AsyncBinaryBody<'a, W>>
+where
+    W: Send,
+{
+    async fn write_body(self: Pin<&mut Self>, w: Pin<&mut W>) -> Result<(), Error> {
+        match &mut self.get_mut().body {
+            Some(body) => body.as_mut().write_body(w).await,
+            None => Err(Error::internal_safe("request body is not available")),
+        }
+    }
+
+    async fn reset(self: Pin<&mut Self>) -> bool
+    where
+        W: 'async_trait,
+    {
+        match &mut self.get_mut().body {
+            Some(body) => body.as_mut().reset().await,
+            None => false,
+        }
+    }
+}
+
+// a response visitor shared between attempts, which is consumed by the attempt that receives a response
+struct SharedVisitor<U>(Arc<Mutex<Option<U>>>);
+
+impl<U> Clone for SharedVisitor<U> {
+    fn clone(&self) -> Self {
+        SharedVisitor(self.0.clone())
+    }
+}
+
+impl<U> SharedVisitor<U> {
+    fn take(&self) -> Result<U, Error> {
+        self.0
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or_else(|| Error::internal_safe("response visitor already consumed"))
+    }
+}
+
+impl<T, U> VisitResponse<T> for SharedVisitor<U>
+where
+    U: VisitResponse<T>,
+{
+    type Output = U::Output;
+
+    fn accept(&self) -> Accept {
+        match &*self.0.lock().unwrap() {
+            Some(visitor) => visitor.accept(),
+            None => Accept::Empty,
+        }
+    }
+
+    fn visit_empty(self) -> Result<U::Output, Error> {
+        self.take()?.visit_empty()
+    }
+
+    fn visit_serializable<'de, D>(self, deserializer: D) -> Result<U::Output, Error>
+    where
+        D: Deserializer<'de>,
+        D::Error: Into<Box<dyn error::Error + Sync + Send>>,
+    {
+        self.take()?.visit_serializable(deserializer)
+    }
+
+    fn visit_binary(self, body: T) -> Result<U::Output, Error> {
+        self.take()?.visit_binary(body)
+    }
+}
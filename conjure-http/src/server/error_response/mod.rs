@@ -0,0 +1,106 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Encoding of errors into HTTP responses.
+use conjure_error::{Error, ErrorKind, Internal, SerializableError};
+use conjure_serde::json;
+use http::header::{HeaderValue, CONTENT_TYPE, RETRY_AFTER};
+use http::{HeaderMap, StatusCode};
+
+#[cfg(test)]
+mod test;
+
+/// The HTTP response a server should send for an error.
+#[derive(Clone, Debug)]
+pub struct ErrorResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl ErrorResponse {
+    /// Returns the response's status code.
+    #[inline]
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Returns the response's headers.
+    #[inline]
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Returns the response's body.
+    ///
+    /// The body is empty for errors other than service errors.
+    #[inline]
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Consumes the response, returning its status code, headers, and body.
+    #[inline]
+    pub fn into_parts(self) -> (StatusCode, HeaderMap, Vec<u8>) {
+        (self.status, self.headers, self.body)
+    }
+}
+
+/// Encodes an error into the HTTP response a server should send for it.
+///
+/// * Service errors produce a response with the status code of the error's `ErrorCode`, and the JSON-serialized
+///   `SerializableError` as the body. The error's instance ID is preserved, including for errors propagated from
+///   another service.
+/// * Throttle errors produce a `429 Too Many Requests` response. If the error has a duration, it is included in a
+///   `Retry-After` header, rounded up to the nearest second.
+/// * Unavailable errors produce a `503 Service Unavailable` response.
+/// * Any other error produces a `500 Internal Server Error` response with the body of an `Internal` service error with
+///   a fresh instance ID.
+pub fn encode_error(error: &Error) -> ErrorResponse {
+    let mut headers = HeaderMap::new();
+
+    let (status, body) = match error.kind() {
+        ErrorKind::Service(error) => {
+            let status = StatusCode::from_u16(error.error_code().status_code())
+                .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            (status, serialize(&mut headers, error))
+        }
+        ErrorKind::Throttle(error) => {
+            if let Some(duration) = error.duration() {
+                // Retry-After has a resolution of seconds, so round up to avoid advising an immediate retry
+                let secs = duration.as_secs() + u64::from(duration.subsec_nanos() > 0);
+                headers.insert(RETRY_AFTER, HeaderValue::from(secs));
+            }
+            (StatusCode::TOO_MANY_REQUESTS, vec![])
+        }
+        ErrorKind::Unavailable(_) => (StatusCode::SERVICE_UNAVAILABLE, vec![]),
+        _ => {
+            let error = conjure_error::encode(&Internal::new());
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                serialize(&mut headers, &error),
+            )
+        }
+    };
+
+    ErrorResponse {
+        status,
+        headers,
+        body,
+    }
+}
+
+fn serialize(headers: &mut HeaderMap, error: &SerializableError) -> Vec<u8> {
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    json::to_vec(error).expect("serializing a SerializableError can't fail")
+}
@@ -0,0 +1,83 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::server::encode_error;
+use conjure_error::{Error, ErrorCode, ErrorKind, InvalidArgument, SerializableError};
+use conjure_object::Uuid;
+use conjure_serde::json;
+use http::StatusCode;
+use std::time::Duration;
+
+fn decode_body(body: &[u8]) -> SerializableError {
+    json::client_from_slice(body).unwrap()
+}
+
+#[test]
+fn service_error() {
+    let error = Error::service_safe("bad", InvalidArgument::new());
+    let response = encode_error(&error);
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(response.headers()["Content-Type"], "application/json");
+    let expected = match error.kind() {
+        ErrorKind::Service(e) => e,
+        kind => panic!("unexpected error kind {:?}", kind),
+    };
+    assert_eq!(decode_body(response.body()), *expected);
+}
+
+#[test]
+fn propagated_service_error() {
+    let instance_id = Uuid::new_v4();
+    let remote = SerializableError::builder()
+        .error_code(ErrorCode::NotFound)
+        .error_name("Test:NotFound")
+        .error_instance_id(instance_id)
+        .insert_parameters("foo", "bar")
+        .build();
+    let error = Error::propagated_service_safe("remote error", remote.clone());
+    let response = encode_error(&error);
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body = decode_body(response.body());
+    assert_eq!(body, remote);
+    assert_eq!(body.error_instance_id(), instance_id);
+}
+
+#[test]
+fn throttle() {
+    let response = encode_error(&Error::throttle_safe("slow down"));
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(response.headers().get("Retry-After").is_none());
+    assert!(response.body().is_empty());
+
+    let response = encode_error(&Error::throttle_for_safe(
+        "slow down",
+        Duration::from_secs(3),
+    ));
+    assert_eq!(response.headers()["Retry-After"], "3");
+
+    let response = encode_error(&Error::throttle_for_safe(
+        "slow down",
+        Duration::from_millis(1500),
+    ));
+    assert_eq!(response.headers()["Retry-After"], "2");
+}
+
+#[test]
+fn unavailable() {
+    let response = encode_error(&Error::unavailable_safe("go away"));
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert!(response.headers().is_empty());
+    assert!(response.body().is_empty());
+}
@@ -23,6 +23,12 @@ use std::future::Future;
 use std::io::Write;
 use std::pin::Pin;
 
+pub use self::error_response::{encode_error, ErrorResponse};
+pub use self::router::{Route, Router};
+
+mod error_response;
+mod router;
+
 /// A trait implemented by synchronous endpoint handlers.
 pub trait Handler<T, B, R>
 where
@@ -71,6 +77,7 @@ pub struct Parameter {
     name: &'static str,
     type_: ParameterType,
     safe: bool,
+    markers: &'static [&'static str],
 }
 
 impl Parameter {
@@ -81,6 +88,7 @@ impl Parameter {
             name,
             type_,
             safe: false,
+            markers: &[],
         }
     }
 
@@ -91,6 +99,13 @@ impl Parameter {
         self
     }
 
+    /// Sets the markers of the parameter.
+    #[inline]
+    pub const fn with_markers(mut self, markers: &'static [&'static str]) -> Parameter {
+        self.markers = markers;
+        self
+    }
+
     /// Returns the name of the parameter.
     #[inline]
     pub fn name(&self) -> &'static str {
@@ -108,6 +123,12 @@ impl Parameter {
     pub fn safe(&self) -> bool {
         self.safe
     }
+
+    /// Returns the fully qualified names of the parameter's marker types.
+    #[inline]
+    pub fn markers(&self) -> &'static [&'static str] {
+        self.markers
+    }
 }
 
 /// The specific type of a parameter.
@@ -180,6 +201,7 @@ pub struct Metadata {
     path: &'static str,
     parameters: &'static [Parameter],
     deprecated: bool,
+    markers: &'static [&'static str],
 }
 
 impl Metadata {
@@ -198,9 +220,17 @@ impl Metadata {
             path,
             parameters,
             deprecated,
+            markers: &[],
         }
     }
 
+    /// Sets the endpoint's markers.
+    #[inline]
+    pub const fn with_markers(mut self, markers: &'static [&'static str]) -> Metadata {
+        self.markers = markers;
+        self
+    }
+
     /// Returns the endpoint's name.
     #[inline]
     pub const fn name(&self) -> &'static str {
@@ -230,6 +260,15 @@ impl Metadata {
     pub const fn deprecated(&self) -> bool {
         self.deprecated
     }
+
+    /// Returns the fully qualified names of the endpoint's marker types.
+    ///
+    /// Markers are Conjure types or external imports attached to an endpoint definition. They carry no behavior of
+    /// their own, but can be used to drive things like idempotency or audit logging.
+    #[inline]
+    pub const fn markers(&self) -> &'static [&'static str] {
+        self.markers
+    }
 }
 
 /// A synchronous HTTP endpoint.
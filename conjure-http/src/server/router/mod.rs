@@ -0,0 +1,276 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Path template based request routing.
+use crate::server::Metadata;
+use crate::PathParams;
+use conjure_error::{Error, InvalidArgument};
+use http::Method;
+use percent_encoding::percent_decode_str;
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod test;
+
+/// A router matching requests to endpoints by their HTTP method and path.
+///
+/// Endpoints are registered by their `Metadata`, and the router compiles their path templates into a tree which is
+/// walked when routing a request. Templates consist of literal segments, parameter segments like `{datasetRid}`, and an
+/// optional final greedy parameter like `{branch:.*}` which matches the remainder of the path, including any `/`s.
+///
+/// When a request path matches multiple templates, literal segments are preferred over parameters, and parameters are
+/// preferred over greedy parameters.
+#[derive(Clone, Debug)]
+pub struct Router<T> {
+    root: Node<T>,
+}
+
+impl<T> Default for Router<T> {
+    fn default() -> Router<T> {
+        Router {
+            root: Node::default(),
+        }
+    }
+}
+
+impl<T> Router<T> {
+    /// Creates a new router with no endpoints.
+    pub fn new() -> Router<T> {
+        Router::default()
+    }
+
+    /// Registers an endpoint with the router, associating it with a value which will be returned when a request is
+    /// routed to it.
+    ///
+    /// Returns an error if the endpoint's path template is invalid, or if it conflicts with a previously registered
+    /// endpoint. Two endpoints conflict if they have the same method and their templates match exactly the same paths.
+    pub fn insert(&mut self, metadata: &Metadata, value: T) -> Result<(), Error> {
+        let template = metadata.path();
+        let invalid = |reason| {
+            Error::internal_safe(reason)
+                .with_safe_param("method", metadata.method().as_str())
+                .with_safe_param("path", template)
+        };
+
+        if !template.starts_with('/') {
+            return Err(invalid("path template must start with `/`"));
+        }
+
+        let mut node = &mut self.root;
+        let mut names = vec![];
+        let mut greedy = false;
+
+        for segment in template[1..].split('/') {
+            if greedy {
+                return Err(invalid(
+                    "greedy parameters must be the last segment of a path",
+                ));
+            }
+
+            if !segment.starts_with('{') && !segment.ends_with('}') {
+                if segment.contains(&['{', '}'][..]) {
+                    return Err(invalid("parameters must span an entire path segment"));
+                }
+                node = node.literals.entry(segment).or_default();
+                continue;
+            }
+
+            if !segment.starts_with('{') || !segment.ends_with('}') {
+                return Err(invalid("parameters must span an entire path segment"));
+            }
+
+            let param = &segment[1..segment.len() - 1];
+            let name = match param.find(':') {
+                Some(idx) => {
+                    if &param[idx + 1..] != ".*" {
+                        return Err(invalid("`.*` is the only supported parameter pattern"));
+                    }
+                    greedy = true;
+                    &param[..idx]
+                }
+                None => param,
+            };
+            if name.is_empty() {
+                return Err(invalid("parameters must be named"));
+            }
+            if names.contains(&name) {
+                return Err(invalid("parameter names must be unique"));
+            }
+            names.push(name);
+
+            if !greedy {
+                node = node.parameter.get_or_insert_with(Box::default);
+            }
+        }
+
+        let leaves = if greedy {
+            &mut node.greedy
+        } else {
+            &mut node.leaves
+        };
+
+        if let Some(leaf) = leaves.iter().find(|l| l.method == *metadata.method()) {
+            return Err(invalid("path template conflicts with another endpoint")
+                .with_safe_param("conflictingPath", leaf.template));
+        }
+
+        leaves.push(Leaf {
+            method: metadata.method().clone(),
+            template,
+            names,
+            value,
+        });
+
+        Ok(())
+    }
+
+    /// Routes a request to an endpoint.
+    ///
+    /// Returns an error if the request's path matches an endpoint but its path parameters could not be
+    /// percent-decoded.
+    pub fn route(&self, method: &Method, path: &str) -> Result<Route<'_, T>, Error> {
+        if !path.starts_with('/') {
+            return Ok(Route::NotFound);
+        }
+
+        let mut allowed = vec![];
+        let mut found = None;
+        self.root
+            .search(Some(&path[1..]), &mut vec![], &mut |leaf, captures| {
+                if leaf.method == *method {
+                    found = Some((leaf, captures.to_vec()));
+                    return true;
+                }
+
+                if !allowed.contains(&leaf.method) {
+                    allowed.push(leaf.method.clone());
+                }
+                false
+            });
+
+        let (leaf, captures) = match found {
+            Some(found) => found,
+            None if allowed.is_empty() => return Ok(Route::NotFound),
+            None => return Ok(Route::MethodNotAllowed { allowed }),
+        };
+
+        let mut path_params = PathParams::new();
+        for (name, value) in leaf.names.iter().zip(captures) {
+            let value = percent_decode_str(value).decode_utf8().map_err(|e| {
+                Error::service_safe(e, InvalidArgument::new()).with_safe_param("parameter", *name)
+            })?;
+            path_params.insert(*name, value);
+        }
+
+        Ok(Route::Matched {
+            value: &leaf.value,
+            path_params,
+        })
+    }
+}
+
+/// The result of routing a request.
+#[derive(Debug)]
+pub enum Route<'a, T> {
+    /// The request matched an endpoint.
+    Matched {
+        /// The value associated with the endpoint.
+        value: &'a T,
+        /// The request's percent-decoded path parameters.
+        path_params: PathParams,
+    },
+    /// The request's path matched one or more endpoints, but none with the request's method.
+    ///
+    /// Servers should respond with a `405 Method Not Allowed` status code and an `Allow` header.
+    MethodNotAllowed {
+        /// The methods of the endpoints matching the request's path.
+        allowed: Vec<Method>,
+    },
+    /// The request's path did not match any endpoint.
+    NotFound,
+}
+
+#[derive(Clone, Debug)]
+struct Node<T> {
+    literals: HashMap<&'static str, Node<T>>,
+    parameter: Option<Box<Node<T>>>,
+    // endpoints with a greedy parameter matching the remainder of the path from this node
+    greedy: Vec<Leaf<T>>,
+    // endpoints whose paths end at this node
+    leaves: Vec<Leaf<T>>,
+}
+
+impl<T> Default for Node<T> {
+    fn default() -> Node<T> {
+        Node {
+            literals: HashMap::new(),
+            parameter: None,
+            greedy: vec![],
+            leaves: vec![],
+        }
+    }
+}
+
+impl<T> Node<T> {
+    // Calls `f` with each endpoint matching the path in order of precedence, until it returns `true`.
+    fn search<'a, 'b>(
+        &'a self,
+        rest: Option<&'b str>,
+        captures: &mut Vec<&'b str>,
+        f: &mut dyn FnMut(&'a Leaf<T>, &[&'b str]) -> bool,
+    ) -> bool {
+        let rest = match rest {
+            Some(rest) => rest,
+            None => return self.leaves.iter().any(|leaf| f(leaf, captures)),
+        };
+
+        let (segment, tail) = match rest.find('/') {
+            Some(idx) => (&rest[..idx], Some(&rest[idx + 1..])),
+            None => (rest, None),
+        };
+
+        if let Some(node) = self.literals.get(segment) {
+            if node.search(tail, captures, f) {
+                return true;
+            }
+        }
+
+        if !segment.is_empty() {
+            if let Some(node) = &self.parameter {
+                captures.push(segment);
+                if node.search(tail, captures, f) {
+                    return true;
+                }
+                captures.pop();
+            }
+        }
+
+        if !rest.is_empty() {
+            captures.push(rest);
+            if self.greedy.iter().any(|leaf| f(leaf, captures)) {
+                return true;
+            }
+            captures.pop();
+        }
+
+        false
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Leaf<T> {
+    method: Method,
+    template: &'static str,
+    names: Vec<&'static str>,
+    value: T,
+}
@@ -0,0 +1,241 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::server::{Metadata, Route, Router};
+use crate::PathParams;
+use conjure_error::{ErrorCode, ErrorKind};
+use http::Method;
+
+fn metadata(method: Method, path: &'static str) -> Metadata {
+    Metadata::new("endpoint", method, path, &[], false)
+}
+
+fn router(routes: &[(Method, &'static str)]) -> Router<usize> {
+    let mut router = Router::new();
+    for (i, (method, path)) in routes.iter().enumerate() {
+        router.insert(&metadata(method.clone(), path), i).unwrap();
+    }
+    router
+}
+
+fn matched(router: &Router<usize>, method: Method, path: &str) -> (usize, PathParams) {
+    match router.route(&method, path).unwrap() {
+        Route::Matched { value, path_params } => (*value, path_params),
+        route => panic!("unexpected route {:?}", route),
+    }
+}
+
+fn params(params: &[(&str, &str)]) -> PathParams {
+    let mut path_params = PathParams::new();
+    for (key, value) in params {
+        path_params.insert(*key, *value);
+    }
+    path_params
+}
+
+#[test]
+fn literals() {
+    let router = router(&[
+        (Method::GET, "/catalog/datasets"),
+        (Method::GET, "/catalog/branches"),
+        (Method::GET, "/"),
+    ]);
+
+    assert_eq!(
+        matched(&router, Method::GET, "/catalog/datasets"),
+        (0, params(&[]))
+    );
+    assert_eq!(
+        matched(&router, Method::GET, "/catalog/branches"),
+        (1, params(&[]))
+    );
+    assert_eq!(matched(&router, Method::GET, "/"), (2, params(&[])));
+
+    for path in &[
+        "/catalog",
+        "/catalog/datasets/",
+        "/catalog/other",
+        "",
+        "catalog/datasets",
+    ] {
+        match router.route(&Method::GET, path).unwrap() {
+            Route::NotFound => {}
+            route => panic!("unexpected route {:?} for {}", route, path),
+        }
+    }
+}
+
+#[test]
+fn parameters() {
+    let router = router(&[
+        (Method::GET, "/catalog/datasets/{datasetRid}"),
+        (
+            Method::GET,
+            "/catalog/datasets/{datasetRid}/branches/{branch}",
+        ),
+    ]);
+
+    assert_eq!(
+        matched(&router, Method::GET, "/catalog/datasets/ri.foo"),
+        (0, params(&[("datasetRid", "ri.foo")]))
+    );
+    assert_eq!(
+        matched(
+            &router,
+            Method::GET,
+            "/catalog/datasets/ri.foo/branches/master"
+        ),
+        (1, params(&[("datasetRid", "ri.foo"), ("branch", "master")]))
+    );
+
+    match router.route(&Method::GET, "/catalog/datasets/").unwrap() {
+        Route::NotFound => {}
+        route => panic!("unexpected route {:?}", route),
+    }
+}
+
+#[test]
+fn greedy_parameters() {
+    let router = router(&[(
+        Method::GET,
+        "/catalog/datasets/{datasetRid}/branches/{branch:.*}",
+    )]);
+
+    assert_eq!(
+        matched(
+            &router,
+            Method::GET,
+            "/catalog/datasets/ri.foo/branches/feature/foo"
+        ),
+        (
+            0,
+            params(&[("datasetRid", "ri.foo"), ("branch", "feature/foo")])
+        )
+    );
+
+    match router
+        .route(&Method::GET, "/catalog/datasets/ri.foo/branches/")
+        .unwrap()
+    {
+        Route::NotFound => {}
+        route => panic!("unexpected route {:?}", route),
+    }
+}
+
+#[test]
+fn percent_decoding() {
+    let router = router(&[(Method::GET, "/files/{name}/{path:.*}")]);
+
+    assert_eq!(
+        matched(&router, Method::GET, "/files/hello%20world%2F/a%2Fb/c%3F"),
+        (0, params(&[("name", "hello world/"), ("path", "a/b/c?")]))
+    );
+
+    let error = router.route(&Method::GET, "/files/%FF/foo").err().unwrap();
+    match error.kind() {
+        ErrorKind::Service(e) => assert_eq!(*e.error_code(), ErrorCode::InvalidArgument),
+        kind => panic!("unexpected error kind {:?}", kind),
+    }
+}
+
+#[test]
+fn precedence() {
+    let router = router(&[
+        (Method::GET, "/foo/{path:.*}"),
+        (Method::GET, "/foo/{id}/bar"),
+        (Method::GET, "/foo/baz/bar"),
+        (Method::GET, "/foo/baz/{id}"),
+    ]);
+
+    assert_eq!(
+        matched(&router, Method::GET, "/foo/baz/bar"),
+        (2, params(&[]))
+    );
+    assert_eq!(
+        matched(&router, Method::GET, "/foo/baz/qux"),
+        (3, params(&[("id", "qux")]))
+    );
+    assert_eq!(
+        matched(&router, Method::GET, "/foo/qux/bar"),
+        (1, params(&[("id", "qux")]))
+    );
+    assert_eq!(
+        matched(&router, Method::GET, "/foo/qux/bar/baz"),
+        (0, params(&[("path", "qux/bar/baz")]))
+    );
+}
+
+#[test]
+fn methods() {
+    let router = router(&[
+        (Method::GET, "/foo/{id}"),
+        (Method::PUT, "/foo/{id}"),
+        (Method::POST, "/foo/bar"),
+    ]);
+
+    assert_eq!(
+        matched(&router, Method::PUT, "/foo/bar"),
+        (1, params(&[("id", "bar")]))
+    );
+    assert_eq!(matched(&router, Method::POST, "/foo/bar"), (2, params(&[])));
+
+    match router.route(&Method::DELETE, "/foo/bar").unwrap() {
+        Route::MethodNotAllowed { allowed } => {
+            assert_eq!(allowed, vec![Method::POST, Method::GET, Method::PUT])
+        }
+        route => panic!("unexpected route {:?}", route),
+    }
+}
+
+#[test]
+fn conflicts() {
+    let mut router = router(&[(Method::GET, "/foo/{id}"), (Method::GET, "/bar/{path:.*}")]);
+
+    assert!(router
+        .insert(&metadata(Method::GET, "/foo/{name}"), 2)
+        .is_err());
+    assert!(router
+        .insert(&metadata(Method::GET, "/bar/{rest:.*}"), 2)
+        .is_err());
+
+    router
+        .insert(&metadata(Method::POST, "/foo/{name}"), 2)
+        .unwrap();
+    router
+        .insert(&metadata(Method::GET, "/foo/bar"), 3)
+        .unwrap();
+    router
+        .insert(&metadata(Method::GET, "/bar/{id}"), 4)
+        .unwrap();
+}
+
+#[test]
+fn invalid_templates() {
+    let mut router = Router::new();
+
+    for path in &[
+        "foo",
+        "/foo/{path:.*}/bar",
+        "/foo/{id:[0-9]+}",
+        "/foo/prefix{id}",
+        "/foo/{id}suffix",
+        "/foo/{}",
+        "/foo/{id}/{id}",
+    ] {
+        assert!(
+            router.insert(&metadata(Method::GET, path), ()).is_err(),
+            "{}",
+            path
+        );
+    }
+}
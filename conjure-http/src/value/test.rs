@@ -0,0 +1,55 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use conjure_object::ByteBuf;
+use conjure_serde::{json, smile};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use crate::value::Value;
+
+fn test_lossless<T>(value: T)
+where
+    T: Serialize,
+{
+    let captured = Value::new(&value).unwrap();
+    assert_eq!(
+        json::to_vec(&captured).unwrap(),
+        json::to_vec(&value).unwrap()
+    );
+    assert_eq!(
+        smile::to_vec(&captured).unwrap(),
+        smile::to_vec(&value).unwrap()
+    );
+}
+
+#[test]
+fn binary() {
+    test_lossless(ByteBuf::from(b"hello world".to_vec()));
+}
+
+#[test]
+fn boolean_keys() {
+    let mut map = BTreeMap::new();
+    map.insert(true, 1);
+    map.insert(false, 2);
+    test_lossless(map);
+}
+
+#[test]
+fn nested() {
+    let mut map = BTreeMap::new();
+    map.insert(1, Some(ByteBuf::from(vec![1, 2, 3])));
+    map.insert(2, None);
+    test_lossless((vec!["hello", "world"], map, 1.5, 'c', ()));
+}
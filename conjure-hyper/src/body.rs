@@ -0,0 +1,181 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Streaming body types shared by the client and server.
+use async_trait::async_trait;
+use bytes::{Buf, Bytes};
+use conjure_error::Error;
+use conjure_http::blocking::{AsyncReadBytes, AsyncWriteBytes};
+use futures::io::{AsyncReadExt, AsyncWriteExt};
+use futures::ready;
+use hyper::body::{HttpBody, Sender};
+use hyper::Body;
+use std::cmp;
+use std::fmt;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A writer used to stream a binary body to the remote side of a connection.
+///
+/// It is passed to `AsyncWriteBody` implementations by both the client and the server, and implements both Tokio's and
+/// the futures crate's `AsyncWrite` traits. It also implements `AsyncWriteBytes`, so a `HyperClient` can be wrapped in
+/// a `BlockingClient`.
+pub struct BodyWriter {
+    sender: Sender,
+}
+
+impl fmt::Debug for BodyWriter {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("BodyWriter").finish()
+    }
+}
+
+impl BodyWriter {
+    pub(crate) fn new(sender: Sender) -> BodyWriter {
+        BodyWriter { sender }
+    }
+
+    // ensures the remote side doesn't see a truncated body as complete
+    pub(crate) fn abort(self) {
+        self.sender.abort();
+    }
+
+    fn poll_write_inner(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        ready!(self.sender.poll_ready(cx))
+            .map_err(|e| io::Error::new(io::ErrorKind::BrokenPipe, e))?;
+        match self.sender.try_send_data(Bytes::copy_from_slice(buf)) {
+            Ok(()) => Poll::Ready(Ok(buf.len())),
+            Err(_) => Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "request body closed",
+            ))),
+        }
+    }
+}
+
+impl tokio::io::AsyncWrite for BodyWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_write_inner(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl futures::io::AsyncWrite for BodyWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_write_inner(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[async_trait]
+impl AsyncWriteBytes for BodyWriter {
+    async fn write_bytes(mut self: Pin<&mut Self>, buf: &[u8]) -> Result<(), Error> {
+        self.write_all(buf).await.map_err(Error::internal_safe)
+    }
+}
+
+/// A streaming binary body received from the remote side of a connection.
+///
+/// It is returned to the client for binary responses and passed to the server for binary requests, and implements both
+/// Tokio's and the futures crate's `AsyncRead` traits. It also implements `AsyncReadBytes`, so a `HyperClient` can be
+/// wrapped in a `BlockingClient`.
+pub struct BodyReader {
+    body: Body,
+    buf: Bytes,
+}
+
+impl fmt::Debug for BodyReader {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("BodyReader").finish()
+    }
+}
+
+impl BodyReader {
+    pub(crate) fn new(body: Body) -> BodyReader {
+        BodyReader {
+            body,
+            buf: Bytes::new(),
+        }
+    }
+
+    // io::Error::other requires Rust 1.74
+    #[allow(clippy::io_other_error)]
+    fn poll_read_inner(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        while self.buf.is_empty() {
+            match ready!(Pin::new(&mut self.body).poll_data(cx)) {
+                Some(Ok(chunk)) => self.buf = chunk,
+                Some(Err(e)) => return Poll::Ready(Err(io::Error::new(io::ErrorKind::Other, e))),
+                None => return Poll::Ready(Ok(0)),
+            }
+        }
+
+        let len = cmp::min(buf.len(), self.buf.len());
+        buf[..len].copy_from_slice(&self.buf[..len]);
+        self.buf.advance(len);
+        Poll::Ready(Ok(len))
+    }
+}
+
+impl tokio::io::AsyncRead for BodyReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_read_inner(cx, buf)
+    }
+}
+
+impl futures::io::AsyncRead for BodyReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_read_inner(cx, buf)
+    }
+}
+
+#[async_trait]
+impl AsyncReadBytes for BodyReader {
+    async fn read_bytes(mut self: Pin<&mut Self>, buf: &mut [u8]) -> io::Result<usize> {
+        self.read(buf).await
+    }
+}
@@ -0,0 +1,344 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! A hyper-based Conjure HTTP client.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use conjure_hyper::client::HyperClient;
+//!
+//! let client = HyperClient::new("https://api.example.com/my-service".parse()?);
+//! let client = MyServiceAsyncClient::new(client);
+//!
+//! let response = client.my_endpoint("hello world").await?;
+//! ```
+use crate::body::{BodyReader, BodyWriter};
+use conjure_error::Error;
+use conjure_http::client::{
+    self, Accept, AsyncClient, AsyncRequestBody, AsyncVisitRequestBody, AsyncWriteBody, Endpoint,
+    VisitResponse,
+};
+use conjure_http::encoding::{self, Encoding};
+use conjure_http::{PathParams, QueryParams};
+use conjure_serde::{json, smile};
+use futures::future;
+use http::header::{HeaderValue, ACCEPT, CONTENT_TYPE};
+use http::uri::{PathAndQuery, Uri};
+use http::{HeaderMap, Request, Response, StatusCode};
+use hyper::body;
+use hyper::client::connect::Connect;
+use hyper::client::HttpConnector;
+use hyper::Body;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::Serialize;
+use std::future::Future;
+use std::pin::Pin;
+
+// everything other than RFC 3986's unreserved characters
+const COMPONENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+const APPLICATION_OCTET_STREAM: &str = "application/octet-stream";
+
+/// An `AsyncClient` implementation which sends requests to a remote server with hyper.
+///
+/// Request URIs are formed by appending the endpoint's path, with its path parameters filled in, and the request's
+/// query parameters to the client's base URI. Serializable request and response bodies are encoded as Conjure JSON by
+/// default, and binary request bodies are streamed to the server as they are written.
+///
+/// Responses with a non-2xx status code are converted into errors: a `429 Too Many Requests` response becomes a
+/// throttle error, a `503 Service Unavailable` response becomes an unavailable error, and any other response with a
+/// `SerializableError` body becomes a propagated service error.
+///
+/// The client must be used from within the context of a Tokio runtime.
+#[derive(Clone, Debug)]
+pub struct HyperClient<C = HttpConnector> {
+    client: hyper::Client<C>,
+    base_uri: Uri,
+    encoding: Encoding,
+}
+
+impl HyperClient {
+    /// Creates a new client which sends requests to the specified base URI over plain HTTP.
+    pub fn new(base_uri: Uri) -> HyperClient {
+        HyperClient::with_client(hyper::Client::new(), base_uri)
+    }
+}
+
+impl<C> HyperClient<C> {
+    /// Creates a new client which sends requests to the specified base URI with an existing hyper `Client`.
+    ///
+    /// This can be used to configure the client's connection pool, or to use a connector supporting HTTPS.
+    pub fn with_client(client: hyper::Client<C>, base_uri: Uri) -> HyperClient<C> {
+        HyperClient {
+            client,
+            base_uri,
+            encoding: Encoding::Json,
+        }
+    }
+
+    /// Sets the preferred encoding of serializable bodies.
+    ///
+    /// Serializable request bodies are encoded with it, and serializable responses are requested in it with JSON as a
+    /// fallback. Responses are decoded according to their `Content-Type` regardless. Defaults to `Encoding::Json`.
+    pub fn encoding(mut self, encoding: Encoding) -> HyperClient<C> {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Returns the client's base URI.
+    pub fn base_uri(&self) -> &Uri {
+        &self.base_uri
+    }
+
+    fn uri(
+        &self,
+        endpoint: &Endpoint,
+        path_params: &PathParams,
+        query_params: &QueryParams,
+    ) -> Result<Uri, Error> {
+        let mut path = self.base_uri.path().trim_end_matches('/').to_string();
+
+        for segment in endpoint.path().split('/').skip(1) {
+            path.push('/');
+
+            if !segment.starts_with('{') || !segment.ends_with('}') {
+                path.push_str(segment);
+                continue;
+            }
+
+            // `{name:.*}` parameters may span multiple segments
+            let param = &segment[1..segment.len() - 1];
+            let (name, greedy) = match param.find(':') {
+                Some(idx) => (&param[..idx], true),
+                None => (param, false),
+            };
+
+            let value = path_params
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value)
+                .ok_or_else(|| {
+                    Error::internal_safe("missing path parameter")
+                        .with_safe_param("path", endpoint.path())
+                        .with_safe_param("parameter", name)
+                })?;
+
+            if greedy {
+                for (i, part) in value.split('/').enumerate() {
+                    if i != 0 {
+                        path.push('/');
+                    }
+                    path.extend(utf8_percent_encode(part, COMPONENT));
+                }
+            } else {
+                path.extend(utf8_percent_encode(value, COMPONENT));
+            }
+        }
+
+        let mut query_params = query_params.iter().collect::<Vec<_>>();
+        query_params.sort_by_key(|(key, _)| *key);
+
+        let mut separator = '?';
+        for (key, values) in query_params {
+            for value in values {
+                path.push(separator);
+                separator = '&';
+                path.extend(utf8_percent_encode(key, COMPONENT));
+                path.push('=');
+                path.extend(utf8_percent_encode(value, COMPONENT));
+            }
+        }
+
+        let mut parts = self.base_uri.clone().into_parts();
+        parts.path_and_query =
+            Some(PathAndQuery::from_maybe_shared(path).map_err(Error::internal_safe)?);
+        Uri::from_parts(parts).map_err(Error::internal_safe)
+    }
+}
+
+impl<C> AsyncClient for HyperClient<C>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    type BinaryWriter = BodyWriter;
+    type BinaryBody = BodyReader;
+
+    fn request<'a, T, U>(
+        &'a self,
+        endpoint: &'static Endpoint,
+        path_params: PathParams,
+        query_params: QueryParams,
+        mut headers: HeaderMap,
+        body: T,
+        response_visitor: U,
+    ) -> Pin<Box<dyn Future<Output = Result<U::Output, Error>> + Send + 'a>>
+    where
+        T: AsyncRequestBody<'a, BodyWriter> + Send + 'a,
+        U: VisitResponse<BodyReader> + Send + 'a,
+    {
+        Box::pin(async move {
+            let uri = self.uri(endpoint, &path_params, &query_params)?;
+
+            match response_visitor.accept() {
+                Accept::Empty => {}
+                Accept::Serializable => {
+                    headers.insert(ACCEPT, encoding::accept(&[self.encoding, Encoding::Json]));
+                }
+                Accept::Binary => {
+                    headers.insert(ACCEPT, HeaderValue::from_static(APPLICATION_OCTET_STREAM));
+                }
+            }
+
+            let visitor = HyperRequestBodyVisitor {
+                encoding: self.encoding,
+            };
+            let (body, writer) = match body.accept(visitor) {
+                HyperRequestBody::Empty => (Body::empty(), None),
+                HyperRequestBody::Serializable(body) => {
+                    headers.insert(CONTENT_TYPE, self.encoding.content_type());
+                    (Body::from(body?), None)
+                }
+                HyperRequestBody::Binary(body) => {
+                    headers.insert(
+                        CONTENT_TYPE,
+                        HeaderValue::from_static(APPLICATION_OCTET_STREAM),
+                    );
+                    let (sender, request_body) = Body::channel();
+                    (request_body, Some((body, BodyWriter::new(sender))))
+                }
+            };
+
+            let mut request = Request::new(body);
+            *request.method_mut() = endpoint.method().clone();
+            *request.uri_mut() = uri;
+            *request.headers_mut() = headers;
+
+            let response = match writer {
+                Some((body, writer)) => {
+                    let (response, write) =
+                        future::join(self.client.request(request), write_body(body, writer)).await;
+                    match response {
+                        Ok(response) => response,
+                        // a failure to write the body will cause the request to fail as well
+                        Err(e) => {
+                            write?;
+                            return Err(Error::internal(e));
+                        }
+                    }
+                }
+                None => self
+                    .client
+                    .request(request)
+                    .await
+                    .map_err(Error::internal)?,
+            };
+
+            if !response.status().is_success() {
+                return Err(decode_error(response).await);
+            }
+
+            visit_response(response, response_visitor).await
+        })
+    }
+}
+
+async fn write_body(
+    mut body: Pin<Box<dyn AsyncWriteBody<BodyWriter> + Sync + Send + '_>>,
+    mut writer: BodyWriter,
+) -> Result<(), Error> {
+    match body.as_mut().write_body(Pin::new(&mut writer)).await {
+        // dropping the sender completes the request body
+        Ok(()) => Ok(()),
+        Err(e) => {
+            writer.abort();
+            Err(e)
+        }
+    }
+}
+
+async fn visit_response<U>(response: Response<Body>, visitor: U) -> Result<U::Output, Error>
+where
+    U: VisitResponse<BodyReader>,
+{
+    if response.status() == StatusCode::NO_CONTENT {
+        return visitor.visit_empty();
+    }
+
+    match visitor.accept() {
+        Accept::Empty => visitor.visit_empty(),
+        Accept::Serializable => {
+            let encoding = response
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(Encoding::from_content_type)
+                .unwrap_or(Encoding::Json);
+            let body = body::to_bytes(response.into_body())
+                .await
+                .map_err(Error::internal)?;
+
+            match encoding {
+                Encoding::Json => {
+                    visitor.visit_serializable(&mut json::ClientDeserializer::from_slice(&body))
+                }
+                Encoding::Smile => {
+                    visitor.visit_serializable(&mut smile::ClientDeserializer::from_slice(&body))
+                }
+            }
+        }
+        Accept::Binary => visitor.visit_binary(BodyReader::new(response.into_body())),
+    }
+}
+
+async fn decode_error(response: Response<Body>) -> Error {
+    let (parts, body) = response.into_parts();
+    // the status code alone is still meaningful if the body can't be read
+    let body = body::to_bytes(body).await.unwrap_or_default();
+    client::decode_error(parts.status, &parts.headers, &body)
+}
+
+enum HyperRequestBody<'a> {
+    Empty,
+    Serializable(Result<Vec<u8>, Error>),
+    Binary(Pin<Box<dyn AsyncWriteBody<BodyWriter> + Sync + Send + 'a>>),
+}
+
+struct HyperRequestBodyVisitor {
+    encoding: Encoding,
+}
+
+impl<'a> AsyncVisitRequestBody<'a, BodyWriter> for HyperRequestBodyVisitor {
+    type Output = HyperRequestBody<'a>;
+
+    fn visit_empty(self) -> HyperRequestBody<'a> {
+        HyperRequestBody::Empty
+    }
+
+    fn visit_serializable<T>(self, body: T) -> HyperRequestBody<'a>
+    where
+        T: Serialize + 'a,
+    {
+        HyperRequestBody::Serializable(self.encoding.serialize(&body))
+    }
+
+    fn visit_binary<T>(self, body: T) -> HyperRequestBody<'a>
+    where
+        T: AsyncWriteBody<BodyWriter> + Sync + Send + 'a,
+    {
+        HyperRequestBody::Binary(Box::pin(body))
+    }
+}
@@ -0,0 +1,23 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Hyper-based implementations of Conjure HTTP clients and servers.
+//!
+//! The `client` module provides an `AsyncClient` implementation which can be used with generated async service
+//! clients, and the `server` module provides a hyper `Service` which serves generated async resources.
+#![warn(missing_docs, clippy::all)]
+#![doc(html_root_url = "https://docs.rs/conjure-hyper/0.7")]
+
+pub mod body;
+pub mod client;
+pub mod server;
@@ -0,0 +1,404 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! A hyper-based Conjure HTTP server.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use conjure_hyper::server::HyperService;
+//! use hyper::service::make_service_fn;
+//! use hyper::Server;
+//! use std::convert::Infallible;
+//!
+//! let service = HyperService::new().resource(MyServiceResource::new(MyServiceHandler));
+//! let make_service = make_service_fn(move |_| {
+//!     let service = service.clone();
+//!     async move { Ok::<_, Infallible>(service) }
+//! });
+//!
+//! Server::bind(&([127, 0, 0, 1], 8080).into())
+//!     .serve(make_service)
+//!     .await?;
+//! ```
+use crate::body::{BodyReader, BodyWriter};
+use conjure_error::{Error, InvalidArgument, NotFound, RequestEntityTooLarge};
+use conjure_http::encoding::{self, Encoding};
+use conjure_http::server::{
+    self, AsyncHandler, AsyncResource, AsyncVisitResponse, AsyncWriteBody, RequestBody, Route,
+    Router, VisitRequestBody,
+};
+use conjure_http::{PathParams, QueryParams};
+use conjure_serde::{json, smile};
+use http::header::{HeaderValue, ALLOW, CONTENT_TYPE};
+use http::{HeaderMap, Method, Request, Response, StatusCode};
+use hyper::body::HttpBody;
+use hyper::service::Service;
+use hyper::Body;
+use percent_encoding::percent_decode_str;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+// in order of preference when the client accepts several equally
+const ENCODINGS: &[Encoding] = &[Encoding::Json, Encoding::Smile];
+const APPLICATION_OCTET_STREAM: &str = "application/octet-stream";
+
+/// A hyper `Service` which dispatches requests to the `AsyncResource`s registered with it.
+///
+/// Requests are routed to an endpoint by their HTTP method and path. A request which doesn't match the path of any
+/// endpoint receives a `404 Not Found` response, and one which only matches the path of endpoints with other methods
+/// receives a `405 Method Not Allowed` response.
+///
+/// Serializable request bodies may be encoded as Conjure JSON or SMILE, and serializable response bodies are encoded as
+/// whichever of the two the request's `Accept` header prefers, defaulting to JSON. Binary request and response bodies
+/// are streamed. Errors returned by an endpoint are serialized as a `SerializableError` with the status code of its
+/// `ErrorCode`, except for throttle and unavailable errors, which produce `429 Too Many Requests` and
+/// `503 Service Unavailable` responses respectively.
+///
+/// Serializable request bodies are buffered in memory before they are deserialized. No limits are placed on their
+/// size by default, so services exposed to untrusted clients should configure one with `max_request_bytes`.
+///
+/// The service must be run from within the context of a Tokio runtime.
+#[derive(Clone, Default)]
+pub struct HyperService {
+    router: Arc<Router<Arc<dyn AsyncRoute>>>,
+    max_request_bytes: Option<usize>,
+    json: json::ServerDeserializerBuilder,
+}
+
+impl fmt::Debug for HyperService {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("HyperService").finish()
+    }
+}
+
+impl HyperService {
+    /// Creates a new service with no registered resources.
+    pub fn new() -> HyperService {
+        HyperService::default()
+    }
+
+    /// Sets the maximum size in bytes of serializable request bodies.
+    ///
+    /// Requests with larger bodies receive a `413 Request Entity Too Large` response without being buffered in full.
+    /// Binary request bodies are streamed to the endpoint and are not subject to the limit.
+    pub fn max_request_bytes(mut self, max_request_bytes: usize) -> HyperService {
+        self.max_request_bytes = Some(max_request_bytes);
+        self
+    }
+
+    /// Sets the builder used to create the deserializers of JSON request bodies.
+    ///
+    /// The builder's limits are enforced as the body is deserialized. Requests which exceed its byte limit receive a
+    /// `413 Request Entity Too Large` response, and those which exceed any of its other limits a `400 Bad Request`
+    /// response. No limits are configured by default.
+    pub fn json_deserializer(mut self, json: json::ServerDeserializerBuilder) -> HyperService {
+        self.json = json;
+        self
+    }
+
+    /// Registers a resource with the service.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the resource's endpoints has an invalid path template or conflicts with an endpoint of a
+    /// previously registered resource.
+    pub fn resource<T>(mut self, resource: T) -> HyperService
+    where
+        T: AsyncResource<BodyReader, BodyWriter> + 'static,
+    {
+        let resource = Arc::new(resource);
+        let router = Arc::make_mut(&mut self.router);
+        for endpoint in T::endpoints::<HyperRequestBody, HyperResponseVisitor>() {
+            let handler = Arc::new(Endpoint {
+                resource: resource.clone(),
+                handler: endpoint.handler,
+            });
+            if let Err(e) = router.insert(&endpoint.metadata, handler) {
+                panic!(
+                    "error registering endpoint {}: {:?}",
+                    endpoint.metadata.name(),
+                    e
+                );
+            }
+        }
+        self
+    }
+
+    async fn handle(&self, request: Request<Body>) -> Result<Response<Body>, Error> {
+        let (parts, body) = request.into_parts();
+
+        let (handler, path_params) = match self.router.route(&parts.method, parts.uri.path())? {
+            Route::Matched { value, path_params } => (value, path_params),
+            Route::MethodNotAllowed { allowed } => return Ok(method_not_allowed(&allowed)),
+            Route::NotFound => {
+                return Err(
+                    Error::service_safe("no endpoint matched the request", NotFound::new())
+                        .with_safe_param("method", parts.method.as_str())
+                        .with_unsafe_param("path", parts.uri.path()),
+                )
+            }
+        };
+
+        let query_params = parse_query(parts.uri.query().unwrap_or(""))?;
+        let body = self.request_body(&parts.headers, body).await?;
+
+        handler
+            .handle(&path_params, &query_params, &parts.headers, body)
+            .await
+    }
+
+    async fn request_body(
+        &self,
+        headers: &HeaderMap,
+        body: Body,
+    ) -> Result<HyperRequestBody, Error> {
+        let content_type = match headers.get(CONTENT_TYPE) {
+            Some(content_type) => content_type,
+            None => return Ok(HyperRequestBody::Empty),
+        };
+
+        if let Some(encoding) = Encoding::from_content_type(content_type) {
+            let body = self.buffer_body(body).await?;
+            return Ok(HyperRequestBody::Serializable(
+                encoding,
+                body,
+                self.json.clone(),
+            ));
+        }
+
+        // ignore parameters like `charset`
+        let mime = content_type
+            .to_str()
+            .ok()
+            .and_then(|s| s.split(';').next())
+            .map(|s| s.trim().to_ascii_lowercase());
+
+        match mime.as_deref() {
+            Some(APPLICATION_OCTET_STREAM) => Ok(HyperRequestBody::Binary(BodyReader::new(body))),
+            _ => Err(Error::service_safe(
+                "unsupported request Content-Type",
+                InvalidArgument::new(),
+            )),
+        }
+    }
+
+    async fn buffer_body(&self, mut body: Body) -> Result<Vec<u8>, Error> {
+        let mut buf = vec![];
+        while let Some(chunk) = body.data().await {
+            let chunk = chunk.map_err(Error::internal)?;
+            if let Some(max) = self.max_request_bytes {
+                if buf.len() + chunk.len() > max {
+                    return Err(Error::service_safe(
+                        "request body exceeds the size limit",
+                        RequestEntityTooLarge::new(),
+                    )
+                    .with_safe_param("maxBytes", max));
+                }
+            }
+            buf.extend_from_slice(&chunk);
+        }
+
+        Ok(buf)
+    }
+}
+
+impl Service<Request<Body>> for HyperService {
+    type Response = Response<Body>;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Response<Body>, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        let service = self.clone();
+        Box::pin(async move {
+            let response = match service.handle(request).await {
+                Ok(response) => response,
+                Err(error) => error_response(&error),
+            };
+            Ok(response)
+        })
+    }
+}
+
+fn decode(value: &str) -> Result<Cow<'_, str>, Error> {
+    percent_decode_str(value)
+        .decode_utf8()
+        .map_err(|e| Error::service_safe(e, InvalidArgument::new()))
+}
+
+fn parse_query(query: &str) -> Result<QueryParams, Error> {
+    let mut query_params = QueryParams::new();
+
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = match pair.find('=') {
+            Some(idx) => (&pair[..idx], &pair[idx + 1..]),
+            None => (pair, ""),
+        };
+        // `+` is the form-encoding of a space
+        let key = decode(&key.replace('+', " "))?.into_owned();
+        let value = decode(&value.replace('+', " "))?.into_owned();
+        query_params.insert(key, value);
+    }
+
+    Ok(query_params)
+}
+
+fn method_not_allowed(methods: &[Method]) -> Response<Body> {
+    let allow = methods
+        .iter()
+        .map(Method::as_str)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let mut response = Response::new(Body::empty());
+    *response.status_mut() = StatusCode::METHOD_NOT_ALLOWED;
+    if let Ok(allow) = HeaderValue::from_str(&allow) {
+        response.headers_mut().insert(ALLOW, allow);
+    }
+    response
+}
+
+fn error_response(error: &Error) -> Response<Body> {
+    let (status, headers, body) = server::encode_error(error).into_parts();
+
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+    *response.headers_mut() = headers;
+    response
+}
+
+trait AsyncRoute: Sync + Send {
+    fn handle<'a>(
+        &'a self,
+        path_params: &'a PathParams,
+        query_params: &'a QueryParams,
+        headers: &'a HeaderMap,
+        body: HyperRequestBody,
+    ) -> Pin<Box<dyn Future<Output = Result<Response<Body>, Error>> + Send + 'a>>;
+}
+
+struct Endpoint<T>
+where
+    T: 'static + Sync + Send,
+{
+    resource: Arc<T>,
+    handler: &'static (dyn AsyncHandler<T, HyperRequestBody, HyperResponseVisitor> + Sync + Send),
+}
+
+impl<T> AsyncRoute for Endpoint<T>
+where
+    T: Sync + Send,
+{
+    fn handle<'a>(
+        &'a self,
+        path_params: &'a PathParams,
+        query_params: &'a QueryParams,
+        headers: &'a HeaderMap,
+        body: HyperRequestBody,
+    ) -> Pin<Box<dyn Future<Output = Result<Response<Body>, Error>> + Send + 'a>> {
+        self.handler.handle(
+            &self.resource,
+            path_params,
+            query_params,
+            headers,
+            body,
+            HyperResponseVisitor {
+                encoding: encoding::negotiate(headers, ENCODINGS).unwrap_or(Encoding::Json),
+            },
+        )
+    }
+}
+
+enum HyperRequestBody {
+    Empty,
+    Serializable(Encoding, Vec<u8>, json::ServerDeserializerBuilder),
+    Binary(BodyReader),
+}
+
+impl RequestBody for HyperRequestBody {
+    type BinaryBody = BodyReader;
+
+    fn accept<V>(self, visitor: V) -> Result<V::Output, Error>
+    where
+        V: VisitRequestBody<BodyReader>,
+    {
+        match self {
+            HyperRequestBody::Empty => visitor.visit_empty(),
+            HyperRequestBody::Serializable(Encoding::Json, body, json) => {
+                visitor.visit_serializable(&mut json.from_slice(&body))
+            }
+            HyperRequestBody::Serializable(Encoding::Smile, body, _) => {
+                visitor.visit_serializable(&mut smile::ServerDeserializer::from_slice(&body))
+            }
+            HyperRequestBody::Binary(body) => visitor.visit_binary(body),
+        }
+    }
+}
+
+struct HyperResponseVisitor {
+    encoding: Encoding,
+}
+
+impl AsyncVisitResponse for HyperResponseVisitor {
+    type BinaryWriter = BodyWriter;
+    type Output = Response<Body>;
+
+    fn visit_empty(self) -> Result<Response<Body>, Error> {
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = StatusCode::NO_CONTENT;
+        Ok(response)
+    }
+
+    fn visit_serializable<T>(self, body: T) -> Result<Response<Body>, Error>
+    where
+        T: Serialize + 'static + Send,
+    {
+        let body = self.encoding.serialize(&body)?;
+
+        let mut response = Response::new(Body::from(body));
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, self.encoding.content_type());
+        Ok(response)
+    }
+
+    fn visit_binary<T>(self, body: T) -> Result<Response<Body>, Error>
+    where
+        T: AsyncWriteBody<BodyWriter> + 'static + Send,
+    {
+        let (sender, response_body) = Body::channel();
+        tokio::spawn(async move {
+            let mut writer = BodyWriter::new(sender);
+            if body.write_body(Pin::new(&mut writer)).await.is_err() {
+                writer.abort();
+            }
+        });
+
+        let mut response = Response::new(response_body);
+        response.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static(APPLICATION_OCTET_STREAM),
+        );
+        Ok(response)
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Procedural macros for Conjure code generation.
+//!
+//! The `include_conjure!` macro generates code for a Conjure IR file at compile time, without the need for a build
+//! script. The path to the IR file is relative to the crate root, and the same options as
+//! `conjure_codegen::Config` are supported:
+//!
+//! ```ignore
+//! mod service_api {
+//!     conjure_macros::include_conjure!(
+//!         "service-api.conjure.json",
+//!         exhaustive = false,
+//!         staged_builders = true,
+//!         strip_prefix = "com.foobar.service",
+//!     );
+//! }
+//! ```
+//!
+//! The generated code depends on the `conjure-object`, `conjure-error`, and `conjure-http` crates in the same way as
+//! code generated by `conjure-codegen`.
+#![warn(clippy::all, missing_docs)]
+#![doc(html_root_url = "https://docs.rs/conjure-macros/0.7")]
+
+extern crate proc_macro;
+
+use conjure_codegen::types::ConjureDefinition;
+use conjure_codegen::Config;
+use proc_macro::TokenStream;
+use quote::quote;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use syn::parse::{Parse, ParseStream};
+use syn::{parse_macro_input, Error, Ident, LitBool, LitStr, Token};
+
+/// Expands to the Rust code generated from a Conjure IR file.
+///
+/// The first argument is the path to a JSON-formatted Conjure IR file, relative to the crate root. It can optionally
+/// be followed by `exhaustive = <bool>`, `staged_builders = <bool>`, and `strip_prefix = "<prefix>"` options, which
+/// behave like the corresponding `conjure_codegen::Config` methods.
+#[proc_macro]
+pub fn include_conjure(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as Input);
+
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+struct Input {
+    path: LitStr,
+    exhaustive: bool,
+    staged_builders: bool,
+    strip_prefix: Option<String>,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Input> {
+        let path = input.parse()?;
+        let mut exhaustive = None;
+        let mut staged_builders = None;
+        let mut strip_prefix = None;
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let key = input.parse::<Ident>()?;
+            input.parse::<Token![=]>()?;
+
+            if key == "exhaustive" {
+                if exhaustive.is_some() {
+                    return Err(Error::new(key.span(), "duplicate option `exhaustive`"));
+                }
+                exhaustive = Some(input.parse::<LitBool>()?.value);
+            } else if key == "staged_builders" {
+                if staged_builders.is_some() {
+                    return Err(Error::new(key.span(), "duplicate option `staged_builders`"));
+                }
+                staged_builders = Some(input.parse::<LitBool>()?.value);
+            } else if key == "strip_prefix" {
+                if strip_prefix.is_some() {
+                    return Err(Error::new(key.span(), "duplicate option `strip_prefix`"));
+                }
+                strip_prefix = Some(input.parse::<LitStr>()?.value());
+            } else {
+                return Err(Error::new(key.span(), format!("unknown option `{}`", key)));
+            }
+        }
+
+        Ok(Input {
+            path,
+            exhaustive: exhaustive.unwrap_or(false),
+            staged_builders: staged_builders.unwrap_or(false),
+            strip_prefix,
+        })
+    }
+}
+
+fn expand(input: &Input) -> Result<proc_macro2::TokenStream, Error> {
+    let span = input.path.span();
+
+    let manifest_dir = env::var_os("CARGO_MANIFEST_DIR")
+        .ok_or_else(|| Error::new(span, "CARGO_MANIFEST_DIR is not set"))?;
+    let path = PathBuf::from(manifest_dir).join(input.path.value());
+
+    let ir = fs::read_to_string(&path).map_err(|e| {
+        Error::new(
+            span,
+            format!("error reading file {}: {}", path.display(), e),
+        )
+    })?;
+    let defs = conjure_serde::json::client_from_str::<ConjureDefinition>(&ir).map_err(|e| {
+        Error::new(
+            span,
+            format!("error parsing Conjure IR file {}: {}", path.display(), e),
+        )
+    })?;
+
+    let mut config = Config::new();
+    config
+        .exhaustive(input.exhaustive)
+        .staged_builders(input.staged_builders)
+        .strip_prefix(input.strip_prefix.clone());
+    let tokens = config
+        .generate_tokens(&defs)
+        .map_err(|e| Error::new(span, e))?;
+
+    // referencing the file ensures that the crate is rebuilt when it changes
+    let path = path
+        .to_str()
+        .ok_or_else(|| Error::new(span, "IR file path is not valid UTF-8"))?;
+
+    Ok(quote! {
+        const _: &[u8] = include_bytes!(#path);
+
+        #tokens
+    })
+}
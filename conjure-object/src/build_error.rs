@@ -0,0 +1,57 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! The error returned by Conjure object builders.
+use std::error::Error;
+use std::fmt;
+
+/// An error returned when building an object without setting all of its required fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildError {
+    type_name: &'static str,
+    missing_fields: Vec<&'static str>,
+}
+
+impl BuildError {
+    pub(crate) fn new(type_name: &'static str, missing_fields: Vec<&'static str>) -> BuildError {
+        BuildError {
+            type_name,
+            missing_fields,
+        }
+    }
+
+    /// Returns the name of the type being built.
+    #[inline]
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns the names of the required fields which were not set.
+    #[inline]
+    pub fn missing_fields(&self) -> &[&'static str] {
+        &self.missing_fields
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "required fields of `{}` were not set: {}",
+            self.type_name,
+            self.missing_fields.join(", ")
+        )
+    }
+}
+
+impl Error for BuildError {}
@@ -15,6 +15,12 @@ use serde::de::{self, IntoDeserializer};
 use std::fmt;
 use std::marker::PhantomData;
 
+use crate::BuildError;
+
+pub fn build_error(type_name: &'static str, missing_fields: Vec<&'static str>) -> BuildError {
+    BuildError::new(type_name, missing_fields)
+}
+
 pub fn valid_enum_variant(s: &str) -> bool {
     if s.is_empty() {
         return false;
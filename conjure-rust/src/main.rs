@@ -13,7 +13,13 @@
 // limitations under the License.
 #![warn(clippy::all)]
 
-use std::path::PathBuf;
+use conjure_codegen::types::ConjureDefinition;
+use conjure_serde::json::Serializer;
+use failure::{Error, ResultExt};
+use serde::Serialize;
+use std::fs;
+use std::iter;
+use std::path::{Path, PathBuf};
 use std::process;
 use structopt::clap::AppSettings;
 use structopt::StructOpt;
@@ -28,6 +34,30 @@ enum Opts {
     )]
     /// Generate Rust code from a conjure IR file.
     Generate(Args),
+    #[structopt(
+        name = "compile",
+        setting = AppSettings::UnifiedHelpMessage,
+        setting = AppSettings::DeriveDisplayOrder,
+        setting = AppSettings::DontCollapseArgsInUsage,
+    )]
+    /// Compile conjure YAML definitions into a conjure IR file.
+    Compile(CompileArgs),
+    #[structopt(
+        name = "openapi",
+        setting = AppSettings::UnifiedHelpMessage,
+        setting = AppSettings::DeriveDisplayOrder,
+        setting = AppSettings::DontCollapseArgsInUsage,
+    )]
+    /// Generate an OpenAPI 3 document from a conjure IR file.
+    OpenApi(OpenApiArgs),
+    #[structopt(
+        name = "json-schema",
+        setting = AppSettings::UnifiedHelpMessage,
+        setting = AppSettings::DeriveDisplayOrder,
+        setting = AppSettings::DontCollapseArgsInUsage,
+    )]
+    /// Generate JSON Schema documents for the types in a conjure IR file.
+    JsonSchema(JsonSchemaArgs),
 }
 
 // FIXME move aliases over to the standard names
@@ -36,9 +66,36 @@ struct Args {
     #[structopt(long = "exhaustive")]
     /// Generate exhaustively matchable enums and unions
     exhaustive: bool,
+    #[structopt(long = "staged-builders")]
+    /// Generate compile-time checked staged builders for objects
+    staged_builders: bool,
     #[structopt(long = "strip-prefix", value_name = "prefix", alias = "stripPrefix")]
     /// Strip a prefix from types's package paths
     strip_prefix: Option<String>,
+    #[structopt(
+        long = "external-type",
+        value_name = "java-type=rust-type",
+        number_of_values = 1,
+        parse(try_from_str = parse_key_value)
+    )]
+    /// Map an external import to a Rust type
+    external_types: Vec<(String, String)>,
+    #[structopt(
+        long = "marker-attribute",
+        value_name = "marker-type=attribute",
+        number_of_values = 1,
+        parse(try_from_str = parse_key_value)
+    )]
+    /// Apply a Rust attribute to endpoints with a marker
+    marker_attributes: Vec<(String, String)>,
+    #[structopt(
+        long = "marker-bound",
+        value_name = "marker-type=bound",
+        number_of_values = 1,
+        parse(try_from_str = parse_key_value)
+    )]
+    /// Require a trait bound on the client for endpoints with a marker
+    marker_bounds: Vec<(String, String)>,
     /// The name of the generated crate
     #[structopt(
         long = "crate-name",
@@ -65,24 +122,138 @@ struct Args {
     output_directory: PathBuf,
 }
 
+#[derive(StructOpt)]
+struct CompileArgs {
+    #[structopt(name = "input", parse(from_os_str))]
+    /// Path to a conjure YAML file, or a directory containing conjure YAML files
+    input: PathBuf,
+    #[structopt(name = "output-json", parse(from_os_str))]
+    /// Path to write the JSON-formatted Conjure IR file
+    output_json: PathBuf,
+}
+
+#[derive(StructOpt)]
+struct OpenApiArgs {
+    #[structopt(long = "title", value_name = "title", default_value = "API")]
+    /// The title of the API
+    title: String,
+    #[structopt(long = "api-version", value_name = "version", default_value = "0.0.0")]
+    /// The version of the API
+    api_version: String,
+    #[structopt(name = "input-json", parse(from_os_str))]
+    /// Path to a JSON-formatted Conjure IR file
+    input_json: PathBuf,
+    #[structopt(name = "output-json", parse(from_os_str))]
+    /// Path to write the JSON-formatted OpenAPI document
+    output_json: PathBuf,
+}
+
+#[derive(StructOpt)]
+struct JsonSchemaArgs {
+    #[structopt(name = "input-json", parse(from_os_str))]
+    /// Path to a JSON-formatted Conjure IR file
+    input_json: PathBuf,
+    #[structopt(name = "output-directory", parse(from_os_str))]
+    /// Directory to place the JSON Schema documents
+    output_directory: PathBuf,
+}
+
 fn main() {
-    let Opts::Generate(args) = Opts::from_args();
+    let r = match Opts::from_args() {
+        Opts::Generate(args) => generate(args),
+        Opts::Compile(args) => compile(args),
+        Opts::OpenApi(args) => openapi(args),
+        Opts::JsonSchema(args) => json_schema(args),
+    };
+
+    if let Err(e) = r {
+        eprintln!("{}", e);
+        for e in e.iter_causes() {
+            eprintln!("Caused by: {}", e);
+        }
+        process::exit(1);
+    }
+}
 
+fn generate(args: Args) -> Result<(), Error> {
     let mut config = conjure_codegen::Config::new();
-    config.exhaustive(args.exhaustive);
+    config
+        .exhaustive(args.exhaustive)
+        .staged_builders(args.staged_builders);
     if let Some(prefix) = args.strip_prefix {
         config.strip_prefix(prefix);
     }
+    for (java_type, rust_type) in &args.external_types {
+        config.external_type(java_type, rust_type);
+    }
+    for (marker, attribute) in &args.marker_attributes {
+        config.marker_attribute(marker, attribute);
+    }
+    for (marker, bound) in &args.marker_bounds {
+        config.marker_bound(marker, bound);
+    }
     if let (Some(crate_name), Some(crate_version)) = (args.crate_name, args.crate_version) {
         config.build_crate(&crate_name, &crate_version);
     }
-    let r = config.generate_files(&args.input_json, &args.output_directory);
+    config.generate_files(&args.input_json, &args.output_directory)
+}
 
-    if let Err(e) = r {
-        eprintln!("{}", e);
-        for e in e.iter_causes() {
-            eprintln!("Caused by: {}", e);
-        }
-        process::exit(1);
+// shared by all `key=value` options - clap's error message names the option itself
+fn parse_key_value(s: &str) -> Result<(String, String), String> {
+    let mut parts = s.splitn(2, '=');
+    match (parts.next(), parts.next()) {
+        (Some(key), Some(value)) => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected `<key>=<value>`, got `{}`", s)),
+    }
+}
+
+fn compile(args: CompileArgs) -> Result<(), Error> {
+    let defs = conjure_codegen::compile_yaml(iter::once(&args.input))?;
+    write_json(&args.output_json, &defs)
+}
+
+fn openapi(args: OpenApiArgs) -> Result<(), Error> {
+    let defs = read_ir(&args.input_json)?;
+    let document = conjure_codegen::generate_openapi(&defs, &args.title, &args.api_version);
+    write_json(&args.output_json, &document)
+}
+
+fn json_schema(args: JsonSchemaArgs) -> Result<(), Error> {
+    let defs = read_ir(&args.input_json)?;
+
+    fs::create_dir_all(&args.output_directory).with_context(|_| {
+        format!(
+            "error creating directory {}",
+            args.output_directory.display()
+        )
+    })?;
+
+    for (name, document) in conjure_codegen::generate_json_schemas(&defs) {
+        let file = format!("{}.{}.json", name.package(), name.name());
+        write_json(&args.output_directory.join(file), &document)?;
     }
+
+    Ok(())
+}
+
+fn read_ir(path: &Path) -> Result<ConjureDefinition, Error> {
+    let ir = fs::read_to_string(path)
+        .with_context(|_| format!("error reading file {}", path.display()))?;
+    let defs = conjure_serde::json::client_from_str(&ir)
+        .with_context(|_| format!("error parsing Conjure IR file {}", path.display()))?;
+
+    Ok(defs)
+}
+
+fn write_json<T>(path: &Path, value: &T) -> Result<(), Error>
+where
+    T: Serialize,
+{
+    let mut out = vec![];
+    value.serialize(&mut Serializer::pretty(&mut out))?;
+    out.push(b'\n');
+
+    fs::write(path, &out).with_context(|_| format!("error writing file {}", path.display()))?;
+
+    Ok(())
 }
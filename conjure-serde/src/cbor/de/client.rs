@@ -0,0 +1,36 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::cbor::de::read::{IoRead, Read, SliceRead};
+use crate::cbor::de::Deserializer;
+use crate::cbor::error::Error;
+
+binary_deserializer! {
+    /// Deserializes a value from a reader of CBOR data.
+    pub fn client_from_reader;
+    /// Deserializes a value from a slice of CBOR data.
+    pub fn client_from_slice;
+    /// A serde CBOR deserializer appropriate for use by Conjure clients.
+    ///
+    /// The f32 and f64 types can additionally be deserialized from the strings `"Infinity"`, `"-Infinity"`, and
+    /// `"NaN"`, and bytes from base64 encoded strings. Unknown object fields are ignored.
+    pub struct ClientDeserializer {
+        deny_unknown_fields: false,
+        /// Creates a Conjure CBOR client deserializer from an `io::Read`.
+        fn from_reader;
+        /// Creates a Conjure CBOR client deserializer from a `&[u8]`.
+        fn from_slice;
+        /// Validates that the input stream is at the end.
+        fn end;
+    }
+}
@@ -0,0 +1,700 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use serde::de::{self, IntoDeserializer};
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::f32;
+use std::fmt;
+use std::str;
+
+use crate::cbor::de::read::{Read, Reference};
+use crate::cbor::error::Error;
+use crate::cbor::{
+    BREAK, FLOAT_DOUBLE, FLOAT_HALF, FLOAT_SINGLE, INFO_INDEFINITE, INFO_U16, INFO_U32, INFO_U64,
+    INFO_U8, MAJOR_ARRAY, MAJOR_BYTES, MAJOR_MAP, MAJOR_NEGATIVE, MAJOR_SIMPLE, MAJOR_TAG,
+    MAJOR_TEXT, MAJOR_UNSIGNED, SIMPLE_FALSE, SIMPLE_NULL, SIMPLE_TRUE, SIMPLE_UNDEFINED,
+    TAG_NEGATIVE_BIGNUM, TAG_POSITIVE_BIGNUM,
+};
+use crate::json::de::{F32Visitor, F64Visitor};
+
+const RECURSION_LIMIT: u8 = 128;
+
+pub mod client;
+pub mod read;
+pub mod server;
+
+/// The CBOR parser shared by the client and server deserializers.
+pub(crate) struct Deserializer<R> {
+    read: R,
+    scratch: Vec<u8>,
+    remaining_depth: u8,
+    deny_unknown_fields: bool,
+}
+
+impl<'de, R> Deserializer<R>
+where
+    R: Read<'de>,
+{
+    pub(crate) fn new(read: R, deny_unknown_fields: bool) -> Deserializer<R> {
+        Deserializer {
+            read,
+            scratch: vec![],
+            remaining_depth: RECURSION_LIMIT,
+            deny_unknown_fields,
+        }
+    }
+
+    pub(crate) fn end(&mut self) -> Result<(), Error> {
+        match self.read.next()? {
+            None => Ok(()),
+            Some(_) => Err(Error::message("trailing data after value")),
+        }
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, Error> {
+        self.read.peek()
+    }
+
+    fn next(&mut self) -> Result<u8, Error> {
+        self.read.next()?.ok_or_else(Error::eof)
+    }
+
+    fn enter(&mut self) -> Result<(), Error> {
+        self.remaining_depth -= 1;
+        if self.remaining_depth == 0 {
+            self.remaining_depth += 1;
+            return Err(Error::message("recursion limit exceeded"));
+        }
+
+        Ok(())
+    }
+
+    fn exit(&mut self) {
+        self.remaining_depth += 1;
+    }
+
+    // returns the initial byte of the next data item along with its innermost semantic tag, if any
+    fn next_item(&mut self) -> Result<(u8, Option<u64>), Error> {
+        let mut tag = None;
+        loop {
+            let byte = self.next()?;
+            if byte >> 5 != MAJOR_TAG {
+                return Ok((byte, tag));
+            }
+            tag = Some(self.parse_argument(byte)?);
+        }
+    }
+
+    // returns `None` for indefinite-length items
+    fn parse_optional_argument(&mut self, byte: u8) -> Result<Option<u64>, Error> {
+        let len = match byte & 0x1f {
+            info @ 0..=23 => return Ok(Some(u64::from(info))),
+            INFO_U8 => 1,
+            INFO_U16 => 2,
+            INFO_U32 => 4,
+            INFO_U64 => 8,
+            INFO_INDEFINITE if byte >> 5 >= MAJOR_BYTES && byte >> 5 <= MAJOR_MAP => {
+                return Ok(None)
+            }
+            _ => return Err(invalid_byte(byte)),
+        };
+
+        let bytes = self.read.read(len, &mut self.scratch)?;
+        Ok(Some(
+            bytes.iter().fold(0u64, |v, &b| (v << 8) | u64::from(b)),
+        ))
+    }
+
+    fn parse_argument(&mut self, byte: u8) -> Result<u64, Error> {
+        self.parse_optional_argument(byte)?
+            .ok_or_else(|| invalid_byte(byte))
+    }
+
+    fn parse_len(&mut self, byte: u8) -> Result<Option<usize>, Error> {
+        match self.parse_optional_argument(byte)? {
+            Some(len) => usize::try_from(len)
+                .map(Some)
+                .map_err(|_| Error::message("length overflow")),
+            None => Ok(None),
+        }
+    }
+
+    // parses the contents of a byte or text string, concatenating the chunks of indefinite-length strings
+    fn parse_string(&mut self, byte: u8) -> Result<Reference<'de, '_, [u8]>, Error> {
+        if let Some(len) = self.parse_len(byte)? {
+            return self.read.read(len, &mut self.scratch);
+        }
+
+        let mut buf = vec![];
+        loop {
+            let chunk = self.next()?;
+            if chunk == BREAK {
+                break;
+            }
+            if chunk >> 5 != byte >> 5 {
+                return Err(Error::message("invalid indefinite-length string chunk"));
+            }
+            let len = self
+                .parse_len(chunk)?
+                .ok_or_else(|| Error::message("nested indefinite-length string"))?;
+            buf.extend_from_slice(&self.read.read(len, &mut self.scratch)?);
+        }
+
+        self.scratch = buf;
+        Ok(Reference::Copied(&self.scratch))
+    }
+
+    fn parse_bignum<V>(&mut self, tag: u64, byte: u8, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let bytes = self.parse_string(byte)?;
+        let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+        if bytes.len() - start > 16 {
+            return Err(Error::message("big integer out of range"));
+        }
+        let value = bytes[start..]
+            .iter()
+            .fold(0u128, |v, &b| (v << 8) | u128::from(b));
+
+        if tag == TAG_POSITIVE_BIGNUM {
+            return match u64::try_from(value) {
+                Ok(value) => visitor.visit_u64(value),
+                Err(_) => visitor.visit_u128(value),
+            };
+        }
+
+        // negative values are encoded as -1 - n
+        let value = i128::try_from(value)
+            .map(|v| -1 - v)
+            .map_err(|_| Error::message("big integer out of range"))?;
+        match i64::try_from(value) {
+            Ok(value) => visitor.visit_i64(value),
+            Err(_) => visitor.visit_i128(value),
+        }
+    }
+
+    fn parse_key(&mut self) -> Result<Cow<'de, str>, Error> {
+        let (byte, _) = self.next_item()?;
+        match byte >> 5 {
+            MAJOR_TEXT => {}
+            // other encoders may write integer keys natively, so they're stringified like they'd be in JSON
+            MAJOR_UNSIGNED => return Ok(Cow::Owned(self.parse_argument(byte)?.to_string())),
+            MAJOR_NEGATIVE => {
+                let value = -1 - i128::from(self.parse_argument(byte)?);
+                return Ok(Cow::Owned(value.to_string()));
+            }
+            _ => return Err(Error::message("map keys must be strings")),
+        }
+
+        let key = match to_str(self.parse_string(byte)?)? {
+            Reference::Borrowed(key) => Cow::Borrowed(key),
+            Reference::Copied(key) => Cow::Owned(key.to_string()),
+        };
+        Ok(key)
+    }
+
+    fn visit_item<V>(&mut self, byte: u8, tag: Option<u64>, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match (tag, byte >> 5) {
+            (Some(tag @ TAG_POSITIVE_BIGNUM), MAJOR_BYTES)
+            | (Some(tag @ TAG_NEGATIVE_BIGNUM), MAJOR_BYTES) => {
+                return self.parse_bignum(tag, byte, visitor)
+            }
+            _ => {}
+        }
+
+        match byte >> 5 {
+            MAJOR_UNSIGNED => {
+                let value = self.parse_argument(byte)?;
+                visitor.visit_u64(value)
+            }
+            MAJOR_NEGATIVE => {
+                let value = self.parse_argument(byte)?;
+                match i64::try_from(value) {
+                    Ok(value) => visitor.visit_i64(-1 - value),
+                    Err(_) => visitor.visit_i128(-1 - i128::from(value)),
+                }
+            }
+            MAJOR_BYTES => match self.parse_string(byte)? {
+                Reference::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
+                Reference::Copied(bytes) => visitor.visit_bytes(bytes),
+            },
+            MAJOR_TEXT => match to_str(self.parse_string(byte)?)? {
+                Reference::Borrowed(value) => visitor.visit_borrowed_str(value),
+                Reference::Copied(value) => visitor.visit_str(value),
+            },
+            MAJOR_ARRAY => {
+                let len = self.parse_len(byte)?;
+                self.enter()?;
+                let mut access = SeqAccess {
+                    de: self,
+                    remaining: len,
+                };
+                let value = visitor.visit_seq(&mut access);
+                let remaining = access.remaining;
+                self.exit();
+                let value = value?;
+
+                self.finish(remaining, "trailing elements in array")?;
+                Ok(value)
+            }
+            MAJOR_MAP => {
+                let len = self.parse_len(byte)?;
+                self.visit_map(len, None, visitor)
+            }
+            MAJOR_SIMPLE => match byte {
+                SIMPLE_FALSE => visitor.visit_bool(false),
+                SIMPLE_TRUE => visitor.visit_bool(true),
+                SIMPLE_NULL | SIMPLE_UNDEFINED => visitor.visit_unit(),
+                FLOAT_HALF => {
+                    let bits = self.parse_argument(byte)?;
+                    visitor.visit_f32(f16_to_f32(bits as u16))
+                }
+                FLOAT_SINGLE => {
+                    let bits = self.parse_argument(byte)?;
+                    visitor.visit_f32(f32::from_bits(bits as u32))
+                }
+                FLOAT_DOUBLE => {
+                    let bits = self.parse_argument(byte)?;
+                    visitor.visit_f64(f64::from_bits(bits))
+                }
+                _ => Err(invalid_byte(byte)),
+            },
+            _ => Err(invalid_byte(byte)),
+        }
+    }
+
+    fn visit_map<V>(
+        &mut self,
+        len: Option<usize>,
+        fields: Option<&'static [&'static str]>,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.enter()?;
+        let mut access = MapAccess {
+            de: self,
+            remaining: len,
+            fields,
+            key: None,
+        };
+        let value = visitor.visit_map(&mut access);
+        let remaining = access.remaining;
+        self.exit();
+        let value = value?;
+
+        self.finish(remaining, "trailing entries in map")?;
+        Ok(value)
+    }
+
+    // checks that a visitor consumed all of the contents of an array or map
+    fn finish(&mut self, remaining: Option<usize>, message: &str) -> Result<(), Error> {
+        match remaining {
+            Some(0) => Ok(()),
+            Some(_) => Err(Error::message(message)),
+            None => match self.next()? {
+                BREAK => Ok(()),
+                _ => Err(Error::message(message)),
+            },
+        }
+    }
+}
+
+impl<'de, R> de::Deserializer<'de> for &mut Deserializer<R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let (byte, tag) = self.next_item()?;
+        self.visit_item(byte, tag, visitor)
+    }
+
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_any(F32Visitor(visitor))
+    }
+
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_any(F64Visitor(visitor))
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_any(BytesVisitor(visitor))
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_any(BytesVisitor(visitor))
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        match self.peek()? {
+            Some(SIMPLE_NULL) | Some(SIMPLE_UNDEFINED) => {
+                self.next()?;
+                visitor.visit_none()
+            }
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _: &'static str, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let (byte, tag) = self.next_item()?;
+        if self.deny_unknown_fields && byte >> 5 == MAJOR_MAP {
+            let len = self.parse_len(byte)?;
+            self.visit_map(len, Some(fields), visitor)
+        } else {
+            self.visit_item(byte, tag, visitor)
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let (byte, tag) = self.next_item()?;
+        match byte >> 5 {
+            MAJOR_MAP => {
+                let len = self.parse_len(byte)?;
+                match len {
+                    Some(1) | None => {}
+                    Some(_) => return Err(Error::message("expected a single entry enum map")),
+                }
+
+                self.enter()?;
+                let value = visitor.visit_enum(VariantAccess { de: self });
+                self.exit();
+                let value = value?;
+
+                self.finish(len.map(|_| 0), "expected end of enum map")?;
+                Ok(value)
+            }
+            MAJOR_TEXT => {
+                let variant = to_str(self.parse_string(byte)?)?;
+                visitor.visit_enum((&*variant).into_deserializer())
+            }
+            _ => self.visit_item(byte, tag, visitor),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 char str string unit unit_struct seq tuple
+        tuple_struct map identifier ignored_any
+    }
+}
+
+struct SeqAccess<'a, R> {
+    de: &'a mut Deserializer<R>,
+    // `None` for indefinite-length arrays
+    remaining: Option<usize>,
+}
+
+impl<'a, 'de, R> de::SeqAccess<'de> for SeqAccess<'a, R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        if !has_next(self.de, &mut self.remaining)? {
+            return Ok(None);
+        }
+
+        seed.deserialize(&mut *self.de).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.remaining
+    }
+}
+
+struct MapAccess<'a, 'de, R> {
+    de: &'a mut Deserializer<R>,
+    // `None` for indefinite-length maps
+    remaining: Option<usize>,
+    // only set when unknown fields should be rejected
+    fields: Option<&'static [&'static str]>,
+    key: Option<Cow<'de, str>>,
+}
+
+impl<'a, 'de, R> de::MapAccess<'de> for MapAccess<'a, 'de, R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        if !has_next(self.de, &mut self.remaining)? {
+            return Ok(None);
+        }
+
+        let key = self.de.parse_key()?;
+        let value = seed.deserialize(KeyDeserializer { key: &key })?;
+        if self.fields.is_some() {
+            self.key = Some(key);
+        }
+
+        Ok(Some(value))
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        match self.fields {
+            Some(fields) => seed.deserialize(FieldDeserializer {
+                de: &mut *self.de,
+                fields,
+                key: self.key.take(),
+            }),
+            None => seed.deserialize(&mut *self.de),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.remaining
+    }
+}
+
+// determines if an array or map has another element, leaving the break marker of indefinite-length items in place
+fn has_next<'de, R>(de: &mut Deserializer<R>, remaining: &mut Option<usize>) -> Result<bool, Error>
+where
+    R: Read<'de>,
+{
+    match remaining {
+        Some(0) => Ok(false),
+        Some(remaining) => {
+            *remaining -= 1;
+            Ok(true)
+        }
+        None => match de.peek()? {
+            Some(BREAK) => Ok(false),
+            Some(_) => Ok(true),
+            None => Err(Error::eof()),
+        },
+    }
+}
+
+struct VariantAccess<'a, R> {
+    de: &'a mut Deserializer<R>,
+}
+
+impl<'a, 'de, R> de::EnumAccess<'de> for VariantAccess<'a, R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let key = self.de.parse_key()?;
+        let value = seed.deserialize(KeyDeserializer { key: &key })?;
+        Ok((value, self))
+    }
+}
+
+impl<'a, 'de, R> de::VariantAccess<'de> for VariantAccess<'a, R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        de::Deserialize::deserialize(self.de)
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.de)
+    }
+
+    fn tuple_variant<V>(self, _: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        de::Deserializer::deserialize_seq(self.de, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        de::Deserializer::deserialize_struct(self.de, "", fields, visitor)
+    }
+}
+
+// map keys are always treated as strings
+key_deserializer!();
+
+// the value of a struct field, which is only ignored by the struct if the field is unknown
+struct FieldDeserializer<'a, 'de, R> {
+    de: &'a mut Deserializer<R>,
+    fields: &'static [&'static str],
+    key: Option<Cow<'de, str>>,
+}
+
+impl<'a, 'de, R> de::Deserializer<'de> for FieldDeserializer<'a, 'de, R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+
+    forward_deserialize_values!(this => this.de);
+
+    fn deserialize_ignored_any<V>(self, _: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let key = match &self.key {
+            Some(key) => &**key,
+            None => "<unknown>",
+        };
+
+        Err(de::Error::unknown_field(key, self.fields))
+    }
+}
+
+struct BytesVisitor<T>(T);
+
+impl<'de, T> de::Visitor<'de> for BytesVisitor<T>
+where
+    T: de::Visitor<'de>,
+{
+    type Value = T::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("binary data or a base64 string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<T::Value, E>
+    where
+        E: de::Error,
+    {
+        match base64::decode(v) {
+            Ok(v) => self.0.visit_byte_buf(v),
+            Err(_) => Err(E::invalid_value(de::Unexpected::Str(v), &self)),
+        }
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<T::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_bytes(v)
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<T::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_borrowed_bytes(v)
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<T::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_byte_buf(v)
+    }
+}
+
+fn to_str<'de, 's>(bytes: Reference<'de, 's, [u8]>) -> Result<Reference<'de, 's, str>, Error> {
+    let invalid = |_| Error::message("invalid UTF-8 in string");
+    match bytes {
+        Reference::Borrowed(bytes) => str::from_utf8(bytes)
+            .map(Reference::Borrowed)
+            .map_err(invalid),
+        Reference::Copied(bytes) => str::from_utf8(bytes)
+            .map(Reference::Copied)
+            .map_err(invalid),
+    }
+}
+
+fn f16_to_f32(bits: u16) -> f32 {
+    let exponent = i32::from((bits >> 10) & 0x1f);
+    let mantissa = f32::from(bits & 0x3ff);
+    let value = match exponent {
+        0 => mantissa * 2f32.powi(-24),
+        0x1f if mantissa == 0. => f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => (mantissa + 1024.) * 2f32.powi(exponent - 25),
+    };
+
+    if bits & 0x8000 == 0 {
+        value
+    } else {
+        -value
+    }
+}
+
+fn invalid_byte(byte: u8) -> Error {
+    Error::message(format_args!("invalid initial byte 0x{:02x}", byte))
+}
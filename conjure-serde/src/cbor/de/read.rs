@@ -0,0 +1,179 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::io::{self, Read as _};
+use std::ops::Deref;
+
+use crate::cbor::error::Error;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// A source of CBOR data.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait Read<'de>: private::Sealed {
+    #[doc(hidden)]
+    fn next(&mut self) -> Result<Option<u8>, Error>;
+
+    #[doc(hidden)]
+    fn peek(&mut self) -> Result<Option<u8>, Error>;
+
+    #[doc(hidden)]
+    fn read<'s>(
+        &'s mut self,
+        len: usize,
+        scratch: &'s mut Vec<u8>,
+    ) -> Result<Reference<'de, 's, [u8]>, Error>;
+}
+
+#[doc(hidden)]
+pub enum Reference<'b, 'c, T>
+where
+    T: ?Sized,
+{
+    Borrowed(&'b T),
+    Copied(&'c T),
+}
+
+impl<'b, 'c, T> Deref for Reference<'b, 'c, T>
+where
+    T: ?Sized,
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        match *self {
+            Reference::Borrowed(b) => b,
+            Reference::Copied(c) => c,
+        }
+    }
+}
+
+/// CBOR input from a byte slice.
+pub struct SliceRead<'a> {
+    slice: &'a [u8],
+    index: usize,
+}
+
+impl<'a> SliceRead<'a> {
+    /// Creates a new CBOR input source from a byte slice.
+    pub fn new(slice: &'a [u8]) -> SliceRead<'a> {
+        SliceRead { slice, index: 0 }
+    }
+}
+
+impl<'a> private::Sealed for SliceRead<'a> {}
+
+impl<'a> Read<'a> for SliceRead<'a> {
+    fn next(&mut self) -> Result<Option<u8>, Error> {
+        let b = self.slice.get(self.index).cloned();
+        if b.is_some() {
+            self.index += 1;
+        }
+        Ok(b)
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, Error> {
+        Ok(self.slice.get(self.index).cloned())
+    }
+
+    fn read<'s>(
+        &'s mut self,
+        len: usize,
+        _: &'s mut Vec<u8>,
+    ) -> Result<Reference<'a, 's, [u8]>, Error> {
+        if self.slice.len() - self.index < len {
+            return Err(Error::eof());
+        }
+
+        let bytes = &self.slice[self.index..self.index + len];
+        self.index += len;
+        Ok(Reference::Borrowed(bytes))
+    }
+}
+
+/// CBOR input from an `io::Read`.
+///
+/// Bytes are read from the reader one at a time, so it should be buffered if reads are expensive.
+pub struct IoRead<R> {
+    reader: R,
+    peeked: Option<u8>,
+}
+
+impl<R> IoRead<R>
+where
+    R: io::Read,
+{
+    /// Creates a new CBOR input source from an `io::Read`.
+    pub fn new(reader: R) -> IoRead<R> {
+        IoRead {
+            reader,
+            peeked: None,
+        }
+    }
+}
+
+impl<R> private::Sealed for IoRead<R> {}
+
+impl<'de, R> Read<'de> for IoRead<R>
+where
+    R: io::Read,
+{
+    fn next(&mut self) -> Result<Option<u8>, Error> {
+        if let Some(b) = self.peeked.take() {
+            return Ok(Some(b));
+        }
+
+        let mut buf = [0];
+        loop {
+            match self.reader.read(&mut buf) {
+                Ok(0) => return Ok(None),
+                Ok(_) => return Ok(Some(buf[0])),
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(Error::io(e)),
+            }
+        }
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, Error> {
+        if self.peeked.is_none() {
+            self.peeked = self.next()?;
+        }
+        Ok(self.peeked)
+    }
+
+    fn read<'s>(
+        &'s mut self,
+        len: usize,
+        scratch: &'s mut Vec<u8>,
+    ) -> Result<Reference<'de, 's, [u8]>, Error> {
+        scratch.clear();
+        if len == 0 {
+            return Ok(Reference::Copied(scratch));
+        }
+
+        scratch.extend(self.peeked.take());
+        // the length hasn't been validated, so let the buffer grow as data actually arrives
+        (&mut self.reader)
+            .take((len - scratch.len()) as u64)
+            .read_to_end(scratch)
+            .map_err(Error::io)?;
+        if scratch.len() < len {
+            return Err(Error::eof());
+        }
+
+        Ok(Reference::Copied(scratch))
+    }
+}
@@ -0,0 +1,67 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! CBOR serialization support.
+//!
+//! [CBOR](https://tools.ietf.org/html/rfc7049) is a compact binary encoding of a superset of the JSON data model. It
+//! preserves the Conjure semantics of the `json` module, though some of them fall out of the format more naturally:
+//!
+//! * Floating point values are encoded natively, so non-finite values round trip without being converted to strings.
+//!   The strings `"Infinity"`, `"-Infinity"`, and `"NaN"` are still accepted when deserializing.
+//! * Byte sequences are encoded as native byte strings rather than Base64-encoded strings. Base64-encoded strings are
+//!   still accepted when deserializing.
+//! * Map keys are always encoded as text strings, with other primitive keys stringified as they are in JSON.
+//!
+//! As with JSON, Conjure clients should ignore unknown fields while Conjure servers should trigger errors.
+//!
+//! The deserializers accept indefinite-length items and half precision floats. Semantic tags other than bignums are
+//! skipped.
+
+pub use crate::cbor::de::client::{client_from_reader, client_from_slice, ClientDeserializer};
+pub use crate::cbor::de::read::{IoRead, Read, SliceRead};
+pub use crate::cbor::de::server::{server_from_reader, server_from_slice, ServerDeserializer};
+pub use crate::cbor::error::Error;
+pub use crate::cbor::ser::{to_vec, to_writer, Serializer};
+
+mod de;
+mod error;
+mod ser;
+#[cfg(test)]
+mod test;
+
+const MAJOR_UNSIGNED: u8 = 0;
+const MAJOR_NEGATIVE: u8 = 1;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_TAG: u8 = 6;
+const MAJOR_SIMPLE: u8 = 7;
+
+const INFO_U8: u8 = 24;
+const INFO_U16: u8 = 25;
+const INFO_U32: u8 = 26;
+const INFO_U64: u8 = 27;
+const INFO_INDEFINITE: u8 = 31;
+
+const TAG_POSITIVE_BIGNUM: u64 = 2;
+const TAG_NEGATIVE_BIGNUM: u64 = 3;
+
+const SIMPLE_FALSE: u8 = 0xf4;
+const SIMPLE_TRUE: u8 = 0xf5;
+const SIMPLE_NULL: u8 = 0xf6;
+const SIMPLE_UNDEFINED: u8 = 0xf7;
+const FLOAT_HALF: u8 = 0xf9;
+const FLOAT_SINGLE: u8 = 0xfa;
+const FLOAT_DOUBLE: u8 = 0xfb;
+const BREAK: u8 = 0xff;
@@ -0,0 +1,521 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use serde::ser;
+use std::convert::TryFrom;
+use std::io::Write;
+
+use crate::cbor::error::Error;
+use crate::cbor::{
+    BREAK, FLOAT_DOUBLE, FLOAT_SINGLE, INFO_INDEFINITE, INFO_U16, INFO_U32, INFO_U64, INFO_U8,
+    MAJOR_ARRAY, MAJOR_BYTES, MAJOR_MAP, MAJOR_NEGATIVE, MAJOR_TAG, MAJOR_TEXT, MAJOR_UNSIGNED,
+    SIMPLE_FALSE, SIMPLE_NULL, SIMPLE_TRUE, TAG_NEGATIVE_BIGNUM, TAG_POSITIVE_BIGNUM,
+};
+
+/// Serializes a value as CBOR into a byte buffer.
+pub fn to_vec<T>(value: &T) -> Result<Vec<u8>, Error>
+where
+    T: ?Sized + ser::Serialize,
+{
+    let mut buf = Vec::with_capacity(128);
+    value.serialize(&mut Serializer::new(&mut buf))?;
+    Ok(buf)
+}
+
+/// Serializes a value as CBOR into a writer.
+pub fn to_writer<W, T>(writer: W, value: &T) -> Result<(), Error>
+where
+    W: Write,
+    T: ?Sized + ser::Serialize,
+{
+    value.serialize(&mut Serializer::new(writer))
+}
+
+/// A serde CBOR serializer compatible with Conjure.
+///
+/// Sequences and maps are written with a definite length when one is provided, and with an indefinite length
+/// otherwise.
+pub struct Serializer<W> {
+    writer: W,
+}
+
+impl<W> Serializer<W>
+where
+    W: Write,
+{
+    /// Creates a new Conjure CBOR serializer.
+    pub fn new(writer: W) -> Serializer<W> {
+        Serializer { writer }
+    }
+
+    /// Returns the inner writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+        self.writer.write_all(buf).map_err(Error::io)
+    }
+
+    // writes the initial byte of a data item along with its argument in the smallest form that holds it
+    fn write_head(&mut self, major: u8, v: u64) -> Result<(), Error> {
+        let mut buf = [0; 9];
+        let len = if v < u64::from(INFO_U8) {
+            buf[0] = v as u8;
+            1
+        } else if let Ok(v) = u8::try_from(v) {
+            buf[0] = INFO_U8;
+            buf[1] = v;
+            2
+        } else if let Ok(v) = u16::try_from(v) {
+            buf[0] = INFO_U16;
+            buf[1..3].copy_from_slice(&v.to_be_bytes());
+            3
+        } else if let Ok(v) = u32::try_from(v) {
+            buf[0] = INFO_U32;
+            buf[1..5].copy_from_slice(&v.to_be_bytes());
+            5
+        } else {
+            buf[0] = INFO_U64;
+            buf[1..].copy_from_slice(&v.to_be_bytes());
+            9
+        };
+        buf[0] |= major << 5;
+
+        self.write(&buf[..len])
+    }
+
+    fn write_indefinite(&mut self, major: u8) -> Result<(), Error> {
+        self.write(&[major << 5 | INFO_INDEFINITE])
+    }
+
+    fn write_i64(&mut self, v: i64) -> Result<(), Error> {
+        if v < 0 {
+            self.write_head(MAJOR_NEGATIVE, !v as u64)
+        } else {
+            self.write_head(MAJOR_UNSIGNED, v as u64)
+        }
+    }
+
+    fn write_i128(&mut self, v: i128) -> Result<(), Error> {
+        if let Ok(v) = i64::try_from(v) {
+            return self.write_i64(v);
+        }
+
+        // negative values are encoded as -1 - n
+        if v < 0 {
+            match u64::try_from(!v) {
+                Ok(n) => self.write_head(MAJOR_NEGATIVE, n),
+                Err(_) => self.write_bignum(TAG_NEGATIVE_BIGNUM, !v as u128),
+            }
+        } else {
+            self.write_u128(v as u128)
+        }
+    }
+
+    fn write_u128(&mut self, v: u128) -> Result<(), Error> {
+        match u64::try_from(v) {
+            Ok(v) => self.write_head(MAJOR_UNSIGNED, v),
+            Err(_) => self.write_bignum(TAG_POSITIVE_BIGNUM, v),
+        }
+    }
+
+    fn write_bignum(&mut self, tag: u64, v: u128) -> Result<(), Error> {
+        let bytes = v.to_be_bytes();
+        let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+        self.write_head(MAJOR_TAG, tag)?;
+        self.write_bytes(&bytes[start..])
+    }
+
+    fn write_bytes(&mut self, v: &[u8]) -> Result<(), Error> {
+        self.write_head(MAJOR_BYTES, v.len() as u64)?;
+        self.write(v)
+    }
+
+    fn write_str(&mut self, v: &str) -> Result<(), Error> {
+        self.write_head(MAJOR_TEXT, v.len() as u64)?;
+        self.write(v.as_bytes())
+    }
+
+    fn start(&mut self, major: u8, len: Option<usize>) -> Result<Compound<'_, W>, Error> {
+        match len {
+            Some(len) => self.write_head(major, len as u64)?,
+            None => self.write_indefinite(major)?,
+        }
+
+        Ok(Compound {
+            ser: self,
+            remaining: len,
+        })
+    }
+
+    // enum variants other than unit variants are encoded as single entry maps
+    fn start_variant(&mut self, variant: &str) -> Result<(), Error> {
+        self.write_head(MAJOR_MAP, 1)?;
+        self.write_str(variant)
+    }
+}
+
+impl<'a, W> ser::Serializer for &'a mut Serializer<W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Compound<'a, W>;
+    type SerializeTuple = Compound<'a, W>;
+    type SerializeTupleStruct = Compound<'a, W>;
+    type SerializeTupleVariant = Compound<'a, W>;
+    type SerializeMap = Compound<'a, W>;
+    type SerializeStruct = Compound<'a, W>;
+    type SerializeStructVariant = Compound<'a, W>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.write(&[if v { SIMPLE_TRUE } else { SIMPLE_FALSE }])
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> {
+        self.write_i64(i64::from(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Error> {
+        self.write_i64(i64::from(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Error> {
+        self.write_i64(i64::from(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        self.write_i64(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), Error> {
+        self.write_i128(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> {
+        self.write_head(MAJOR_UNSIGNED, u64::from(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Error> {
+        self.write_head(MAJOR_UNSIGNED, u64::from(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Error> {
+        self.write_head(MAJOR_UNSIGNED, u64::from(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Error> {
+        self.write_head(MAJOR_UNSIGNED, v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), Error> {
+        self.write_u128(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Error> {
+        let mut buf = [FLOAT_SINGLE, 0, 0, 0, 0];
+        buf[1..].copy_from_slice(&v.to_bits().to_be_bytes());
+        self.write(&buf)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Error> {
+        let mut buf = [FLOAT_DOUBLE, 0, 0, 0, 0, 0, 0, 0, 0];
+        buf[1..].copy_from_slice(&v.to_bits().to_be_bytes());
+        self.write(&buf)
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.write_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        self.write_str(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        self.write_bytes(v)
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.write(&[SIMPLE_NULL])
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.write(&[SIMPLE_NULL])
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), Error> {
+        self.write(&[SIMPLE_NULL])
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.write_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.start_variant(variant)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Compound<'a, W>, Error> {
+        self.start(MAJOR_ARRAY, len)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Compound<'a, W>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, len: usize) -> Result<Compound<'a, W>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a, W>, Error> {
+        self.start_variant(variant)?;
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Compound<'a, W>, Error> {
+        self.start(MAJOR_MAP, len)
+    }
+
+    fn serialize_struct(self, _: &'static str, len: usize) -> Result<Compound<'a, W>, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a, W>, Error> {
+        self.start_variant(variant)?;
+        self.serialize_map(Some(len))
+    }
+}
+
+pub struct Compound<'a, W> {
+    ser: &'a mut Serializer<W>,
+    // the number of elements or entries left to write, or `None` for an indefinite-length item
+    remaining: Option<usize>,
+}
+
+impl<'a, W> Compound<'a, W>
+where
+    W: Write,
+{
+    fn next(&mut self) -> Result<(), Error> {
+        match &mut self.remaining {
+            Some(0) => Err(Error::message("more items than the declared length")),
+            Some(remaining) => {
+                *remaining -= 1;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        match self.remaining {
+            Some(0) => Ok(()),
+            Some(_) => Err(Error::message("fewer items than the declared length")),
+            None => self.ser.write(&[BREAK]),
+        }
+    }
+}
+
+impl<'a, W> ser::SerializeSeq for Compound<'a, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.next()?;
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl<'a, W> ser::SerializeTuple for Compound<'a, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl<'a, W> ser::SerializeTupleStruct for Compound<'a, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl<'a, W> ser::SerializeTupleVariant for Compound<'a, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl<'a, W> ser::SerializeMap for Compound<'a, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.next()?;
+        key.serialize(MapKeySerializer {
+            ser: &mut *self.ser,
+        })
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl<'a, W> ser::SerializeStruct for Compound<'a, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.next()?;
+        self.ser.write_str(key)?;
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+impl<'a, W> ser::SerializeStructVariant for Compound<'a, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish()
+    }
+}
+
+// map keys are always written as text strings for compatibility with JSON
+map_key_serializer!(write_str);
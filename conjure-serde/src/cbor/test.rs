@@ -0,0 +1,209 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::f64;
+use std::fmt::Debug;
+
+use crate::cbor::{client_from_reader, client_from_slice, server_from_slice, to_vec};
+
+fn test_de<T>(ty: &T, cbor: &[u8])
+where
+    T: DeserializeOwned + PartialEq + Debug,
+{
+    assert_eq!(*ty, client_from_slice::<T>(cbor).unwrap());
+    assert_eq!(*ty, server_from_slice::<T>(cbor).unwrap());
+
+    let mut r = cbor;
+    assert_eq!(*ty, client_from_reader::<_, T>(&mut r).unwrap());
+}
+
+fn test_serde<T>(ty: &T, expected_cbor: &[u8])
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    assert_eq!(to_vec(ty).unwrap(), expected_cbor);
+    test_de(ty, expected_cbor);
+}
+
+#[test]
+fn scalars() {
+    test_serde(&0, &[0x00]);
+    test_serde(&23, &[0x17]);
+    test_serde(&24, &[0x18, 0x18]);
+    test_serde(&1000, &[0x19, 0x03, 0xe8]);
+    test_serde(&1_000_000, &[0x1a, 0x00, 0x0f, 0x42, 0x40]);
+    test_serde(
+        &1_000_000_000_000i64,
+        &[0x1b, 0x00, 0x00, 0x00, 0xe8, 0xd4, 0xa5, 0x10, 0x00],
+    );
+    test_serde(&-1, &[0x20]);
+    test_serde(&-1000, &[0x39, 0x03, 0xe7]);
+    test_serde(&true, &[0xf5]);
+    test_serde(&None::<i32>, &[0xf6]);
+    test_serde(&"".to_string(), &[0x60]);
+    test_serde(&"foo".to_string(), b"\x63foo");
+    test_serde(&"é".to_string(), &[0x62, 0xc3, 0xa9]);
+    test_serde(&1.5, &[0xfb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0]);
+    test_serde(&1.5f32, &[0xfa, 0x3f, 0xc0, 0, 0]);
+
+    test_de(&None::<i32>, &[0xf7]);
+    test_de(&1.5, &[0xf9, 0x3e, 0x00]);
+    test_de(&-2f32.powi(-14), &[0xf9, 0x84, 0x00]);
+}
+
+#[test]
+fn big_integers() {
+    test_serde(
+        &!0u64,
+        &[0x1b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+    );
+    test_serde(
+        &-18_446_744_073_709_551_616i128,
+        &[0x3b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff],
+    );
+    test_serde(
+        &18_446_744_073_709_551_616u128,
+        &[
+            0xc2, 0x49, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ],
+    );
+    test_serde(
+        &-18_446_744_073_709_551_617i128,
+        &[
+            0xc3, 0x49, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ],
+    );
+    test_de(&1u64, &[0xc2, 0x41, 0x01]);
+}
+
+#[test]
+fn collections() {
+    test_serde(&vec![1, 2], &[0x82, 0x01, 0x02]);
+    test_serde(
+        &vec![(1, "a".to_string())]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>(),
+        b"\xa1\x611\x61a",
+    );
+
+    test_de(&vec![1, 2], &[0x9f, 0x01, 0x02, 0xff]);
+    test_de(&json!({"a": [1]}), b"\xbf\x61a\x9f\x01\xff\xff");
+    test_de(
+        &vec![(1, 2)].into_iter().collect::<BTreeMap<i32, i32>>(),
+        &[0xa1, 0x01, 0x02],
+    );
+}
+
+#[test]
+fn indefinite_strings() {
+    test_de(&"foobar".to_string(), b"\x7f\x63foo\x63bar\xff");
+    test_de(
+        &ByteBuf::from(b"foobar".to_vec()),
+        b"\x5f\x43foo\x43bar\xff",
+    );
+}
+
+#[test]
+fn binary_serde() {
+    test_serde(&ByteBuf::from(b"foobar".to_vec()), b"\x46foobar");
+    test_de(&ByteBuf::from(b"foobar".to_vec()), b"\x68Zm9vYmFy");
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn nonfinite_doubles() {
+    for &value in &[f64::INFINITY, f64::NEG_INFINITY, f64::NAN] {
+        let deserialized = client_from_slice::<f64>(&to_vec(&value).unwrap()).unwrap();
+        assert!((value.is_nan() && deserialized.is_nan()) || value == deserialized);
+    }
+
+    let deserialized = client_from_slice::<f64>(&to_vec("-Infinity").unwrap()).unwrap();
+    assert_eq!(deserialized, f64::NEG_INFINITY);
+    let deserialized = client_from_slice::<f64>(&[0xf9, 0x7c, 0x00]).unwrap();
+    assert_eq!(deserialized, f64::INFINITY);
+}
+
+#[test]
+fn tags() {
+    // a self-described CBOR datetime string
+    test_de(
+        &"2013-03-21T20:04:00Z".to_string(),
+        b"\xd9\xd9\xf7\xc0\x742013-03-21T20:04:00Z",
+    );
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum Enum {
+    Unit,
+    Newtype(i32),
+    Tuple(i32, i32),
+    Struct { foo: i32 },
+}
+
+#[test]
+fn enums() {
+    test_serde(&Enum::Unit, b"\x64Unit");
+    test_serde(&Enum::Newtype(1), b"\xa1\x67Newtype\x01");
+    test_serde(&Enum::Tuple(1, 2), b"\xa1\x65Tuple\x82\x01\x02");
+    test_serde(&Enum::Struct { foo: 1 }, b"\xa1\x66Struct\xa1\x63foo\x01");
+    test_de(&Enum::Newtype(1), b"\xbf\x67Newtype\x01\xff");
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Foo {
+    foo: i32,
+}
+
+#[test]
+fn client_unknown_fields() {
+    let cbor = to_vec(&json!({"foo": 1, "bogus": {"hello": [1, 2]}})).unwrap();
+    let deserialized = client_from_slice::<Foo>(&cbor).unwrap();
+    assert_eq!(Foo { foo: 1 }, deserialized);
+}
+
+#[test]
+fn server_unknown_fields() {
+    let cbor = to_vec(&json!({"foo": 1, "bogus": "hello"})).unwrap();
+    let e = server_from_slice::<Foo>(&cbor).unwrap_err();
+
+    assert!(e.to_string().contains("foo"));
+    assert!(e.to_string().contains("bogus"));
+
+    // unknown fields of nested values are still rejected, but other ignored values aren't
+    let cbor = to_vec(&json!({"a": {"foo": 1, "bogus": 2}})).unwrap();
+    assert!(server_from_slice::<BTreeMap<String, Foo>>(&cbor).is_err());
+    let value = server_from_slice::<serde::de::IgnoredAny>(&cbor);
+    assert!(value.is_ok());
+}
+
+#[test]
+fn invalid() {
+    assert!(client_from_slice::<i32>(&[]).is_err());
+    assert!(client_from_slice::<i32>(&[0x01, 0x01]).is_err());
+    assert!(client_from_slice::<i32>(&[0x1c]).is_err());
+    assert!(client_from_slice::<i32>(&[0x1f]).is_err());
+    assert!(client_from_slice::<String>(&[0x63, b'f']).is_err());
+    assert!(client_from_slice::<String>(&[0x62, 0xff, 0xff]).is_err());
+    assert!(client_from_slice::<Vec<i32>>(&[0x82, 0x01]).is_err());
+    assert!(client_from_slice::<Vec<i32>>(&[0x9f, 0x01]).is_err());
+    assert!(client_from_slice::<String>(b"\x7f\x43foo\xff").is_err());
+
+    let mut nested = vec![0x81; 200];
+    nested.push(0x01);
+    assert!(client_from_slice::<serde_json::Value>(&nested).is_err());
+}
@@ -0,0 +1,258 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use serde_json::ser::{CharEscape, CompactFormatter, Formatter};
+use std::fmt;
+use std::io::{self, Write};
+
+macro_rules! redirect {
+    ($($method:ident($($arg:ident: $ty:ty),*),)*) => {
+        $(
+            fn $method<W>(&mut self, writer: &mut W $(, $arg: $ty)*) -> io::Result<()>
+            where
+                W: ?Sized + Write,
+            {
+                match self.buf() {
+                    Some(buf) => CompactFormatter.$method(buf $(, $arg)*),
+                    None => CompactFormatter.$method(writer $(, $arg)*),
+                }
+            }
+        )*
+    };
+}
+
+/// A serde_json `Formatter` which writes a canonical form of its input.
+///
+/// Two semantically equal values are always written as identical bytes:
+///
+/// * No insignificant whitespace is written.
+/// * Object entries are sorted by key, comparing keys by their Unicode code points. This includes the stringified keys
+///   of maps with non-string keys.
+/// * Floating point values are written in their shortest round-trip form, formatted as by ECMAScript's
+///   `Number.prototype.toString` in the manner of RFC 8785. For example, `1.0` is written as `1`, `-0.0` as `0`, and
+///   `1e21` as `1e+21`.
+///
+/// The order of array elements is preserved, so sets should be represented by types with a deterministic iteration
+/// order. Object entries with `null` values are preserved as well, since they may be meaningful in maps and `Any`
+/// values. Conjure objects already omit absent optional fields.
+#[derive(Default)]
+pub struct CanonicalFormatter {
+    objects: Vec<Object>,
+}
+
+// an object whose entries are buffered until it's complete so they can be sorted
+#[derive(Default)]
+struct Object {
+    entries: Vec<(Vec<u8>, Vec<u8>)>,
+    in_key: bool,
+}
+
+impl CanonicalFormatter {
+    /// Creates a new canonical formatter.
+    pub fn new() -> CanonicalFormatter {
+        CanonicalFormatter::default()
+    }
+
+    // returns the buffer being written to if we're inside of an object
+    fn buf(&mut self) -> Option<&mut Vec<u8>> {
+        let object = self.objects.last_mut()?;
+        let in_key = object.in_key;
+        let (key, value) = object.entries.last_mut()?;
+        if in_key {
+            Some(key)
+        } else {
+            Some(value)
+        }
+    }
+
+    fn write_float<W, T>(&mut self, writer: &mut W, value: T) -> io::Result<()>
+    where
+        W: ?Sized + Write,
+        T: fmt::LowerExp,
+    {
+        let value = format_float(value);
+        match self.buf() {
+            Some(buf) => buf.write_all(value.as_bytes()),
+            None => writer.write_all(value.as_bytes()),
+        }
+    }
+}
+
+impl Formatter for CanonicalFormatter {
+    redirect!(
+        write_null(),
+        write_bool(value: bool),
+        write_i8(value: i8),
+        write_i16(value: i16),
+        write_i32(value: i32),
+        write_i64(value: i64),
+        write_i128(value: i128),
+        write_u8(value: u8),
+        write_u16(value: u16),
+        write_u32(value: u32),
+        write_u64(value: u64),
+        write_u128(value: u128),
+        write_number_str(value: &str),
+        begin_string(),
+        end_string(),
+        write_string_fragment(fragment: &str),
+        write_char_escape(char_escape: CharEscape),
+        begin_array(),
+        end_array(),
+        begin_array_value(first: bool),
+        end_array_value(),
+        write_raw_fragment(fragment: &str),
+    );
+
+    fn write_f32<W>(&mut self, writer: &mut W, value: f32) -> io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        self.write_float(writer, value)
+    }
+
+    fn write_f64<W>(&mut self, writer: &mut W, value: f64) -> io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        self.write_float(writer, value)
+    }
+
+    fn begin_object<W>(&mut self, _: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        self.objects.push(Object::default());
+        Ok(())
+    }
+
+    fn end_object<W>(&mut self, writer: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        let object = self.objects.pop().expect("unbalanced object");
+
+        let mut entries = object
+            .entries
+            .into_iter()
+            .map(|(key, value)| {
+                let decoded = serde_json::from_slice::<String>(&key)?;
+                Ok((decoded, key, value))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut buf = vec![b'{'];
+        for (i, (_, key, value)) in entries.iter().enumerate() {
+            if i != 0 {
+                buf.push(b',');
+            }
+            buf.extend_from_slice(key);
+            buf.push(b':');
+            buf.extend_from_slice(value);
+        }
+        buf.push(b'}');
+
+        match self.buf() {
+            Some(parent) => parent.extend_from_slice(&buf),
+            None => writer.write_all(&buf)?,
+        }
+        Ok(())
+    }
+
+    fn begin_object_key<W>(&mut self, _: &mut W, _: bool) -> io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        let object = self.objects.last_mut().expect("key outside of object");
+        object.entries.push((vec![], vec![]));
+        object.in_key = true;
+        Ok(())
+    }
+
+    fn end_object_key<W>(&mut self, _: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        self.objects
+            .last_mut()
+            .expect("key outside of object")
+            .in_key = false;
+        Ok(())
+    }
+
+    fn begin_object_value<W>(&mut self, _: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        Ok(())
+    }
+
+    fn end_object_value<W>(&mut self, _: &mut W) -> io::Result<()>
+    where
+        W: ?Sized + Write,
+    {
+        Ok(())
+    }
+}
+
+// Rust's exponential formatting produces the shortest round-trip digits, which we then lay out following ECMAScript.
+fn format_float<T>(value: T) -> String
+where
+    T: fmt::LowerExp,
+{
+    let s = format!("{:e}", value);
+    let negative = s.starts_with('-');
+    let s = s.trim_start_matches('-');
+
+    let idx = match s.find('e') {
+        Some(idx) => idx,
+        // non-finite values are handled by the serializer
+        None => return s.to_string(),
+    };
+    let digits = s[..idx].chars().filter(|c| *c != '.').collect::<String>();
+    if digits == "0" {
+        return "0".to_string();
+    }
+
+    let exponent = s[idx + 1..].parse::<i32>().unwrap();
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+
+    let mut out = String::new();
+    if negative {
+        out.push('-');
+    }
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend((0..n - k).map(|_| '0'));
+    } else if 0 < n && n <= 21 {
+        out.push_str(&digits[..n as usize]);
+        out.push('.');
+        out.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend((0..-n).map(|_| '0'));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        out.push('e');
+        out.push(if n > 0 { '+' } else { '-' });
+        out.push_str(&(n - 1).abs().to_string());
+    }
+    out
+}
@@ -13,13 +13,14 @@
 // limitations under the License.
 use serde::de;
 use serde_json::de::{IoRead, Read, SliceRead, StrRead};
-use serde_json::Error;
 use std::f32;
 use std::f64;
 use std::fmt;
 use std::io;
 
+use crate::json::de::path::{self, Track};
 use crate::json::de::{ByteBufVisitor, F32Visitor, F64Visitor};
+use crate::json::error::Error;
 
 /// Deserializes a value from a reader of JSON data.
 pub fn client_from_reader<R, T>(reader: R) -> Result<T, Error>
@@ -58,8 +59,21 @@ where
 /// A serde JSON deserializer appropriate for use by Conjure clients.
 ///
 /// In contrast to serde_json, the f32 and f64 types can be deserialized from the strings `"Infinity"`, `"-Infinity"`,
-/// and `"NaN"`, and bytes are deserialized from base64 encoded strings. Unknown object fields are ignored.
-pub struct ClientDeserializer<R>(serde_json::Deserializer<R>);
+/// and `"NaN"`, and bytes are deserialized from base64 encoded strings. Unknown object fields are ignored. Errors
+/// record the path to the value which triggered them.
+pub struct ClientDeserializer<R> {
+    de: serde_json::Deserializer<R>,
+    track: Track,
+}
+
+impl<R> ClientDeserializer<R> {
+    fn new(de: serde_json::Deserializer<R>) -> ClientDeserializer<R> {
+        ClientDeserializer {
+            de,
+            track: Track::default(),
+        }
+    }
+}
 
 impl<R> ClientDeserializer<IoRead<R>>
 where
@@ -67,14 +81,14 @@ where
 {
     /// Creates a Conjure JSON client deserializer from an `io::Read`.
     pub fn from_reader(reader: R) -> ClientDeserializer<IoRead<R>> {
-        ClientDeserializer(serde_json::Deserializer::from_reader(reader))
+        ClientDeserializer::new(serde_json::Deserializer::from_reader(reader))
     }
 }
 
 impl<'a> ClientDeserializer<SliceRead<'a>> {
     /// Creates a Conjure JSON client deserializer from a `&[u8]`.
     pub fn from_slice(bytes: &'a [u8]) -> ClientDeserializer<SliceRead<'a>> {
-        ClientDeserializer(serde_json::Deserializer::from_slice(bytes))
+        ClientDeserializer::new(serde_json::Deserializer::from_slice(bytes))
     }
 }
 
@@ -82,7 +96,7 @@ impl<'a> ClientDeserializer<StrRead<'a>> {
     /// Creates a Conjure JSON client deserializer from a `&str`.
     #[allow(clippy::should_implement_trait)] // match serde_json's API
     pub fn from_str(s: &'a str) -> ClientDeserializer<StrRead<'a>> {
-        ClientDeserializer(serde_json::Deserializer::from_str(s))
+        ClientDeserializer::new(serde_json::Deserializer::from_str(s))
     }
 }
 
@@ -92,7 +106,16 @@ where
 {
     /// Validates that the input stream is at the end or that it only has trailing whitespace.
     pub fn end(&mut self) -> Result<(), Error> {
-        self.0.end()
+        self.de.end().map_err(|e| Error::new(e, None))
+    }
+
+    fn deserialize_tracked<F, T>(&mut self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(
+            path::Deserializer<'_, &mut serde_json::Deserializer<R>>,
+        ) -> Result<T, serde_json::Error>,
+    {
+        f(path::Deserializer::new(&mut self.de, &self.track)).map_err(|e| self.track.error(e))
     }
 }
 
@@ -103,7 +126,7 @@ macro_rules! delegate_deserialize {
             where
                 V: de::Visitor<'de>
             {
-                (self.0).$method(Visitor(visitor))
+                self.deserialize_tracked(|de| de.$method(Visitor(visitor)))
             }
         )*
     }
@@ -143,14 +166,14 @@ where
     where
         V: de::Visitor<'de>,
     {
-        self.0.deserialize_any(F32Visitor(visitor))
+        self.deserialize_tracked(|de| de.deserialize_any(F32Visitor(visitor)))
     }
 
     fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, Error>
     where
         V: de::Visitor<'de>,
     {
-        self.0.deserialize_any(F64Visitor(visitor))
+        self.deserialize_tracked(|de| de.deserialize_any(F64Visitor(visitor)))
     }
 
     fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Error>
@@ -164,14 +187,14 @@ where
     where
         V: de::Visitor<'de>,
     {
-        self.0.deserialize_str(ByteBufVisitor(visitor))
+        self.deserialize_tracked(|de| de.deserialize_str(ByteBufVisitor(visitor)))
     }
 
     fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Error>
     where
         V: de::Visitor<'de>,
     {
-        self.0.deserialize_unit_struct(name, Visitor(visitor))
+        self.deserialize_tracked(|de| de.deserialize_unit_struct(name, Visitor(visitor)))
     }
 
     fn deserialize_newtype_struct<V>(
@@ -182,14 +205,14 @@ where
     where
         V: de::Visitor<'de>,
     {
-        self.0.deserialize_newtype_struct(name, Visitor(visitor))
+        self.deserialize_tracked(|de| de.deserialize_newtype_struct(name, Visitor(visitor)))
     }
 
     fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Error>
     where
         V: de::Visitor<'de>,
     {
-        self.0.deserialize_tuple(len, Visitor(visitor))
+        self.deserialize_tracked(|de| de.deserialize_tuple(len, Visitor(visitor)))
     }
 
     fn deserialize_tuple_struct<V>(
@@ -201,7 +224,7 @@ where
     where
         V: de::Visitor<'de>,
     {
-        self.0.deserialize_tuple_struct(name, len, Visitor(visitor))
+        self.deserialize_tracked(|de| de.deserialize_tuple_struct(name, len, Visitor(visitor)))
     }
 
     fn deserialize_struct<V>(
@@ -213,7 +236,7 @@ where
     where
         V: de::Visitor<'de>,
     {
-        self.0.deserialize_struct(name, fields, Visitor(visitor))
+        self.deserialize_tracked(|de| de.deserialize_struct(name, fields, Visitor(visitor)))
     }
 
     fn deserialize_enum<V>(
@@ -225,7 +248,7 @@ where
     where
         V: de::Visitor<'de>,
     {
-        self.0.deserialize_enum(name, variants, Visitor(visitor))
+        self.deserialize_tracked(|de| de.deserialize_enum(name, variants, Visitor(visitor)))
     }
 
     // we can't delegate this due to the signature, but luckily we know the answer
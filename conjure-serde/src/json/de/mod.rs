@@ -17,6 +17,7 @@ use std::f64;
 use std::fmt;
 
 pub mod client;
+mod path;
 pub mod server;
 
 macro_rules! delegate_visit {
@@ -34,7 +35,7 @@ macro_rules! delegate_visit {
 
 macro_rules! float_visitor {
     ($name:ident, $method:ident, $module:ident) => {
-        struct $name<T>(T);
+        pub(crate) struct $name<T>(pub(crate) T);
 
         impl<'de, T> de::Visitor<'de> for $name<T>
         where
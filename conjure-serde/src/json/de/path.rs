@@ -0,0 +1,849 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Tracking of the path to the value being deserialized, so errors can report where they happened.
+//!
+//! The wrappers here sit directly on top of serde_json's deserializer. Each array element and object value records
+//! its position in the shared `Track` as an error propagates out through it, so the innermost segment is recorded
+//! first. Since they see every value, the wrappers also enforce the structural resource limits of the deserializer.
+use serde::de;
+use serde_json::Value;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::fmt::Write;
+
+use crate::json::error::{Error, Limit};
+
+enum Segment {
+    Index(usize),
+    // a field or variant name declared by the type being deserialized
+    Field(String),
+    // `None` if the key couldn't be captured
+    Key(Option<String>),
+}
+
+impl Segment {
+    fn key(key: Option<String>, names: &[&str]) -> Segment {
+        match key {
+            Some(key) if names.contains(&&*key) => Segment::Field(key),
+            key => Segment::Key(key),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub(crate) struct Limits {
+    pub(crate) depth: Option<usize>,
+    pub(crate) string_length: Option<usize>,
+    pub(crate) elements: Option<usize>,
+    pub(crate) bytes: Option<usize>,
+}
+
+#[derive(Default)]
+pub(crate) struct Track {
+    segments: RefCell<Vec<Segment>>,
+    limits: Limits,
+    depth: Cell<usize>,
+    exceeded: Cell<Option<Limit>>,
+}
+
+impl Track {
+    pub(crate) fn new(limits: Limits) -> Track {
+        Track {
+            limits,
+            ..Track::default()
+        }
+    }
+
+    /// Returns an error indicating that the input exceeds the byte limit.
+    pub(crate) fn bytes_error(&self) -> serde_json::Error {
+        let max = self.limits.bytes.unwrap_or(0);
+        self.exceeded(Limit::Bytes, max, "input length")
+    }
+
+    /// Records that the byte limit was exceeded while reading input.
+    pub(crate) fn bytes_exceeded(&self) {
+        self.exceeded.set(Some(Limit::Bytes));
+    }
+
+    fn exceeded<E>(&self, limit: Limit, max: usize, what: &str) -> E
+    where
+        E: de::Error,
+    {
+        self.exceeded.set(Some(limit));
+        E::custom(format_args!("{} exceeds the limit of {}", what, max))
+    }
+
+    fn enter<E>(&self) -> Result<Depth<'_>, E>
+    where
+        E: de::Error,
+    {
+        let depth = self.depth.get() + 1;
+        match self.limits.depth {
+            Some(max) if depth > max => Err(self.exceeded(Limit::Depth, max, "nesting depth")),
+            _ => {
+                self.depth.set(depth);
+                Ok(Depth(self))
+            }
+        }
+    }
+
+    fn check_string<E>(&self, v: &str) -> Result<(), E>
+    where
+        E: de::Error,
+    {
+        match self.limits.string_length {
+            Some(max) if v.len() > max => {
+                Err(self.exceeded(Limit::StringLength, max, "string length"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_elements<E>(&self, count: usize) -> Result<(), E>
+    where
+        E: de::Error,
+    {
+        match self.limits.elements {
+            Some(max) if count > max => Err(self.exceeded(Limit::Elements, max, "element count")),
+            _ => Ok(()),
+        }
+    }
+
+    // records a segment of the path as an error propagates out of the value it identifies
+    fn on_error<E>(&self, segment: Segment) -> impl FnOnce(E) -> E + '_ {
+        move |e| {
+            self.segments.borrow_mut().push(segment);
+            e
+        }
+    }
+
+    // a successfully deserialized value means any previously recorded error was handled by a visitor
+    fn clear(&self) {
+        self.segments.borrow_mut().clear();
+        self.exceeded.set(None);
+    }
+
+    /// Creates an error with the path recorded as it propagated, resetting the track.
+    ///
+    /// Map keys are redacted from the safe version of the path since they come from the input, while field and
+    /// variant names are declared by the types being deserialized.
+    pub(crate) fn error(&self, e: serde_json::Error) -> Error {
+        let mut path = "$".to_string();
+        let mut safe_path = "$".to_string();
+        for segment in self.segments.borrow_mut().drain(..).rev() {
+            match segment {
+                Segment::Index(index) => {
+                    let _ = write!(path, "[{}]", index);
+                    let _ = write!(safe_path, "[{}]", index);
+                }
+                Segment::Field(key) => {
+                    write_key(&mut path, key.clone());
+                    write_key(&mut safe_path, key);
+                }
+                Segment::Key(Some(key)) => {
+                    write_key(&mut path, key);
+                    safe_path.push_str("[*]");
+                }
+                Segment::Key(None) => {
+                    path.push_str("[?]");
+                    safe_path.push_str("[*]");
+                }
+            }
+        }
+
+        Error::new(e, Some((path, safe_path))).with_limit(self.exceeded.take())
+    }
+}
+
+// resets the nesting depth when leaving an array or object
+struct Depth<'a>(&'a Track);
+
+impl Drop for Depth<'_> {
+    fn drop(&mut self) {
+        self.0.depth.set(self.0.depth.get() - 1);
+    }
+}
+
+fn write_key(path: &mut String, key: String) {
+    if is_identifier(&key) {
+        path.push('.');
+        path.push_str(&key);
+    } else {
+        let _ = write!(path, "[{}]", Value::String(key));
+    }
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+macro_rules! declared_names {
+    () => {
+        &[]
+    };
+    ($names:ident) => {
+        $names
+    };
+}
+
+// methods which declare field or variant names pass them along with `=> names`
+macro_rules! wrap_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*) $(=> $names:ident)?,)*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error>
+            where
+                V: de::Visitor<'de>,
+            {
+                let (de, visitor) = self.wrap(visitor, declared_names!($($names)?));
+                de.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+macro_rules! wrap_deserialize_all {
+    () => {
+        wrap_deserialize!(
+            deserialize_any(),
+            deserialize_bool(),
+            deserialize_i8(),
+            deserialize_i16(),
+            deserialize_i32(),
+            deserialize_i64(),
+            deserialize_i128(),
+            deserialize_u8(),
+            deserialize_u16(),
+            deserialize_u32(),
+            deserialize_u64(),
+            deserialize_u128(),
+            deserialize_f32(),
+            deserialize_f64(),
+            deserialize_char(),
+            deserialize_str(),
+            deserialize_string(),
+            deserialize_bytes(),
+            deserialize_byte_buf(),
+            deserialize_option(),
+            deserialize_unit(),
+            deserialize_unit_struct(name: &'static str),
+            deserialize_newtype_struct(name: &'static str),
+            deserialize_seq(),
+            deserialize_tuple(len: usize),
+            deserialize_tuple_struct(name: &'static str, len: usize),
+            deserialize_map(),
+            deserialize_struct(name: &'static str, fields: &'static [&'static str]) => fields,
+            deserialize_enum(name: &'static str, variants: &'static [&'static str]) => variants,
+            deserialize_identifier(),
+            deserialize_ignored_any(),
+        );
+
+        fn is_human_readable(&self) -> bool {
+            self.de.is_human_readable()
+        }
+    };
+}
+
+/// A deserializer which tracks the path to the values it deserializes.
+pub(crate) struct Deserializer<'a, D> {
+    de: D,
+    track: &'a Track,
+}
+
+impl<'a, D> Deserializer<'a, D> {
+    pub(crate) fn new(de: D, track: &'a Track) -> Deserializer<'a, D> {
+        Deserializer { de, track }
+    }
+
+    fn wrap<V>(self, visitor: V, names: &'static [&'static str]) -> (D, Visitor<'a, V>) {
+        let visitor = Visitor {
+            visitor,
+            track: self.track,
+            names,
+        };
+        (self.de, visitor)
+    }
+}
+
+impl<'a, 'de, D> de::Deserializer<'de> for Deserializer<'a, D>
+where
+    D: de::Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    wrap_deserialize_all!();
+}
+
+macro_rules! delegate_visit {
+    ($($method:ident = $ty:ty,)*) => {
+        $(
+            fn $method<E>(self, v: $ty) -> Result<V::Value, E>
+            where
+                E: de::Error,
+            {
+                self.visitor.$method(v)
+            }
+        )*
+    };
+}
+
+struct Visitor<'a, V> {
+    visitor: V,
+    track: &'a Track,
+    // the field or variant names declared by the type being deserialized
+    names: &'static [&'static str],
+}
+
+impl<'a, 'de, V> de::Visitor<'de> for Visitor<'a, V>
+where
+    V: de::Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.visitor.expecting(formatter)
+    }
+
+    delegate_visit!(
+        visit_bool = bool,
+        visit_i8 = i8,
+        visit_i16 = i16,
+        visit_i32 = i32,
+        visit_i64 = i64,
+        visit_i128 = i128,
+        visit_u8 = u8,
+        visit_u16 = u16,
+        visit_u32 = u32,
+        visit_u64 = u64,
+        visit_u128 = u128,
+        visit_f32 = f32,
+        visit_f64 = f64,
+        visit_char = char,
+        visit_bytes = &[u8],
+        visit_borrowed_bytes = &'de [u8],
+        visit_byte_buf = Vec<u8>,
+    );
+
+    fn visit_str<E>(self, v: &str) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.track.check_string(v)?;
+        self.visitor.visit_str(v)
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.track.check_string(v)?;
+        self.visitor.visit_borrowed_str(v)
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.track.check_string(&v)?;
+        self.visitor.visit_string(v)
+    }
+
+    fn visit_none<E>(self) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visitor.visit_none()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<V::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        self.visitor
+            .visit_some(Deserializer::new(deserializer, self.track))
+    }
+
+    fn visit_unit<E>(self) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visitor.visit_unit()
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<V::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        self.visitor
+            .visit_newtype_struct(Deserializer::new(deserializer, self.track))
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<V::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        let _depth = self.track.enter()?;
+        self.visitor.visit_seq(SeqAccess {
+            seq,
+            track: self.track,
+            index: 0,
+        })
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<V::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let _depth = self.track.enter()?;
+        self.visitor.visit_map(MapAccess {
+            map,
+            track: self.track,
+            names: self.names,
+            key: None,
+            count: 0,
+        })
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<V::Value, A::Error>
+    where
+        A: de::EnumAccess<'de>,
+    {
+        let _depth = self.track.enter()?;
+        self.visitor.visit_enum(EnumAccess {
+            data,
+            track: self.track,
+            names: self.names,
+        })
+    }
+}
+
+struct Seed<'a, S> {
+    seed: S,
+    track: &'a Track,
+}
+
+impl<'a, 'de, S> de::DeserializeSeed<'de> for Seed<'a, S>
+where
+    S: de::DeserializeSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<S::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let value = self
+            .seed
+            .deserialize(Deserializer::new(deserializer, self.track))?;
+        self.track.clear();
+        Ok(value)
+    }
+}
+
+struct SeqAccess<'a, A> {
+    seq: A,
+    track: &'a Track,
+    index: usize,
+}
+
+impl<'a, 'de, A> de::SeqAccess<'de> for SeqAccess<'a, A>
+where
+    A: de::SeqAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, A::Error>
+    where
+        S: de::DeserializeSeed<'de>,
+    {
+        let index = self.index;
+        self.index += 1;
+
+        let track = self.track;
+        let value = self
+            .seq
+            .next_element_seed(Seed { seed, track })
+            .map_err(track.on_error(Segment::Index(index)))?;
+        if value.is_some() {
+            track.check_elements(self.index)?;
+        }
+
+        Ok(value)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.seq.size_hint()
+    }
+}
+
+struct MapAccess<'a, A> {
+    map: A,
+    track: &'a Track,
+    names: &'static [&'static str],
+    key: Option<String>,
+    count: usize,
+}
+
+impl<'a, 'de, A> de::MapAccess<'de> for MapAccess<'a, A>
+where
+    A: de::MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, A::Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        self.key = None;
+        let key = self.map.next_key_seed(CaptureSeed {
+            seed,
+            track: self.track,
+            key: &mut self.key,
+        })?;
+        if key.is_some() {
+            self.count += 1;
+            self.track.check_elements(self.count)?;
+        }
+
+        Ok(key)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, A::Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let track = self.track;
+        let key = self.key.take();
+        self.map
+            .next_value_seed(Seed { seed, track })
+            .map_err(track.on_error(Segment::key(key, self.names)))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.map.size_hint()
+    }
+}
+
+struct EnumAccess<'a, A> {
+    data: A,
+    track: &'a Track,
+    names: &'static [&'static str],
+}
+
+impl<'a, 'de, A> de::EnumAccess<'de> for EnumAccess<'a, A>
+where
+    A: de::EnumAccess<'de>,
+{
+    type Error = A::Error;
+    type Variant = VariantAccess<'a, A::Variant>;
+
+    #[allow(clippy::type_complexity)]
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), A::Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let mut variant = None;
+        let (value, data) = self.data.variant_seed(CaptureSeed {
+            seed,
+            track: self.track,
+            key: &mut variant,
+        })?;
+
+        let access = VariantAccess {
+            data,
+            track: self.track,
+            variant: Segment::key(variant, self.names),
+        };
+        Ok((value, access))
+    }
+}
+
+// the contents of a variant are treated like the value of a single entry object
+struct VariantAccess<'a, A> {
+    data: A,
+    track: &'a Track,
+    variant: Segment,
+}
+
+impl<'a, 'de, A> de::VariantAccess<'de> for VariantAccess<'a, A>
+where
+    A: de::VariantAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), A::Error> {
+        let track = self.track;
+        let variant = self.variant;
+        self.data.unit_variant().map_err(track.on_error(variant))
+    }
+
+    fn newtype_variant_seed<S>(self, seed: S) -> Result<S::Value, A::Error>
+    where
+        S: de::DeserializeSeed<'de>,
+    {
+        let track = self.track;
+        let variant = self.variant;
+        self.data
+            .newtype_variant_seed(Seed { seed, track })
+            .map_err(track.on_error(variant))
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, A::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let track = self.track;
+        let variant = self.variant;
+        self.data
+            .tuple_variant(
+                len,
+                Visitor {
+                    visitor,
+                    track,
+                    names: &[],
+                },
+            )
+            .map_err(track.on_error(variant))
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, A::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let track = self.track;
+        let variant = self.variant;
+        self.data
+            .struct_variant(
+                fields,
+                Visitor {
+                    visitor,
+                    track,
+                    names: fields,
+                },
+            )
+            .map_err(track.on_error(variant))
+    }
+}
+
+// records the string form of an object key or enum variant as it's deserialized
+struct CaptureSeed<'a, 'b, S> {
+    seed: S,
+    track: &'a Track,
+    key: &'b mut Option<String>,
+}
+
+impl<'a, 'b, 'de, S> de::DeserializeSeed<'de> for CaptureSeed<'a, 'b, S>
+where
+    S: de::DeserializeSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<S::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        self.seed.deserialize(CaptureDeserializer {
+            de: deserializer,
+            track: self.track,
+            key: self.key,
+        })
+    }
+}
+
+struct CaptureDeserializer<'a, 'b, D> {
+    de: D,
+    track: &'a Track,
+    key: &'b mut Option<String>,
+}
+
+impl<'a, 'b, D> CaptureDeserializer<'a, 'b, D> {
+    fn wrap<V>(self, visitor: V, _: &'static [&'static str]) -> (D, CaptureVisitor<'a, 'b, V>) {
+        let visitor = CaptureVisitor {
+            visitor,
+            track: self.track,
+            key: self.key,
+        };
+        (self.de, visitor)
+    }
+}
+
+impl<'a, 'b, 'de, D> de::Deserializer<'de> for CaptureDeserializer<'a, 'b, D>
+where
+    D: de::Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    wrap_deserialize_all!();
+}
+
+macro_rules! capture_visit {
+    ($($method:ident = $ty:ty,)*) => {
+        $(
+            fn $method<E>(self, v: $ty) -> Result<V::Value, E>
+            where
+                E: de::Error,
+            {
+                *self.key = Some(v.to_string());
+                self.visitor.$method(v)
+            }
+        )*
+    };
+}
+
+struct CaptureVisitor<'a, 'b, V> {
+    visitor: V,
+    track: &'a Track,
+    key: &'b mut Option<String>,
+}
+
+impl<'a, 'b, 'de, V> de::Visitor<'de> for CaptureVisitor<'a, 'b, V>
+where
+    V: de::Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.visitor.expecting(formatter)
+    }
+
+    capture_visit!(
+        visit_bool = bool,
+        visit_i8 = i8,
+        visit_i16 = i16,
+        visit_i32 = i32,
+        visit_i64 = i64,
+        visit_i128 = i128,
+        visit_u8 = u8,
+        visit_u16 = u16,
+        visit_u32 = u32,
+        visit_u64 = u64,
+        visit_u128 = u128,
+        visit_f32 = f32,
+        visit_f64 = f64,
+        visit_char = char,
+    );
+
+    fn visit_str<E>(self, v: &str) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.track.check_string(v)?;
+        *self.key = Some(v.to_string());
+        self.visitor.visit_str(v)
+    }
+
+    fn visit_borrowed_str<E>(self, v: &'de str) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.track.check_string(v)?;
+        *self.key = Some(v.to_string());
+        self.visitor.visit_borrowed_str(v)
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.track.check_string(&v)?;
+        *self.key = Some(v.clone());
+        self.visitor.visit_string(v)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visitor.visit_bytes(v)
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visitor.visit_borrowed_bytes(v)
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visitor.visit_byte_buf(v)
+    }
+
+    fn visit_none<E>(self) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visitor.visit_none()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<V::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        self.visitor.visit_some(CaptureDeserializer {
+            de: deserializer,
+            track: self.track,
+            key: self.key,
+        })
+    }
+
+    fn visit_unit<E>(self) -> Result<V::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visitor.visit_unit()
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<V::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        self.visitor.visit_newtype_struct(CaptureDeserializer {
+            de: deserializer,
+            track: self.track,
+            key: self.key,
+        })
+    }
+
+    fn visit_seq<A>(self, seq: A) -> Result<V::Value, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        self.visitor.visit_seq(seq)
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<V::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        self.visitor.visit_map(map)
+    }
+
+    fn visit_enum<A>(self, data: A) -> Result<V::Value, A::Error>
+    where
+        A: de::EnumAccess<'de>,
+    {
+        self.visitor.visit_enum(data)
+    }
+}
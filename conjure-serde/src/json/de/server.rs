@@ -13,11 +13,14 @@
 // limitations under the License.
 use serde::de;
 use serde_json::de::{IoRead, Read, SliceRead, StrRead};
-use serde_json::Error;
 use std::fmt;
 use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
+use crate::json::de::path::{self, Limits, Track};
 use crate::json::de::{ByteBufVisitor, F32Visitor, F64Visitor};
+use crate::json::error::{Error, Limit};
 use std::borrow::Cow;
 
 /// Deserializes a value from a reader of JSON data.
@@ -57,8 +60,34 @@ where
 /// A serde JSON deserializer appropriate for use by Conjure servers.
 ///
 /// In contrast to serde_json, the f32 and f64 types can be deserialized from the strings `"Infinity"`, `"-Infinity"`,
-/// and `"NaN"`, and bytes are deserialized from base64 encoded strings. Unknown object fields trigger errors.
-pub struct ServerDeserializer<R>(serde_json::Deserializer<R>);
+/// and `"NaN"`, and bytes are deserialized from base64 encoded strings. Unknown object fields trigger errors. Errors
+/// record the path to the value which triggered them.
+///
+/// No limits are placed on the size or structure of the input by default. Use a `ServerDeserializerBuilder` to
+/// create deserializers which enforce limits when handling untrusted input.
+pub struct ServerDeserializer<R> {
+    de: serde_json::Deserializer<R>,
+    track: Track,
+    // set once the input is known to exceed the byte limit
+    bytes_exceeded: Option<Arc<AtomicBool>>,
+}
+
+impl<R> ServerDeserializer<R> {
+    fn new(de: serde_json::Deserializer<R>) -> ServerDeserializer<R> {
+        ServerDeserializer {
+            de,
+            track: Track::default(),
+            bytes_exceeded: None,
+        }
+    }
+
+    fn is_bytes_exceeded(&self) -> bool {
+        match &self.bytes_exceeded {
+            Some(bytes_exceeded) => bytes_exceeded.load(Ordering::Relaxed),
+            None => false,
+        }
+    }
+}
 
 impl<R> ServerDeserializer<IoRead<R>>
 where
@@ -66,14 +95,14 @@ where
 {
     /// Creates a Conjure JSON server deserializer from an `io::Read`.
     pub fn from_reader(reader: R) -> ServerDeserializer<IoRead<R>> {
-        ServerDeserializer(serde_json::Deserializer::from_reader(reader))
+        ServerDeserializer::new(serde_json::Deserializer::from_reader(reader))
     }
 }
 
 impl<'a> ServerDeserializer<SliceRead<'a>> {
     /// Creates a Conjure JSON server deserializer from a `&[u8]`.
     pub fn from_slice(bytes: &'a [u8]) -> ServerDeserializer<SliceRead<'a>> {
-        ServerDeserializer(serde_json::Deserializer::from_slice(bytes))
+        ServerDeserializer::new(serde_json::Deserializer::from_slice(bytes))
     }
 }
 
@@ -81,7 +110,7 @@ impl<'a> ServerDeserializer<StrRead<'a>> {
     /// Creates a Conjure JSON server deserializer from a `&str`.
     #[allow(clippy::should_implement_trait)] // match serde_json's API
     pub fn from_str(s: &'a str) -> ServerDeserializer<StrRead<'a>> {
-        ServerDeserializer(serde_json::Deserializer::from_str(s))
+        ServerDeserializer::new(serde_json::Deserializer::from_str(s))
     }
 }
 
@@ -91,7 +120,168 @@ where
 {
     /// Validates that the input stream is at the end or that it only has trailing whitespace.
     pub fn end(&mut self) -> Result<(), Error> {
-        self.0.end()
+        match self.de.end() {
+            Ok(()) => Ok(()),
+            Err(e) if e.is_io() && self.is_bytes_exceeded() => {
+                Err(Error::new(e, None).with_limit(Some(Limit::Bytes)))
+            }
+            Err(e) => Err(Error::new(e, None)),
+        }
+    }
+
+    fn deserialize_tracked<F, T>(&mut self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(
+            path::Deserializer<'_, &mut serde_json::Deserializer<R>>,
+        ) -> Result<T, serde_json::Error>,
+    {
+        if self.is_bytes_exceeded() {
+            let e = self.track.bytes_error();
+            return Err(self.track.error(e));
+        }
+
+        match f(path::Deserializer::new(&mut self.de, &self.track)) {
+            Ok(value) => Ok(value),
+            Err(e) => {
+                if e.is_io() && self.is_bytes_exceeded() {
+                    self.track.bytes_exceeded();
+                }
+                Err(self.track.error(e))
+            }
+        }
+    }
+}
+
+/// A builder for `ServerDeserializer`s which enforce limits on the resources consumed by their input.
+///
+/// Deserializers created by the builder return errors reporting the exceeded `Limit` as soon as their input breaks
+/// one of the configured limits. No limits are configured by default.
+#[derive(Debug, Clone, Default)]
+pub struct ServerDeserializerBuilder {
+    limits: Limits,
+}
+
+impl ServerDeserializerBuilder {
+    /// Creates a new builder with no limits configured.
+    pub fn new() -> ServerDeserializerBuilder {
+        ServerDeserializerBuilder::default()
+    }
+
+    /// Sets the maximum nesting depth of arrays and objects.
+    ///
+    /// A top-level object has a depth of 1, an array within it a depth of 2, and so on.
+    pub fn max_depth(&mut self, max_depth: usize) -> &mut ServerDeserializerBuilder {
+        self.limits.depth = Some(max_depth);
+        self
+    }
+
+    /// Sets the maximum length in bytes of strings, including object keys.
+    pub fn max_string_length(
+        &mut self,
+        max_string_length: usize,
+    ) -> &mut ServerDeserializerBuilder {
+        self.limits.string_length = Some(max_string_length);
+        self
+    }
+
+    /// Sets the maximum number of elements in each array and entries in each object.
+    pub fn max_elements(&mut self, max_elements: usize) -> &mut ServerDeserializerBuilder {
+        self.limits.elements = Some(max_elements);
+        self
+    }
+
+    /// Sets the maximum number of bytes of input.
+    pub fn max_bytes(&mut self, max_bytes: usize) -> &mut ServerDeserializerBuilder {
+        self.limits.bytes = Some(max_bytes);
+        self
+    }
+
+    /// Creates a Conjure JSON server deserializer from an `io::Read`.
+    pub fn from_reader<R>(&self, reader: R) -> ServerDeserializer<IoRead<LimitedReader<R>>>
+    where
+        R: io::Read,
+    {
+        let exceeded = Arc::new(AtomicBool::new(false));
+        let reader = LimitedReader {
+            reader,
+            remaining: self.limits.bytes,
+            exceeded: exceeded.clone(),
+        };
+        self.build(serde_json::Deserializer::from_reader(reader), exceeded)
+    }
+
+    /// Creates a Conjure JSON server deserializer from a `&[u8]`.
+    pub fn from_slice<'a>(&self, bytes: &'a [u8]) -> ServerDeserializer<SliceRead<'a>> {
+        let exceeded = self.check_len(bytes.len());
+        self.build(serde_json::Deserializer::from_slice(bytes), exceeded)
+    }
+
+    /// Creates a Conjure JSON server deserializer from a `&str`.
+    pub fn from_str<'a>(&self, s: &'a str) -> ServerDeserializer<StrRead<'a>> {
+        let exceeded = self.check_len(s.len());
+        self.build(serde_json::Deserializer::from_str(s), exceeded)
+    }
+
+    fn check_len(&self, len: usize) -> Arc<AtomicBool> {
+        let exceeded = match self.limits.bytes {
+            Some(max) => len > max,
+            None => false,
+        };
+        Arc::new(AtomicBool::new(exceeded))
+    }
+
+    fn build<R>(
+        &self,
+        de: serde_json::Deserializer<R>,
+        bytes_exceeded: Arc<AtomicBool>,
+    ) -> ServerDeserializer<R> {
+        ServerDeserializer {
+            de,
+            track: Track::new(self.limits),
+            bytes_exceeded: Some(bytes_exceeded),
+        }
+    }
+}
+
+/// An `io::Read` adapter which fails once its input exceeds a `ServerDeserializerBuilder`'s byte limit.
+pub struct LimitedReader<R> {
+    reader: R,
+    remaining: Option<usize>,
+    exceeded: Arc<AtomicBool>,
+}
+
+impl<R> io::Read for LimitedReader<R>
+where
+    R: io::Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = match self.remaining {
+            Some(remaining) => remaining,
+            None => return self.reader.read(buf),
+        };
+
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        // input of exactly the limit is fine, so only fail once we see a byte past it
+        if remaining == 0 {
+            return match self.reader.read(&mut [0])? {
+                0 => Ok(0),
+                _ => {
+                    self.exceeded.store(true, Ordering::Relaxed);
+                    Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "input length exceeds the byte limit",
+                    ))
+                }
+            };
+        }
+
+        let len = usize::min(buf.len(), remaining);
+        let nread = self.reader.read(&mut buf[..len])?;
+        self.remaining = Some(remaining - nread);
+        Ok(nread)
     }
 }
 
@@ -102,7 +292,7 @@ macro_rules! delegate_deserialize {
             where
                 V: de::Visitor<'de>
             {
-                (self.0).$method(Visitor(visitor))
+                self.deserialize_tracked(|de| de.$method(Visitor(visitor)))
             }
         )*
     }
@@ -142,14 +332,14 @@ where
     where
         V: de::Visitor<'de>,
     {
-        self.0.deserialize_any(F32Visitor(visitor))
+        self.deserialize_tracked(|de| de.deserialize_any(F32Visitor(visitor)))
     }
 
     fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, Error>
     where
         V: de::Visitor<'de>,
     {
-        self.0.deserialize_any(F64Visitor(visitor))
+        self.deserialize_tracked(|de| de.deserialize_any(F64Visitor(visitor)))
     }
 
     fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Error>
@@ -163,14 +353,14 @@ where
     where
         V: de::Visitor<'de>,
     {
-        self.0.deserialize_str(ByteBufVisitor(visitor))
+        self.deserialize_tracked(|de| de.deserialize_str(ByteBufVisitor(visitor)))
     }
 
     fn deserialize_unit_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value, Error>
     where
         V: de::Visitor<'de>,
     {
-        self.0.deserialize_unit_struct(name, Visitor(visitor))
+        self.deserialize_tracked(|de| de.deserialize_unit_struct(name, Visitor(visitor)))
     }
 
     fn deserialize_newtype_struct<V>(
@@ -181,14 +371,14 @@ where
     where
         V: de::Visitor<'de>,
     {
-        self.0.deserialize_newtype_struct(name, Visitor(visitor))
+        self.deserialize_tracked(|de| de.deserialize_newtype_struct(name, Visitor(visitor)))
     }
 
     fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Error>
     where
         V: de::Visitor<'de>,
     {
-        self.0.deserialize_tuple(len, Visitor(visitor))
+        self.deserialize_tracked(|de| de.deserialize_tuple(len, Visitor(visitor)))
     }
 
     fn deserialize_tuple_struct<V>(
@@ -200,7 +390,7 @@ where
     where
         V: de::Visitor<'de>,
     {
-        self.0.deserialize_tuple_struct(name, len, Visitor(visitor))
+        self.deserialize_tracked(|de| de.deserialize_tuple_struct(name, len, Visitor(visitor)))
     }
 
     fn deserialize_struct<V>(
@@ -212,8 +402,9 @@ where
     where
         V: de::Visitor<'de>,
     {
-        self.0
-            .deserialize_struct(name, fields, StructVisitor { visitor, fields })
+        self.deserialize_tracked(|de| {
+            de.deserialize_struct(name, fields, StructVisitor { visitor, fields })
+        })
     }
 
     fn deserialize_enum<V>(
@@ -225,7 +416,7 @@ where
     where
         V: de::Visitor<'de>,
     {
-        self.0.deserialize_enum(name, variants, Visitor(visitor))
+        self.deserialize_tracked(|de| de.deserialize_enum(name, variants, Visitor(visitor)))
     }
 
     // we can't delegate this due to the signature, but luckily we know the answer
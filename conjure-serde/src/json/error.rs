@@ -0,0 +1,148 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use serde::de;
+use serde_json::error::Category;
+use std::error;
+use std::fmt;
+
+/// A resource limit enforced by a `ServerDeserializer`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Limit {
+    /// The maximum nesting depth of arrays and objects.
+    Depth,
+    /// The maximum length of a string, in bytes.
+    StringLength,
+    /// The maximum number of elements in an array or entries in an object.
+    Elements,
+    /// The maximum number of bytes of input.
+    Bytes,
+}
+
+/// An error deserializing JSON data.
+///
+/// In addition to the underlying serde_json error, it records the path to the value which triggered the error, if
+/// the error was associated with one.
+#[derive(Debug)]
+pub struct Error {
+    inner: serde_json::Error,
+    // the full and safe paths
+    path: Option<(String, String)>,
+    limit: Option<Limit>,
+}
+
+impl Error {
+    pub(crate) fn new(inner: serde_json::Error, path: Option<(String, String)>) -> Error {
+        Error {
+            inner,
+            path,
+            limit: None,
+        }
+    }
+
+    pub(crate) fn with_limit(mut self, limit: Option<Limit>) -> Error {
+        self.limit = limit;
+        self
+    }
+
+    /// Returns the path to the value which triggered the error, e.g. `$.datasets[3].fileSystemId`.
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_ref().map(|(path, _)| &**path)
+    }
+
+    /// Returns the path to the value which triggered the error with map keys redacted, e.g. `$.datasets[*].fileSystemId`.
+    ///
+    /// Unlike the full path, the only parts of the input it includes are the field and variant names declared by the
+    /// types being deserialized, so it is safe to log.
+    pub fn safe_path(&self) -> Option<&str> {
+        self.path.as_ref().map(|(_, path)| &**path)
+    }
+
+    /// Returns the resource limit which was exceeded, if the error was caused by one.
+    ///
+    /// Exceeding the `Bytes` limit indicates that the input as a whole was too large, while the other limits indicate
+    /// that the input was malformed.
+    pub fn limit(&self) -> Option<Limit> {
+        self.limit
+    }
+
+    /// Returns the underlying serde_json error.
+    pub fn into_inner(self) -> serde_json::Error {
+        self.inner
+    }
+
+    /// Returns the one-based line number at which the error was detected.
+    pub fn line(&self) -> usize {
+        self.inner.line()
+    }
+
+    /// Returns the one-based column number at which the error was detected.
+    pub fn column(&self) -> usize {
+        self.inner.column()
+    }
+
+    /// Categorizes the cause of the error.
+    pub fn classify(&self) -> Category {
+        self.inner.classify()
+    }
+
+    /// Returns true if the error was caused by a failure to read bytes from an IO stream.
+    pub fn is_io(&self) -> bool {
+        self.inner.is_io()
+    }
+
+    /// Returns true if the error was caused by input that was not syntactically valid JSON.
+    pub fn is_syntax(&self) -> bool {
+        self.inner.is_syntax()
+    }
+
+    /// Returns true if the error was caused by input data that was semantically incorrect.
+    pub fn is_data(&self) -> bool {
+        self.inner.is_data()
+    }
+
+    /// Returns true if the error was caused by prematurely reaching the end of the input data.
+    pub fn is_eof(&self) -> bool {
+        self.inner.is_eof()
+    }
+}
+
+impl From<Error> for serde_json::Error {
+    fn from(e: Error) -> serde_json::Error {
+        e.inner
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.path() {
+            Some(path) => write!(fmt, "{}: {}", path, self.inner),
+            None => fmt::Display::fmt(&self.inner, fmt),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.inner.source()
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T>(msg: T) -> Error
+    where
+        T: fmt::Display,
+    {
+        Error::new(de::Error::custom(msg), None)
+    }
+}
@@ -10,16 +10,27 @@
 //!
 //! This crate provides `Serializer` and `Deserializer` implementations which wrap serde_json's and handle these special
 //! behaviors.
+//!
+//! The serializer also supports a canonical mode, which writes semantically equal values as identical bytes for use
+//! when hashing or signing serialized data.
 
+pub use crate::json::canonical::CanonicalFormatter;
 pub use crate::json::de::client::{
     client_from_reader, client_from_slice, client_from_str, ClientDeserializer,
 };
 pub use crate::json::de::server::{
-    server_from_reader, server_from_slice, server_from_str, ServerDeserializer,
+    server_from_reader, server_from_slice, server_from_str, LimitedReader, ServerDeserializer,
+    ServerDeserializerBuilder,
+};
+pub use crate::json::error::{Error, Limit};
+pub use crate::json::ser::{
+    to_canonical_string, to_canonical_vec, to_canonical_writer, to_string, to_vec, to_writer,
+    Serializer,
 };
-pub use crate::json::ser::{to_string, to_vec, to_writer, Serializer};
 
-mod de;
+mod canonical;
+pub(crate) mod de;
+mod error;
 mod ser;
 #[cfg(test)]
 mod test;
@@ -20,6 +20,8 @@ use std::f64;
 use std::fmt;
 use std::io::Write;
 
+use crate::json::CanonicalFormatter;
+
 /// Serializes a value as JSON into a byte buffer.
 pub fn to_vec<T>(value: &T) -> Result<Vec<u8>, Error>
 where
@@ -49,6 +51,41 @@ where
     value.serialize(&mut Serializer::new(writer))
 }
 
+/// Serializes a value as canonical JSON into a byte buffer.
+///
+/// See `CanonicalFormatter` for details of the canonical form.
+pub fn to_canonical_vec<T>(value: &T) -> Result<Vec<u8>, Error>
+where
+    T: ?Sized + ser::Serialize,
+{
+    let mut buf = Vec::with_capacity(128);
+    value.serialize(&mut Serializer::canonical(&mut buf))?;
+    Ok(buf)
+}
+
+/// Serializes a value as canonical JSON into a string.
+///
+/// See `CanonicalFormatter` for details of the canonical form.
+pub fn to_canonical_string<T>(value: &T) -> Result<String, Error>
+where
+    T: ?Sized + ser::Serialize,
+{
+    let vec = to_canonical_vec(value)?;
+    // JSON is always valid UTF8
+    unsafe { Ok(String::from_utf8_unchecked(vec)) }
+}
+
+/// Serializes a value as canonical JSON into a writer.
+///
+/// See `CanonicalFormatter` for details of the canonical form.
+pub fn to_canonical_writer<W, T>(writer: W, value: &T) -> Result<(), Error>
+where
+    W: Write,
+    T: ser::Serialize,
+{
+    value.serialize(&mut Serializer::canonical(writer))
+}
+
 /// A serde JSON serializer compatible with the Conjure specification.
 ///
 /// In contrast to serde_json, the f32 and f64 types are serialized as the strings `"Infinity"`, `"-Infinity"`, and
@@ -75,6 +112,19 @@ where
     }
 }
 
+impl<W> Serializer<W, CanonicalFormatter>
+where
+    W: Write,
+{
+    /// Creates a new Conjure canonical JSON serializer.
+    ///
+    /// Semantically equal values are always serialized to identical bytes. See `CanonicalFormatter` for details of
+    /// the canonical form.
+    pub fn canonical(writer: W) -> Serializer<W, CanonicalFormatter> {
+        Serializer::with_formatter(writer, CanonicalFormatter::new())
+    }
+}
+
 impl<W, F> Serializer<W, F>
 where
     W: Write,
@@ -14,9 +14,12 @@
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_bytes::ByteBuf;
+use std::collections::{BTreeMap, HashMap};
 use std::f64;
 use std::fmt::Debug;
 
+use crate::json::{Limit, ServerDeserializerBuilder};
+
 fn serialize<T>(value: &T) -> String
 where
     T: Serialize,
@@ -146,3 +149,244 @@ fn server_unknown_fields() {
     assert!(e.to_string().contains("foo"));
     assert!(e.to_string().contains("bogus"));
 }
+
+#[derive(Deserialize, Debug)]
+struct Dataset {
+    #[serde(rename = "fileSystemId")]
+    _file_system_id: i32,
+}
+
+#[derive(Deserialize, Debug)]
+struct Datasets {
+    #[serde(rename = "datasets")]
+    _datasets: Vec<Dataset>,
+}
+
+#[derive(Deserialize, Debug)]
+struct DatasetsByName {
+    #[serde(rename = "byName")]
+    _by_name: BTreeMap<String, Dataset>,
+}
+
+#[derive(Deserialize, Debug)]
+#[allow(dead_code)]
+enum Variant {
+    #[serde(rename = "value")]
+    Value(i32),
+}
+
+#[test]
+fn error_paths() {
+    let json = r#"{"datasets": [{"fileSystemId": 1}, {"fileSystemId": "foo"}]}"#;
+    let e = crate::json::server_from_str::<Datasets>(json).unwrap_err();
+    assert_eq!(e.path(), Some("$.datasets[1].fileSystemId"));
+    assert_eq!(e.safe_path(), Some("$.datasets[1].fileSystemId"));
+    assert!(e.to_string().starts_with("$.datasets[1].fileSystemId: "));
+    let e = crate::json::client_from_str::<Datasets>(json).unwrap_err();
+    assert_eq!(e.path(), Some("$.datasets[1].fileSystemId"));
+
+    let e =
+        crate::json::server_from_str::<Datasets>(r#"{"datasets": [{"bogus": 1}]}"#).unwrap_err();
+    assert_eq!(e.path(), Some("$.datasets[0].bogus"));
+    assert_eq!(e.safe_path(), Some("$.datasets[0][*]"));
+
+    let e = crate::json::server_from_str::<Datasets>(r#"{"datasets": [{}]}"#).unwrap_err();
+    assert_eq!(e.path(), Some("$.datasets[0]"));
+
+    let e =
+        crate::json::client_from_str::<BTreeMap<i32, Vec<i32>>>(r#"{"1": [1, true]}"#).unwrap_err();
+    assert_eq!(e.path(), Some(r#"$["1"][1]"#));
+    assert_eq!(e.safe_path(), Some("$[*][1]"));
+
+    let json = r#"{"byName": {"secret": {"fileSystemId": "foo"}}}"#;
+    let e = crate::json::server_from_str::<DatasetsByName>(json).unwrap_err();
+    assert_eq!(e.path(), Some("$.byName.secret.fileSystemId"));
+    assert_eq!(e.safe_path(), Some("$.byName[*].fileSystemId"));
+
+    let e = crate::json::server_from_str::<Variant>(r#"{"value": true}"#).unwrap_err();
+    assert_eq!(e.path(), Some("$.value"));
+    assert_eq!(e.safe_path(), Some("$.value"));
+
+    let e = crate::json::client_from_str::<i32>("true").unwrap_err();
+    assert_eq!(e.path(), Some("$"));
+
+    let e = crate::json::client_from_str::<i32>("1 2").unwrap_err();
+    assert_eq!(e.path(), None);
+}
+
+#[test]
+fn error_paths_ignore_handled_errors() {
+    #[derive(Deserialize, Debug)]
+    #[serde(untagged)]
+    #[allow(dead_code)]
+    enum Value {
+        Int(i32),
+        Strings(Vec<String>),
+    }
+
+    let json = r#"[1, ["foo"], true]"#;
+    let e = crate::json::client_from_str::<Vec<Value>>(json).unwrap_err();
+    assert_eq!(e.path(), Some("$[2]"));
+}
+
+fn limited<T>(builder: &ServerDeserializerBuilder, json: &str) -> Result<T, crate::json::Error>
+where
+    T: DeserializeOwned,
+{
+    let mut de = builder.from_str(json);
+    let value = T::deserialize(&mut de)?;
+    de.end()?;
+    Ok(value)
+}
+
+#[test]
+fn depth_limit() {
+    let mut builder = ServerDeserializerBuilder::new();
+    builder.max_depth(2);
+
+    limited::<Vec<Vec<i32>>>(&builder, "[[1]]").unwrap();
+    let e = limited::<Vec<Vec<Vec<i32>>>>(&builder, "[[[1]]]").unwrap_err();
+    assert_eq!(e.limit(), Some(Limit::Depth));
+    assert_eq!(e.path(), Some("$[0][0]"));
+
+    let e = limited::<BTreeMap<String, Vec<Vec<i32>>>>(&builder, r#"{"a": [[]]}"#).unwrap_err();
+    assert_eq!(e.limit(), Some(Limit::Depth));
+
+    // the depth is restored when leaving a collection
+    limited::<Vec<Vec<i32>>>(&builder, "[[1], [2], []]").unwrap();
+}
+
+#[test]
+fn string_length_limit() {
+    let mut builder = ServerDeserializerBuilder::new();
+    builder.max_string_length(3);
+
+    limited::<Vec<String>>(&builder, r#"["foo"]"#).unwrap();
+    let e = limited::<Vec<String>>(&builder, r#"["foo", "fooo"]"#).unwrap_err();
+    assert_eq!(e.limit(), Some(Limit::StringLength));
+    assert_eq!(e.path(), Some("$[1]"));
+
+    let e = limited::<BTreeMap<String, i32>>(&builder, r#"{"fooo": 1}"#).unwrap_err();
+    assert_eq!(e.limit(), Some(Limit::StringLength));
+}
+
+#[test]
+fn elements_limit() {
+    let mut builder = ServerDeserializerBuilder::new();
+    builder.max_elements(2);
+
+    limited::<Vec<i32>>(&builder, "[1, 2]").unwrap();
+    let e = limited::<Vec<i32>>(&builder, "[1, 2, 3]").unwrap_err();
+    assert_eq!(e.limit(), Some(Limit::Elements));
+    assert_eq!(e.path(), Some("$"));
+
+    limited::<BTreeMap<String, i32>>(&builder, r#"{"a": 1, "b": 2}"#).unwrap();
+    let e = limited::<BTreeMap<String, i32>>(&builder, r#"{"a": 1, "b": 2, "c": 3}"#).unwrap_err();
+    assert_eq!(e.limit(), Some(Limit::Elements));
+}
+
+#[test]
+fn bytes_limit() {
+    let mut builder = ServerDeserializerBuilder::new();
+    builder.max_bytes(6);
+
+    limited::<String>(&builder, r#""fooo""#).unwrap();
+    let e = limited::<String>(&builder, r#""foooo""#).unwrap_err();
+    assert_eq!(e.limit(), Some(Limit::Bytes));
+
+    let mut de = builder.from_reader(&br#""fooo""#[..]);
+    String::deserialize(&mut de).unwrap();
+    de.end().unwrap();
+
+    let mut de = builder.from_reader(&br#""foooo""#[..]);
+    let e = String::deserialize(&mut de).unwrap_err();
+    assert_eq!(e.limit(), Some(Limit::Bytes));
+
+    // trailing data past the limit is caught by end
+    let mut de = builder.from_reader(&br#""fooo"  "#[..]);
+    String::deserialize(&mut de).unwrap();
+    let e = de.end().unwrap_err();
+    assert_eq!(e.limit(), Some(Limit::Bytes));
+}
+
+#[test]
+fn other_errors_have_no_limit() {
+    let mut builder = ServerDeserializerBuilder::new();
+    builder
+        .max_depth(1)
+        .max_string_length(1)
+        .max_elements(1)
+        .max_bytes(100);
+
+    let e = limited::<Vec<i32>>(&builder, r#"["a"]"#).unwrap_err();
+    assert_eq!(e.limit(), None);
+}
+
+#[test]
+fn canonical_key_order() {
+    #[derive(Serialize)]
+    struct Foo {
+        b: i32,
+        a: BTreeMap<i32, &'static str>,
+        c: HashMap<&'static str, i32>,
+    }
+
+    let foo = Foo {
+        b: 1,
+        a: vec![(9, "nine"), (10, "ten")].into_iter().collect(),
+        c: vec![("z", 1), ("\"", 2), ("é", 3), ("a", 4)]
+            .into_iter()
+            .collect(),
+    };
+    assert_eq!(
+        crate::json::to_canonical_string(&foo).unwrap(),
+        r#"{"a":{"10":"ten","9":"nine"},"b":1,"c":{"\"":2,"a":4,"z":1,"é":3}}"#,
+    );
+}
+
+#[test]
+fn canonical_nulls() {
+    #[derive(Serialize)]
+    struct Foo {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        a: Option<i32>,
+        b: Vec<Option<i32>>,
+        c: BTreeMap<&'static str, Option<i32>>,
+    }
+
+    let foo = Foo {
+        a: None,
+        b: vec![None],
+        c: vec![("y", Some(1)), ("x", None)].into_iter().collect(),
+    };
+    assert_eq!(
+        crate::json::to_canonical_string(&foo).unwrap(),
+        r#"{"b":[null],"c":{"x":null,"y":1}}"#
+    );
+}
+
+#[test]
+fn canonical_floats() {
+    let cases = &[
+        (1.0, "1"),
+        (-0.0, "0"),
+        (0.5, "0.5"),
+        (-123.456, "-123.456"),
+        (0.000001, "0.000001"),
+        (0.0000001, "1e-7"),
+        (1e20, "100000000000000000000"),
+        (1e21, "1e+21"),
+        (1.5e300, "1.5e+300"),
+        (f64::NAN, r#""NaN""#),
+    ];
+
+    for &(value, expected) in cases {
+        assert_eq!(crate::json::to_canonical_string(&value).unwrap(), expected);
+        if !value.is_nan() {
+            let roundtrip = crate::json::client_from_str::<f64>(expected).unwrap();
+            assert_eq!(roundtrip, value);
+        }
+    }
+
+    assert_eq!(crate::json::to_canonical_string(&0.1f32).unwrap(), "0.1");
+}
@@ -28,4 +28,9 @@
 #![warn(clippy::all, missing_docs)]
 #![doc(html_root_url = "https://docs.rs/conjure-serde/0.6")]
 
+#[macro_use]
+mod macros;
+
+pub mod cbor;
 pub mod json;
+pub mod smile;
@@ -0,0 +1,436 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Forwards `de::Deserializer` methods to another deserializer, for the client and server wrappers of the binary
+// formats.
+macro_rules! forward_deserialize {
+    ($this:ident => $target:expr; $($method:ident($($arg:ident: $ty:ty),*),)*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Error>
+            where
+                V: serde::de::Visitor<'de>,
+            {
+                let $this = self;
+                serde::de::Deserializer::$method($target, $($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+// the methods of `de::Deserializer` which take a visitor
+macro_rules! forward_deserialize_all {
+    ($this:ident => $target:expr) => {
+        forward_deserialize!(
+            $this => $target;
+            deserialize_ignored_any(),
+        );
+        forward_deserialize_values!($this => $target);
+    };
+}
+
+macro_rules! forward_deserialize_values {
+    ($this:ident => $target:expr) => {
+        forward_deserialize!(
+            $this => $target;
+            deserialize_any(),
+            deserialize_bool(),
+            deserialize_i8(),
+            deserialize_i16(),
+            deserialize_i32(),
+            deserialize_i64(),
+            deserialize_i128(),
+            deserialize_u8(),
+            deserialize_u16(),
+            deserialize_u32(),
+            deserialize_u64(),
+            deserialize_u128(),
+            deserialize_f32(),
+            deserialize_f64(),
+            deserialize_char(),
+            deserialize_str(),
+            deserialize_string(),
+            deserialize_bytes(),
+            deserialize_byte_buf(),
+            deserialize_option(),
+            deserialize_unit(),
+            deserialize_unit_struct(name: &'static str),
+            deserialize_newtype_struct(name: &'static str),
+            deserialize_seq(),
+            deserialize_tuple(len: usize),
+            deserialize_tuple_struct(name: &'static str, len: usize),
+            deserialize_map(),
+            deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+            deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+            deserialize_identifier(),
+        );
+    };
+}
+
+// Defines the error type of a binary format.
+macro_rules! binary_error {
+    ($(#[$meta:meta])* pub struct Error;) => {
+        $(#[$meta])*
+        #[derive(Debug)]
+        pub struct Error(Box<ErrorKind>);
+
+        #[derive(Debug)]
+        enum ErrorKind {
+            Io(std::io::Error),
+            Message(String),
+        }
+
+        impl Error {
+            pub(crate) fn io(error: std::io::Error) -> Error {
+                Error(Box::new(ErrorKind::Io(error)))
+            }
+
+            pub(crate) fn message<T>(message: T) -> Error
+            where
+                T: std::fmt::Display,
+            {
+                Error(Box::new(ErrorKind::Message(message.to_string())))
+            }
+
+            pub(crate) fn eof() -> Error {
+                Error::message("unexpected end of input")
+            }
+
+            /// Returns true if the error was caused by a failure to read or write bytes on an IO stream.
+            pub fn is_io(&self) -> bool {
+                match *self.0 {
+                    ErrorKind::Io(_) => true,
+                    ErrorKind::Message(_) => false,
+                }
+            }
+        }
+
+        impl std::fmt::Display for Error {
+            fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match &*self.0 {
+                    ErrorKind::Io(e) => std::fmt::Display::fmt(e, fmt),
+                    ErrorKind::Message(e) => fmt.write_str(e),
+                }
+            }
+        }
+
+        impl std::error::Error for Error {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                match &*self.0 {
+                    ErrorKind::Io(e) => Some(e),
+                    ErrorKind::Message(_) => None,
+                }
+            }
+        }
+
+        impl serde::de::Error for Error {
+            fn custom<T>(msg: T) -> Error
+            where
+                T: std::fmt::Display,
+            {
+                Error::message(msg)
+            }
+        }
+
+        impl serde::ser::Error for Error {
+            fn custom<T>(msg: T) -> Error
+            where
+                T: std::fmt::Display,
+            {
+                Error::message(msg)
+            }
+        }
+    };
+}
+
+// Defines a client or server wrapper around the `Deserializer` of a binary format, along with its convenience
+// functions. The `Deserializer`, `IoRead`, `SliceRead`, `Read`, and `Error` types of the format must be in scope.
+macro_rules! binary_deserializer {
+    (
+        $(#[$from_reader_meta:meta])*
+        pub fn $from_reader:ident;
+        $(#[$from_slice_meta:meta])*
+        pub fn $from_slice:ident;
+        $(#[$meta:meta])*
+        pub struct $name:ident {
+            deny_unknown_fields: $deny_unknown_fields:expr,
+            $(#[$new_reader_meta:meta])*
+            fn from_reader;
+            $(#[$new_slice_meta:meta])*
+            fn from_slice;
+            $(#[$end_meta:meta])*
+            fn end;
+        }
+    ) => {
+        $(#[$from_reader_meta])*
+        pub fn $from_reader<R, T>(reader: R) -> Result<T, Error>
+        where
+            R: std::io::Read,
+            T: serde::de::DeserializeOwned,
+        {
+            let mut de = $name::from_reader(reader);
+            let value = T::deserialize(&mut de)?;
+            de.end()?;
+            Ok(value)
+        }
+
+        $(#[$from_slice_meta])*
+        pub fn $from_slice<'a, T>(s: &'a [u8]) -> Result<T, Error>
+        where
+            T: serde::de::Deserialize<'a>,
+        {
+            let mut de = $name::from_slice(s);
+            let value = T::deserialize(&mut de)?;
+            de.end()?;
+            Ok(value)
+        }
+
+        $(#[$meta])*
+        pub struct $name<R>(Deserializer<R>);
+
+        impl<R> $name<IoRead<R>>
+        where
+            R: std::io::Read,
+        {
+            $(#[$new_reader_meta])*
+            pub fn from_reader(reader: R) -> $name<IoRead<R>> {
+                $name(Deserializer::new(IoRead::new(reader), $deny_unknown_fields))
+            }
+        }
+
+        impl<'a> $name<SliceRead<'a>> {
+            $(#[$new_slice_meta])*
+            pub fn from_slice(bytes: &'a [u8]) -> $name<SliceRead<'a>> {
+                $name(Deserializer::new(SliceRead::new(bytes), $deny_unknown_fields))
+            }
+        }
+
+        impl<'de, R> $name<R>
+        where
+            R: Read<'de>,
+        {
+            $(#[$end_meta])*
+            pub fn end(&mut self) -> Result<(), Error> {
+                self.0.end()
+            }
+        }
+
+        impl<'de, R> serde::de::Deserializer<'de> for &mut $name<R>
+        where
+            R: Read<'de>,
+        {
+            type Error = Error;
+
+            forward_deserialize_all!(this => &mut this.0);
+        }
+    };
+}
+
+// Defines a `KeyDeserializer` for binary formats which always encode map keys as strings, so other primitive keys
+// are parsed from them like they are in JSON. The format's `Error` type must be in scope.
+macro_rules! key_deserializer {
+    () => {
+        struct KeyDeserializer<'a, 'de> {
+            key: &'a std::borrow::Cow<'de, str>,
+        }
+
+        impl<'a, 'de> serde::de::Deserializer<'de> for KeyDeserializer<'a, 'de> {
+            type Error = Error;
+
+            fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: serde::de::Visitor<'de>,
+            {
+                match self.key {
+                    std::borrow::Cow::Borrowed(key) => visitor.visit_borrowed_str(key),
+                    std::borrow::Cow::Owned(key) => visitor.visit_str(key),
+                }
+            }
+
+            deserialize_parsed_key!(
+                deserialize_bool => visit_bool,
+                deserialize_i8 => visit_i8,
+                deserialize_i16 => visit_i16,
+                deserialize_i32 => visit_i32,
+                deserialize_i64 => visit_i64,
+                deserialize_i128 => visit_i128,
+                deserialize_u8 => visit_u8,
+                deserialize_u16 => visit_u16,
+                deserialize_u32 => visit_u32,
+                deserialize_u64 => visit_u64,
+                deserialize_u128 => visit_u128,
+                deserialize_f32 => visit_f32,
+                deserialize_f64 => visit_f64,
+            );
+
+            fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: serde::de::Visitor<'de>,
+            {
+                visitor.visit_some(self)
+            }
+
+            fn deserialize_newtype_struct<V>(
+                self,
+                _: &'static str,
+                visitor: V,
+            ) -> Result<V::Value, Error>
+            where
+                V: serde::de::Visitor<'de>,
+            {
+                visitor.visit_newtype_struct(self)
+            }
+
+            fn deserialize_enum<V>(
+                self,
+                _: &'static str,
+                _: &'static [&'static str],
+                visitor: V,
+            ) -> Result<V::Value, Error>
+            where
+                V: serde::de::Visitor<'de>,
+            {
+                visitor.visit_enum(serde::de::IntoDeserializer::into_deserializer(&**self.key))
+            }
+
+            serde::forward_to_deserialize_any! {
+                char str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+                ignored_any
+            }
+        }
+    };
+}
+
+macro_rules! deserialize_parsed_key {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Error>
+            where
+                V: serde::de::Visitor<'de>,
+            {
+                match self.key.parse() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(_) => Err(serde::de::Error::invalid_value(
+                        serde::de::Unexpected::Str(self.key),
+                        &visitor,
+                    )),
+                }
+            }
+        )*
+    };
+}
+
+// Defines a `MapKeySerializer` for binary formats which always encode map keys as strings, so other primitive keys
+// are stringified like they are in JSON. Keys are written with the `$write` method of the format's `Serializer`, and
+// the format's `Error` type must be in scope.
+macro_rules! map_key_serializer {
+    ($write:ident) => {
+        struct MapKeySerializer<'a, W> {
+            ser: &'a mut Serializer<W>,
+        }
+
+        impl<'a, W> serde::ser::Serializer for MapKeySerializer<'a, W>
+        where
+            W: std::io::Write,
+        {
+            type Ok = ();
+            type Error = Error;
+
+            type SerializeSeq = serde::ser::Impossible<(), Error>;
+            type SerializeTuple = serde::ser::Impossible<(), Error>;
+            type SerializeTupleStruct = serde::ser::Impossible<(), Error>;
+            type SerializeTupleVariant = serde::ser::Impossible<(), Error>;
+            type SerializeMap = serde::ser::Impossible<(), Error>;
+            type SerializeStruct = serde::ser::Impossible<(), Error>;
+            type SerializeStructVariant = serde::ser::Impossible<(), Error>;
+
+            serialize_display_key!(
+                $write;
+                serialize_bool = bool,
+                serialize_i8 = i8,
+                serialize_i16 = i16,
+                serialize_i32 = i32,
+                serialize_i64 = i64,
+                serialize_i128 = i128,
+                serialize_u8 = u8,
+                serialize_u16 = u16,
+                serialize_u32 = u32,
+                serialize_u64 = u64,
+                serialize_u128 = u128,
+                serialize_char = char,
+            );
+
+            fn serialize_str(self, v: &str) -> Result<(), Error> {
+                self.ser.$write(v)
+            }
+
+            fn serialize_unit_variant(
+                self,
+                _: &'static str,
+                _: u32,
+                variant: &'static str,
+            ) -> Result<(), Error> {
+                self.ser.$write(variant)
+            }
+
+            fn serialize_newtype_struct<T>(self, _: &'static str, value: &T) -> Result<(), Error>
+            where
+                T: ?Sized + serde::ser::Serialize,
+            {
+                value.serialize(self)
+            }
+
+            reject_key!(
+                serialize_f32(f32) -> (),
+                serialize_f64(f64) -> (),
+                serialize_bytes(&[u8]) -> (),
+                serialize_none() -> (),
+                serialize_some<T>(&T) -> (),
+                serialize_unit() -> (),
+                serialize_unit_struct(&'static str) -> (),
+                serialize_newtype_variant<T>(&'static str, u32, &'static str, &T) -> (),
+                serialize_seq(Option<usize>) -> serde::ser::Impossible<(), Error>,
+                serialize_tuple(usize) -> serde::ser::Impossible<(), Error>,
+                serialize_tuple_struct(&'static str, usize) -> serde::ser::Impossible<(), Error>,
+                serialize_tuple_variant(&'static str, u32, &'static str, usize)
+                    -> serde::ser::Impossible<(), Error>,
+                serialize_map(Option<usize>) -> serde::ser::Impossible<(), Error>,
+                serialize_struct(&'static str, usize) -> serde::ser::Impossible<(), Error>,
+                serialize_struct_variant(&'static str, u32, &'static str, usize)
+                    -> serde::ser::Impossible<(), Error>,
+            );
+        }
+    };
+}
+
+macro_rules! serialize_display_key {
+    ($write:ident; $($method:ident = $ty:ty,)*) => {
+        $(
+            fn $method(self, v: $ty) -> Result<(), Error> {
+                self.ser.$write(&v.to_string())
+            }
+        )*
+    };
+}
+
+macro_rules! reject_key {
+    ($($method:ident$(<$t:ident>)*($($arg:ty),*) -> $ret:ty,)*) => {
+        $(
+            fn $method$(<$t>)*(self, $(_: $arg),*) -> Result<$ret, Error>
+            $(where $t: ?Sized + serde::ser::Serialize)*
+            {
+                Err(Error::message("key must be a string"))
+            }
+        )*
+    };
+}
@@ -0,0 +1,720 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use serde::de::{self, IntoDeserializer};
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::fmt;
+use std::str;
+
+use crate::json::de::{F32Visitor, F64Visitor};
+use crate::smile::de::read::{Read, Reference};
+use crate::smile::error::Error;
+use crate::smile::{
+    HEADER, HEADER_SHARED_NAMES, HEADER_SHARED_VALUES, HEADER_VERSION_MASK, KEY_EMPTY_STRING,
+    KEY_LONG_SHARED, KEY_LONG_UNICODE, KEY_SHORT_ASCII, KEY_SHORT_SHARED, KEY_SHORT_UNICODE,
+    MAX_SHARED_STRINGS, TOKEN_BIG_DECIMAL, TOKEN_BIG_INTEGER, TOKEN_BINARY_7BIT, TOKEN_BINARY_RAW,
+    TOKEN_DOUBLE, TOKEN_EMPTY_STRING, TOKEN_END_ARRAY, TOKEN_END_CONTENT, TOKEN_END_OBJECT,
+    TOKEN_END_STRING, TOKEN_FALSE, TOKEN_FLOAT, TOKEN_INT, TOKEN_LONG, TOKEN_LONG_ASCII,
+    TOKEN_LONG_SHARED_VALUE, TOKEN_LONG_UNICODE, TOKEN_NULL, TOKEN_SHORT_ASCII,
+    TOKEN_SHORT_UNICODE, TOKEN_SMALL_INT, TOKEN_START_ARRAY, TOKEN_START_OBJECT, TOKEN_TINY_ASCII,
+    TOKEN_TINY_UNICODE, TOKEN_TRUE,
+};
+
+const RECURSION_LIMIT: u8 = 128;
+
+pub mod client;
+pub mod read;
+pub mod server;
+
+/// The SMILE parser shared by the client and server deserializers.
+pub(crate) struct Deserializer<R> {
+    read: R,
+    scratch: Vec<u8>,
+    header_read: bool,
+    shared_keys: Option<Vec<String>>,
+    shared_values: Option<Vec<String>>,
+    remaining_depth: u8,
+    deny_unknown_fields: bool,
+}
+
+impl<'de, R> Deserializer<R>
+where
+    R: Read<'de>,
+{
+    pub(crate) fn new(read: R, deny_unknown_fields: bool) -> Deserializer<R> {
+        Deserializer {
+            read,
+            scratch: vec![],
+            header_read: false,
+            shared_keys: None,
+            shared_values: None,
+            remaining_depth: RECURSION_LIMIT,
+            deny_unknown_fields,
+        }
+    }
+
+    pub(crate) fn end(&mut self) -> Result<(), Error> {
+        match self.read.next()? {
+            None => Ok(()),
+            Some(TOKEN_END_CONTENT) if self.read.peek()?.is_none() => Ok(()),
+            Some(_) => Err(Error::message("trailing data after value")),
+        }
+    }
+
+    fn read_header(&mut self) -> Result<(), Error> {
+        if self.header_read {
+            return Ok(());
+        }
+
+        let (valid, flags) = {
+            let header = self.read.read(HEADER.len() + 1, &mut self.scratch)?;
+            (header[..HEADER.len()] == HEADER, header[HEADER.len()])
+        };
+        if !valid {
+            return Err(Error::message("missing SMILE header"));
+        }
+        if flags & HEADER_VERSION_MASK != 0 {
+            return Err(Error::message("unsupported SMILE version"));
+        }
+
+        if flags & HEADER_SHARED_NAMES != 0 {
+            self.shared_keys = Some(vec![]);
+        }
+        if flags & HEADER_SHARED_VALUES != 0 {
+            self.shared_values = Some(vec![]);
+        }
+        self.header_read = true;
+
+        Ok(())
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, Error> {
+        self.read_header()?;
+        self.read.peek()
+    }
+
+    fn next(&mut self) -> Result<u8, Error> {
+        self.read_header()?;
+        self.read.next()?.ok_or_else(Error::eof)
+    }
+
+    fn enter(&mut self) -> Result<(), Error> {
+        self.remaining_depth -= 1;
+        if self.remaining_depth == 0 {
+            self.remaining_depth += 1;
+            return Err(Error::message("recursion limit exceeded"));
+        }
+
+        Ok(())
+    }
+
+    fn exit(&mut self) {
+        self.remaining_depth += 1;
+    }
+
+    fn parse_vint(&mut self) -> Result<u64, Error> {
+        // the last byte holds 6 bits and has its high bit set, and the others hold 7 bits each
+        let mut value = 0u64;
+        for _ in 0..10 {
+            let b = self.next()?;
+            if b & 0x80 != 0 {
+                if value >> 58 != 0 {
+                    break;
+                }
+                return Ok((value << 6) | u64::from(b & 0x3f));
+            }
+
+            if value >> 57 != 0 {
+                break;
+            }
+            value = (value << 7) | u64::from(b);
+        }
+
+        Err(Error::message("variable length integer overflow"))
+    }
+
+    fn parse_len(&mut self) -> Result<usize, Error> {
+        let len = self.parse_vint()?;
+        usize::try_from(len).map_err(|_| Error::message("length overflow"))
+    }
+
+    fn parse_7bit(&mut self, len: usize) -> Result<Vec<u8>, Error> {
+        // each chunk of up to 7 bytes is spread across one more byte than it contains, 7 bits at a time, with the
+        // final byte holding the remaining low bits
+        let remainder = match len % 7 {
+            0 => 0,
+            n => n + 1,
+        };
+        let encoded_len = (len / 7)
+            .checked_mul(8)
+            .and_then(|l| l.checked_add(remainder))
+            .ok_or_else(|| Error::message("length overflow"))?;
+        let encoded = self.read.read(encoded_len, &mut self.scratch)?;
+
+        let mut bytes = Vec::with_capacity(len);
+        for chunk in encoded.chunks(8) {
+            let n = chunk.len() - 1;
+            let value = chunk[..n]
+                .iter()
+                .fold(0u64, |v, &b| (v << 7) | u64::from(b & 0x7f));
+            let value = (value << n) | u64::from(chunk[n] & ((1 << n) - 1));
+            bytes.extend((0..n).rev().map(|i| (value >> (8 * i)) as u8));
+        }
+
+        Ok(bytes)
+    }
+
+    fn parse_big_integer<V>(&mut self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let len = self.parse_len()?;
+        let bytes = self.parse_7bit(len)?;
+        if bytes.is_empty() || bytes.len() > 16 {
+            return Err(Error::message("big integer out of range"));
+        }
+
+        // sign extend the big-endian two's complement value
+        let init = if bytes[0] & 0x80 == 0 { 0 } else { -1 };
+        let value = bytes
+            .iter()
+            .fold(init, |v: i128, &b| (v << 8) | i128::from(b));
+
+        if let Ok(value) = i64::try_from(value) {
+            visitor.visit_i64(value)
+        } else if let Ok(value) = u64::try_from(value) {
+            visitor.visit_u64(value)
+        } else {
+            visitor.visit_i128(value)
+        }
+    }
+
+    // returns `None` without consuming anything if the token isn't a string
+    fn parse_str_value<'s>(
+        &'s mut self,
+        token: u8,
+    ) -> Result<Option<Reference<'de, 's, str>>, Error> {
+        let len = match token {
+            TOKEN_EMPTY_STRING => return Ok(Some(Reference::Borrowed(""))),
+            0x01..=0x1f => return self.shared_value(usize::from(token - 1)).map(Some),
+            TOKEN_LONG_SHARED_VALUE..=0xef => {
+                let index = usize::from(token & 0x03) << 8 | usize::from(self.next()?);
+                return self.shared_value(index).map(Some);
+            }
+            TOKEN_LONG_ASCII | TOKEN_LONG_UNICODE => {
+                let bytes = self.read.read_until(TOKEN_END_STRING, &mut self.scratch)?;
+                return to_str(bytes).map(Some);
+            }
+            TOKEN_TINY_ASCII..=0x5f => usize::from(token - TOKEN_TINY_ASCII) + 1,
+            TOKEN_SHORT_ASCII..=0x7f => usize::from(token - TOKEN_SHORT_ASCII) + 33,
+            TOKEN_TINY_UNICODE..=0x9f => usize::from(token - TOKEN_TINY_UNICODE) + 2,
+            TOKEN_SHORT_UNICODE..=0xbf => usize::from(token - TOKEN_SHORT_UNICODE) + 34,
+            _ => return Ok(None),
+        };
+
+        let value = to_str(self.read.read(len, &mut self.scratch)?)?;
+        if let Some(shared_values) = &mut self.shared_values {
+            add_shared(shared_values, &value);
+        }
+
+        Ok(Some(value))
+    }
+
+    fn shared_value(&self, index: usize) -> Result<Reference<'de, '_, str>, Error> {
+        let shared_values = self
+            .shared_values
+            .as_ref()
+            .ok_or_else(|| Error::message("shared string values are not enabled"))?;
+        let value = shared_values
+            .get(index)
+            .ok_or_else(|| Error::message("invalid shared string value reference"))?;
+
+        Ok(Reference::Copied(value))
+    }
+
+    fn parse_key(&mut self) -> Result<Cow<'de, str>, Error> {
+        let token = self.next()?;
+        let len = match token {
+            KEY_EMPTY_STRING => return Ok(Cow::Borrowed("")),
+            KEY_LONG_SHARED..=0x33 => {
+                let index = usize::from(token & 0x03) << 8 | usize::from(self.next()?);
+                return self.shared_key(index);
+            }
+            KEY_LONG_UNICODE => None,
+            KEY_SHORT_SHARED..=0x7f => return self.shared_key(usize::from(token & 0x3f)),
+            KEY_SHORT_ASCII..=0xbf => Some(usize::from(token & 0x3f) + 1),
+            KEY_SHORT_UNICODE..=0xf7 => Some(usize::from(token & 0x3f) + 2),
+            _ => return Err(invalid_token(token)),
+        };
+
+        let bytes = match len {
+            Some(len) => self.read.read(len, &mut self.scratch)?,
+            None => self.read.read_until(TOKEN_END_STRING, &mut self.scratch)?,
+        };
+        let key = match to_str(bytes)? {
+            Reference::Borrowed(key) => Cow::Borrowed(key),
+            Reference::Copied(key) => Cow::Owned(key.to_string()),
+        };
+        if let Some(shared_keys) = &mut self.shared_keys {
+            add_shared(shared_keys, &key);
+        }
+
+        Ok(key)
+    }
+
+    fn shared_key(&self, index: usize) -> Result<Cow<'de, str>, Error> {
+        let shared_keys = self
+            .shared_keys
+            .as_ref()
+            .ok_or_else(|| Error::message("shared keys are not enabled"))?;
+        let key = shared_keys
+            .get(index)
+            .ok_or_else(|| Error::message("invalid shared key reference"))?;
+
+        Ok(Cow::Owned(key.clone()))
+    }
+
+    fn visit_token<V>(&mut self, token: u8, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        if let Some(value) = self.parse_str_value(token)? {
+            return match value {
+                Reference::Borrowed(value) => visitor.visit_borrowed_str(value),
+                Reference::Copied(value) => visitor.visit_str(value),
+            };
+        }
+
+        match token {
+            TOKEN_NULL => visitor.visit_unit(),
+            TOKEN_FALSE => visitor.visit_bool(false),
+            TOKEN_TRUE => visitor.visit_bool(true),
+            TOKEN_INT => {
+                let value = u32::try_from(self.parse_vint()?)
+                    .map_err(|_| Error::message("int value out of range"))?;
+                visitor.visit_i32((value >> 1) as i32 ^ -((value & 1) as i32))
+            }
+            TOKEN_LONG => {
+                let value = self.parse_vint()?;
+                visitor.visit_i64((value >> 1) as i64 ^ -((value & 1) as i64))
+            }
+            TOKEN_BIG_INTEGER => self.parse_big_integer(visitor),
+            TOKEN_FLOAT => {
+                let bytes = self.read.read(5, &mut self.scratch)?;
+                let bits = bytes
+                    .iter()
+                    .fold(0u32, |v, &b| (v << 7) | u32::from(b & 0x7f));
+                visitor.visit_f32(f32::from_bits(bits))
+            }
+            TOKEN_DOUBLE => {
+                let bytes = self.read.read(10, &mut self.scratch)?;
+                let bits = bytes
+                    .iter()
+                    .fold(0u64, |v, &b| (v << 7) | u64::from(b & 0x7f));
+                visitor.visit_f64(f64::from_bits(bits))
+            }
+            TOKEN_BIG_DECIMAL => Err(Error::message("big decimal values are not supported")),
+            TOKEN_SMALL_INT..=0xdf => {
+                let value = token & 0x1f;
+                visitor.visit_i32(i32::from(value >> 1) ^ -i32::from(value & 1))
+            }
+            TOKEN_BINARY_7BIT => {
+                let len = self.parse_len()?;
+                let bytes = self.parse_7bit(len)?;
+                visitor.visit_byte_buf(bytes)
+            }
+            TOKEN_BINARY_RAW => {
+                let len = self.parse_len()?;
+                match self.read.read(len, &mut self.scratch)? {
+                    Reference::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
+                    Reference::Copied(bytes) => visitor.visit_bytes(bytes),
+                }
+            }
+            TOKEN_START_ARRAY => {
+                self.enter()?;
+                let value = visitor.visit_seq(SeqAccess { de: self });
+                self.exit();
+                let value = value?;
+
+                match self.next()? {
+                    TOKEN_END_ARRAY => Ok(value),
+                    _ => Err(Error::message("trailing elements in array")),
+                }
+            }
+            TOKEN_START_OBJECT => self.visit_map(None, visitor),
+            _ => Err(invalid_token(token)),
+        }
+    }
+
+    // the start of the object has already been consumed
+    fn visit_map<V>(
+        &mut self,
+        fields: Option<&'static [&'static str]>,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.enter()?;
+        let value = visitor.visit_map(MapAccess {
+            de: self,
+            fields,
+            key: None,
+        });
+        self.exit();
+        let value = value?;
+
+        match self.next()? {
+            TOKEN_END_OBJECT => Ok(value),
+            _ => Err(Error::message("trailing entries in object")),
+        }
+    }
+}
+
+impl<'de, R> de::Deserializer<'de> for &mut Deserializer<R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let token = self.next()?;
+        self.visit_token(token, visitor)
+    }
+
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_any(F32Visitor(visitor))
+    }
+
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_any(F64Visitor(visitor))
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_any(BytesVisitor(visitor))
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_any(BytesVisitor(visitor))
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        if self.peek()? == Some(TOKEN_NULL) {
+            self.next()?;
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(self, _: &'static str, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        if self.deny_unknown_fields && self.peek()? == Some(TOKEN_START_OBJECT) {
+            self.next()?;
+            self.visit_map(Some(fields), visitor)
+        } else {
+            self.deserialize_any(visitor)
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let token = self.next()?;
+        if token == TOKEN_START_OBJECT {
+            self.enter()?;
+            let value = visitor.visit_enum(VariantAccess { de: self });
+            self.exit();
+            let value = value?;
+
+            return match self.next()? {
+                TOKEN_END_OBJECT => Ok(value),
+                _ => Err(Error::message("expected end of enum object")),
+            };
+        }
+
+        if let Some(variant) = self.parse_str_value(token)? {
+            return visitor.visit_enum((&*variant).into_deserializer());
+        }
+
+        self.visit_token(token, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 char str string unit unit_struct seq tuple
+        tuple_struct map identifier ignored_any
+    }
+}
+
+struct SeqAccess<'a, R> {
+    de: &'a mut Deserializer<R>,
+}
+
+impl<'a, 'de, R> de::SeqAccess<'de> for SeqAccess<'a, R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.de.peek()? {
+            Some(TOKEN_END_ARRAY) => Ok(None),
+            Some(_) => seed.deserialize(&mut *self.de).map(Some),
+            None => Err(Error::eof()),
+        }
+    }
+}
+
+struct MapAccess<'a, 'de, R> {
+    de: &'a mut Deserializer<R>,
+    // only set when unknown fields should be rejected
+    fields: Option<&'static [&'static str]>,
+    key: Option<Cow<'de, str>>,
+}
+
+impl<'a, 'de, R> de::MapAccess<'de> for MapAccess<'a, 'de, R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match self.de.peek()? {
+            Some(TOKEN_END_OBJECT) => return Ok(None),
+            Some(_) => {}
+            None => return Err(Error::eof()),
+        }
+
+        let key = self.de.parse_key()?;
+        let value = seed.deserialize(KeyDeserializer { key: &key })?;
+        if self.fields.is_some() {
+            self.key = Some(key);
+        }
+
+        Ok(Some(value))
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        match self.fields {
+            Some(fields) => seed.deserialize(FieldDeserializer {
+                de: &mut *self.de,
+                fields,
+                key: self.key.take(),
+            }),
+            None => seed.deserialize(&mut *self.de),
+        }
+    }
+}
+
+struct VariantAccess<'a, R> {
+    de: &'a mut Deserializer<R>,
+}
+
+impl<'a, 'de, R> de::EnumAccess<'de> for VariantAccess<'a, R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let key = self.de.parse_key()?;
+        let value = seed.deserialize(KeyDeserializer { key: &key })?;
+        Ok((value, self))
+    }
+}
+
+impl<'a, 'de, R> de::VariantAccess<'de> for VariantAccess<'a, R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        de::Deserialize::deserialize(self.de)
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(self.de)
+    }
+
+    fn tuple_variant<V>(self, _: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        de::Deserializer::deserialize_seq(self.de, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        de::Deserializer::deserialize_struct(self.de, "", fields, visitor)
+    }
+}
+
+// SMILE object keys are always strings
+key_deserializer!();
+
+// the value of a struct field, which is only ignored by the struct if the field is unknown
+struct FieldDeserializer<'a, 'de, R> {
+    de: &'a mut Deserializer<R>,
+    fields: &'static [&'static str],
+    key: Option<Cow<'de, str>>,
+}
+
+impl<'a, 'de, R> de::Deserializer<'de> for FieldDeserializer<'a, 'de, R>
+where
+    R: Read<'de>,
+{
+    type Error = Error;
+
+    forward_deserialize_values!(this => this.de);
+
+    fn deserialize_ignored_any<V>(self, _: V) -> Result<V::Value, Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let key = match &self.key {
+            Some(key) => &**key,
+            None => "<unknown>",
+        };
+
+        Err(de::Error::unknown_field(key, self.fields))
+    }
+}
+
+struct BytesVisitor<T>(T);
+
+impl<'de, T> de::Visitor<'de> for BytesVisitor<T>
+where
+    T: de::Visitor<'de>,
+{
+    type Value = T::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("binary data or a base64 string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<T::Value, E>
+    where
+        E: de::Error,
+    {
+        match base64::decode(v) {
+            Ok(v) => self.0.visit_byte_buf(v),
+            Err(_) => Err(E::invalid_value(de::Unexpected::Str(v), &self)),
+        }
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<T::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_bytes(v)
+    }
+
+    fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<T::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_borrowed_bytes(v)
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<T::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_byte_buf(v)
+    }
+}
+
+fn to_str<'de, 's>(bytes: Reference<'de, 's, [u8]>) -> Result<Reference<'de, 's, str>, Error> {
+    let invalid = |_| Error::message("invalid UTF-8 in string");
+    match bytes {
+        Reference::Borrowed(bytes) => str::from_utf8(bytes)
+            .map(Reference::Borrowed)
+            .map_err(invalid),
+        Reference::Copied(bytes) => str::from_utf8(bytes)
+            .map(Reference::Copied)
+            .map_err(invalid),
+    }
+}
+
+fn add_shared(table: &mut Vec<String>, value: &str) {
+    if table.len() == MAX_SHARED_STRINGS {
+        table.clear();
+    }
+    table.push(value.to_string());
+}
+
+fn invalid_token(token: u8) -> Error {
+    Error::message(format_args!("invalid token 0x{:02x}", token))
+}
@@ -0,0 +1,85 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! SMILE serialization support.
+//!
+//! [SMILE](https://github.com/FasterXML/smile-format-specification) is a binary encoding of the JSON data model used
+//! by Jackson-based Conjure implementations under the `application/x-jackson-smile` content type. It preserves the
+//! Conjure semantics of the `json` module, though some of them fall out of the format more naturally:
+//!
+//! * Floating point values are encoded natively, so non-finite values round trip without being converted to strings.
+//!   The strings `"Infinity"`, `"-Infinity"`, and `"NaN"` are still accepted when deserializing.
+//! * Byte sequences are encoded as native binary values rather than Base64-encoded strings. Base64-encoded strings are
+//!   still accepted when deserializing.
+//!
+//! As with JSON, Conjure clients should ignore unknown fields while Conjure servers should trigger errors.
+//!
+//! The serializer shares repeated property names through back references, but not string values. The deserializers
+//! support all of the format's back reference features.
+
+pub use crate::smile::de::client::{client_from_reader, client_from_slice, ClientDeserializer};
+pub use crate::smile::de::read::{IoRead, Read, SliceRead};
+pub use crate::smile::de::server::{server_from_reader, server_from_slice, ServerDeserializer};
+pub use crate::smile::error::Error;
+pub use crate::smile::ser::{to_vec, to_writer, Serializer};
+
+mod de;
+mod error;
+mod ser;
+#[cfg(test)]
+mod test;
+
+const HEADER: [u8; 3] = [b':', b')', b'\n'];
+const HEADER_VERSION_MASK: u8 = 0xf0;
+const HEADER_SHARED_NAMES: u8 = 0x01;
+const HEADER_SHARED_VALUES: u8 = 0x02;
+
+const TOKEN_EMPTY_STRING: u8 = 0x20;
+const TOKEN_NULL: u8 = 0x21;
+const TOKEN_FALSE: u8 = 0x22;
+const TOKEN_TRUE: u8 = 0x23;
+const TOKEN_INT: u8 = 0x24;
+const TOKEN_LONG: u8 = 0x25;
+const TOKEN_BIG_INTEGER: u8 = 0x26;
+const TOKEN_FLOAT: u8 = 0x28;
+const TOKEN_DOUBLE: u8 = 0x29;
+const TOKEN_BIG_DECIMAL: u8 = 0x2a;
+const TOKEN_TINY_ASCII: u8 = 0x40;
+const TOKEN_SHORT_ASCII: u8 = 0x60;
+const TOKEN_TINY_UNICODE: u8 = 0x80;
+const TOKEN_SHORT_UNICODE: u8 = 0xa0;
+const TOKEN_SMALL_INT: u8 = 0xc0;
+const TOKEN_LONG_ASCII: u8 = 0xe0;
+const TOKEN_LONG_UNICODE: u8 = 0xe4;
+const TOKEN_BINARY_7BIT: u8 = 0xe8;
+const TOKEN_LONG_SHARED_VALUE: u8 = 0xec;
+const TOKEN_START_ARRAY: u8 = 0xf8;
+const TOKEN_END_ARRAY: u8 = 0xf9;
+const TOKEN_START_OBJECT: u8 = 0xfa;
+const TOKEN_END_OBJECT: u8 = 0xfb;
+const TOKEN_END_STRING: u8 = 0xfc;
+const TOKEN_BINARY_RAW: u8 = 0xfd;
+const TOKEN_END_CONTENT: u8 = 0xff;
+
+const KEY_EMPTY_STRING: u8 = 0x20;
+const KEY_LONG_SHARED: u8 = 0x30;
+const KEY_LONG_UNICODE: u8 = 0x34;
+const KEY_SHORT_SHARED: u8 = 0x40;
+const KEY_SHORT_ASCII: u8 = 0x80;
+const KEY_SHORT_UNICODE: u8 = 0xc0;
+
+// both tables are cleared rather than grown once they reach this size
+const MAX_SHARED_STRINGS: usize = 1024;
+const MAX_SHORT_KEY_BYTES: usize = 64;
+const MAX_SHORT_UNICODE_KEY_BYTES: usize = 57;
+const MAX_SHORT_VALUE_BYTES: usize = 64;
@@ -0,0 +1,578 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use serde::ser;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::Write;
+
+use crate::smile::error::Error;
+use crate::smile::{
+    HEADER, HEADER_SHARED_NAMES, KEY_EMPTY_STRING, KEY_LONG_SHARED, KEY_LONG_UNICODE,
+    KEY_SHORT_ASCII, KEY_SHORT_SHARED, KEY_SHORT_UNICODE, MAX_SHARED_STRINGS, MAX_SHORT_KEY_BYTES,
+    MAX_SHORT_UNICODE_KEY_BYTES, MAX_SHORT_VALUE_BYTES, TOKEN_BIG_INTEGER, TOKEN_BINARY_7BIT,
+    TOKEN_DOUBLE, TOKEN_EMPTY_STRING, TOKEN_END_ARRAY, TOKEN_END_OBJECT, TOKEN_END_STRING,
+    TOKEN_FALSE, TOKEN_FLOAT, TOKEN_INT, TOKEN_LONG, TOKEN_LONG_ASCII, TOKEN_LONG_UNICODE,
+    TOKEN_NULL, TOKEN_SHORT_ASCII, TOKEN_SHORT_UNICODE, TOKEN_SMALL_INT, TOKEN_START_ARRAY,
+    TOKEN_START_OBJECT, TOKEN_TINY_ASCII, TOKEN_TINY_UNICODE, TOKEN_TRUE,
+};
+
+/// Serializes a value as SMILE into a byte buffer.
+pub fn to_vec<T>(value: &T) -> Result<Vec<u8>, Error>
+where
+    T: ?Sized + ser::Serialize,
+{
+    let mut buf = Vec::with_capacity(128);
+    value.serialize(&mut Serializer::new(&mut buf))?;
+    Ok(buf)
+}
+
+/// Serializes a value as SMILE into a writer.
+pub fn to_writer<W, T>(writer: W, value: &T) -> Result<(), Error>
+where
+    W: Write,
+    T: ?Sized + ser::Serialize,
+{
+    value.serialize(&mut Serializer::new(writer))
+}
+
+/// A serde SMILE serializer compatible with Conjure.
+///
+/// The SMILE header is written immediately before the first value.
+pub struct Serializer<W> {
+    writer: W,
+    header_pending: bool,
+    shared_keys: HashMap<String, usize>,
+}
+
+impl<W> Serializer<W>
+where
+    W: Write,
+{
+    /// Creates a new Conjure SMILE serializer.
+    pub fn new(writer: W) -> Serializer<W> {
+        Serializer {
+            writer,
+            header_pending: true,
+            shared_keys: HashMap::new(),
+        }
+    }
+
+    /// Returns the inner writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write(&mut self, buf: &[u8]) -> Result<(), Error> {
+        if self.header_pending {
+            self.header_pending = false;
+            self.writer.write_all(&HEADER).map_err(Error::io)?;
+            self.writer
+                .write_all(&[HEADER_SHARED_NAMES])
+                .map_err(Error::io)?;
+        }
+
+        self.writer.write_all(buf).map_err(Error::io)
+    }
+
+    fn write_i32(&mut self, v: i32) -> Result<(), Error> {
+        let zigzag = ((v << 1) ^ (v >> 31)) as u32;
+        if (-16..=15).contains(&v) {
+            return self.write(&[TOKEN_SMALL_INT | zigzag as u8]);
+        }
+
+        self.write(&[TOKEN_INT])?;
+        self.write_vint(u64::from(zigzag))
+    }
+
+    fn write_i64(&mut self, v: i64) -> Result<(), Error> {
+        if let Ok(v) = i32::try_from(v) {
+            return self.write_i32(v);
+        }
+
+        self.write(&[TOKEN_LONG])?;
+        self.write_vint(((v << 1) ^ (v >> 63)) as u64)
+    }
+
+    fn write_i128(&mut self, v: i128) -> Result<(), Error> {
+        if let Ok(v) = i64::try_from(v) {
+            return self.write_i64(v);
+        }
+
+        // the minimal big-endian two's complement representation
+        let bytes = v.to_be_bytes();
+        let mut start = 0;
+        while start < bytes.len() - 1
+            && ((bytes[start] == 0 && bytes[start + 1] & 0x80 == 0)
+                || (bytes[start] == 0xff && bytes[start + 1] & 0x80 != 0))
+        {
+            start += 1;
+        }
+        self.write_big_integer(&bytes[start..])
+    }
+
+    fn write_u128(&mut self, v: u128) -> Result<(), Error> {
+        if let Ok(v) = i128::try_from(v) {
+            return self.write_i128(v);
+        }
+
+        // a leading zero byte keeps the value positive
+        let mut bytes = vec![0];
+        bytes.extend_from_slice(&v.to_be_bytes());
+        self.write_big_integer(&bytes)
+    }
+
+    fn write_big_integer(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.write(&[TOKEN_BIG_INTEGER])?;
+        self.write_vint(bytes.len() as u64)?;
+        self.write_7bit(bytes)
+    }
+
+    fn write_vint(&mut self, mut v: u64) -> Result<(), Error> {
+        // the last byte holds 6 bits and has its high bit set, and the others hold 7 bits each
+        let mut buf = [0; 10];
+        let mut start = buf.len() - 1;
+        buf[start] = 0x80 | (v & 0x3f) as u8;
+        v >>= 6;
+        while v != 0 {
+            start -= 1;
+            buf[start] = (v & 0x7f) as u8;
+            v >>= 7;
+        }
+        self.write(&buf[start..])
+    }
+
+    fn write_7bit(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        // each chunk of up to 7 bytes is spread across one more byte than it contains, 7 bits at a time, with the
+        // final byte holding the remaining low bits
+        for chunk in bytes.chunks(7) {
+            let len = chunk.len();
+            let value = chunk.iter().fold(0u64, |v, &b| (v << 8) | u64::from(b));
+
+            let mut buf = [0; 8];
+            for (i, b) in buf[..len].iter_mut().enumerate() {
+                *b = (value >> (8 * len - 7 * (i + 1))) as u8 & 0x7f;
+            }
+            buf[len] = (value & ((1 << len) - 1)) as u8;
+            self.write(&buf[..=len])?;
+        }
+
+        Ok(())
+    }
+
+    fn write_str(&mut self, v: &str) -> Result<(), Error> {
+        let len = v.len();
+        if len == 0 {
+            return self.write(&[TOKEN_EMPTY_STRING]);
+        }
+
+        let ascii = v.is_ascii();
+        if len <= MAX_SHORT_VALUE_BYTES {
+            let token = match (ascii, len) {
+                (true, 1..=32) => TOKEN_TINY_ASCII + (len - 1) as u8,
+                (true, _) => TOKEN_SHORT_ASCII + (len - 33) as u8,
+                // non-ASCII strings are at least 2 bytes long
+                (false, 2..=33) => TOKEN_TINY_UNICODE + (len - 2) as u8,
+                (false, _) => TOKEN_SHORT_UNICODE + (len - 34) as u8,
+            };
+            self.write(&[token])?;
+            return self.write(v.as_bytes());
+        }
+
+        let token = if ascii {
+            TOKEN_LONG_ASCII
+        } else {
+            TOKEN_LONG_UNICODE
+        };
+        self.write(&[token])?;
+        self.write(v.as_bytes())?;
+        self.write(&[TOKEN_END_STRING])
+    }
+
+    fn write_key(&mut self, key: &str) -> Result<(), Error> {
+        if key.is_empty() {
+            return self.write(&[KEY_EMPTY_STRING]);
+        }
+
+        if let Some(&index) = self.shared_keys.get(key) {
+            return if index < 64 {
+                self.write(&[KEY_SHORT_SHARED | index as u8])
+            } else {
+                self.write(&[KEY_LONG_SHARED | (index >> 8) as u8, index as u8])
+            };
+        }
+
+        let len = key.len();
+        let ascii = key.is_ascii();
+        if ascii && len <= MAX_SHORT_KEY_BYTES {
+            self.write(&[KEY_SHORT_ASCII + (len - 1) as u8])?;
+            self.write(key.as_bytes())?;
+        } else if !ascii && len <= MAX_SHORT_UNICODE_KEY_BYTES {
+            self.write(&[KEY_SHORT_UNICODE + (len - 2) as u8])?;
+            self.write(key.as_bytes())?;
+        } else {
+            self.write(&[KEY_LONG_UNICODE])?;
+            self.write(key.as_bytes())?;
+            self.write(&[TOKEN_END_STRING])?;
+        }
+
+        if self.shared_keys.len() == MAX_SHARED_STRINGS {
+            self.shared_keys.clear();
+        }
+        let index = self.shared_keys.len();
+        self.shared_keys.insert(key.to_string(), index);
+
+        Ok(())
+    }
+}
+
+impl<'a, W> ser::Serializer for &'a mut Serializer<W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Compound<'a, W>;
+    type SerializeTuple = Compound<'a, W>;
+    type SerializeTupleStruct = Compound<'a, W>;
+    type SerializeTupleVariant = Compound<'a, W>;
+    type SerializeMap = Compound<'a, W>;
+    type SerializeStruct = Compound<'a, W>;
+    type SerializeStructVariant = Compound<'a, W>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.write(&[if v { TOKEN_TRUE } else { TOKEN_FALSE }])
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> {
+        self.write_i32(i32::from(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Error> {
+        self.write_i32(i32::from(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Error> {
+        self.write_i32(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        self.write_i64(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), Error> {
+        self.write_i128(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> {
+        self.write_i32(i32::from(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Error> {
+        self.write_i32(i32::from(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Error> {
+        self.write_i64(i64::from(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Error> {
+        self.write_i128(i128::from(v))
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), Error> {
+        self.write_u128(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Error> {
+        let bits = v.to_bits();
+        let mut buf = [TOKEN_FLOAT, 0, 0, 0, 0, 0];
+        for (i, b) in buf[1..].iter_mut().enumerate() {
+            *b = (bits >> (28 - 7 * i)) as u8 & 0x7f;
+        }
+        self.write(&buf)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Error> {
+        let bits = v.to_bits();
+        let mut buf = [TOKEN_DOUBLE, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        for (i, b) in buf[1..].iter_mut().enumerate() {
+            *b = (bits >> (63 - 7 * i)) as u8 & 0x7f;
+        }
+        self.write(&buf)
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.write_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        self.write_str(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        self.write(&[TOKEN_BINARY_7BIT])?;
+        self.write_vint(v.len() as u64)?;
+        self.write_7bit(v)
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.write(&[TOKEN_NULL])
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.write(&[TOKEN_NULL])
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), Error> {
+        self.write(&[TOKEN_NULL])
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.write_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.write(&[TOKEN_START_OBJECT])?;
+        self.write_key(variant)?;
+        value.serialize(&mut *self)?;
+        self.write(&[TOKEN_END_OBJECT])
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Compound<'a, W>, Error> {
+        self.write(&[TOKEN_START_ARRAY])?;
+        Ok(Compound { ser: self })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Compound<'a, W>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, len: usize) -> Result<Compound<'a, W>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Compound<'a, W>, Error> {
+        self.write(&[TOKEN_START_OBJECT])?;
+        self.write_key(variant)?;
+        self.write(&[TOKEN_START_ARRAY])?;
+        Ok(Compound { ser: self })
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Compound<'a, W>, Error> {
+        self.write(&[TOKEN_START_OBJECT])?;
+        Ok(Compound { ser: self })
+    }
+
+    fn serialize_struct(self, _: &'static str, len: usize) -> Result<Compound<'a, W>, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+        _: usize,
+    ) -> Result<Compound<'a, W>, Error> {
+        self.write(&[TOKEN_START_OBJECT])?;
+        self.write_key(variant)?;
+        self.write(&[TOKEN_START_OBJECT])?;
+        Ok(Compound { ser: self })
+    }
+}
+
+pub struct Compound<'a, W> {
+    ser: &'a mut Serializer<W>,
+}
+
+impl<'a, W> ser::SerializeSeq for Compound<'a, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.ser.write(&[TOKEN_END_ARRAY])
+    }
+}
+
+impl<'a, W> ser::SerializeTuple for Compound<'a, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl<'a, W> ser::SerializeTupleStruct for Compound<'a, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl<'a, W> ser::SerializeTupleVariant for Compound<'a, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.ser.write(&[TOKEN_END_ARRAY, TOKEN_END_OBJECT])
+    }
+}
+
+impl<'a, W> ser::SerializeMap for Compound<'a, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        key.serialize(MapKeySerializer {
+            ser: &mut *self.ser,
+        })
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.ser.write(&[TOKEN_END_OBJECT])
+    }
+}
+
+impl<'a, W> ser::SerializeStruct for Compound<'a, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.ser.write_key(key)?;
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        ser::SerializeMap::end(self)
+    }
+}
+
+impl<'a, W> ser::SerializeStructVariant for Compound<'a, W>
+where
+    W: Write,
+{
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.ser.write(&[TOKEN_END_OBJECT, TOKEN_END_OBJECT])
+    }
+}
+
+// SMILE object keys are always strings
+map_key_serializer!(write_key);
@@ -0,0 +1,198 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::f64;
+use std::fmt::Debug;
+
+use crate::smile::{client_from_reader, client_from_slice, server_from_slice, to_vec};
+
+const HEADER: &[u8] = b":)\n\x01";
+
+fn smile(body: &[u8]) -> Vec<u8> {
+    let mut buf = HEADER.to_vec();
+    buf.extend_from_slice(body);
+    buf
+}
+
+fn test_de<T>(ty: &T, smile: &[u8])
+where
+    T: DeserializeOwned + PartialEq + Debug,
+{
+    assert_eq!(*ty, client_from_slice::<T>(smile).unwrap());
+    assert_eq!(*ty, server_from_slice::<T>(smile).unwrap());
+
+    let mut r = smile;
+    assert_eq!(*ty, client_from_reader::<_, T>(&mut r).unwrap());
+}
+
+fn test_serde<T>(ty: &T, expected_smile: &[u8])
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    assert_eq!(to_vec(ty).unwrap(), expected_smile);
+    test_de(ty, expected_smile);
+}
+
+#[test]
+fn scalars() {
+    test_serde(&1, &smile(&[0xc2]));
+    test_serde(&-16, &smile(&[0xdf]));
+    test_serde(&100, &smile(&[0x24, 0x03, 0x88]));
+    test_serde(
+        &(1i64 << 40),
+        &smile(&[0x25, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80]),
+    );
+    test_serde(&true, &smile(&[0x23]));
+    test_serde(&None::<i32>, &smile(&[0x21]));
+    test_serde(&"".to_string(), &smile(&[0x20]));
+    test_serde(&"foo".to_string(), &smile(&[0x42, b'f', b'o', b'o']));
+    test_serde(&"é".to_string(), &smile(&[0x80, 0xc3, 0xa9]));
+    test_serde(&0.5, &smile(&[0x29, 0x00, 0x3f, 0x70, 0, 0, 0, 0, 0, 0, 0]));
+}
+
+#[test]
+fn big_integers() {
+    test_serde(
+        &!0u64,
+        &smile(&[
+            0x26, 0x89, 0x00, 0x3f, 0x7f, 0x7f, 0x7f, 0x7f, 0x7f, 0x7f, 0x7f, 0x7f, 0x03,
+        ]),
+    );
+    let value = -(1i128 << 100);
+    test_de(&value, &to_vec(&value).unwrap());
+}
+
+#[test]
+fn long_strings() {
+    for value in &["a".repeat(100), "é".repeat(100)] {
+        test_de(value, &to_vec(value).unwrap());
+
+        let map = vec![(value.clone(), 1)]
+            .into_iter()
+            .collect::<BTreeMap<_, _>>();
+        test_de(&map, &to_vec(&map).unwrap());
+    }
+}
+
+#[test]
+fn binary_serde() {
+    test_serde(
+        &ByteBuf::from(b"foobar".to_vec()),
+        &smile(&[0xe8, 0x86, 0x33, 0x1b, 0x6d, 0x76, 0x13, 0x05, 0x32]),
+    );
+
+    test_de(
+        &ByteBuf::from(b"foobar".to_vec()),
+        &smile(&[0xfd, 0x86, b'f', b'o', b'o', b'b', b'a', b'r']),
+    );
+    test_de(
+        &ByteBuf::from(b"foobar".to_vec()),
+        &smile(&[0x47, b'Z', b'm', b'9', b'v', b'Y', b'm', b'F', b'y']),
+    );
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn nonfinite_doubles() {
+    for &value in &[f64::INFINITY, f64::NEG_INFINITY, f64::NAN] {
+        let deserialized = client_from_slice::<f64>(&to_vec(&value).unwrap()).unwrap();
+        assert!((value.is_nan() && deserialized.is_nan()) || value == deserialized);
+    }
+
+    let deserialized = client_from_slice::<f64>(&to_vec("-Infinity").unwrap()).unwrap();
+    assert_eq!(deserialized, f64::NEG_INFINITY);
+}
+
+#[test]
+fn shared_keys() {
+    let value = vec![json!({"a": 1}), json!({"a": 2})];
+    test_serde(
+        &value,
+        &smile(&[
+            0xf8, 0xfa, 0x80, b'a', 0xc2, 0xfb, 0xfa, 0x40, 0xc4, 0xfb, 0xf9,
+        ]),
+    );
+
+    // enough keys to use long references and to overflow the table
+    let map = (0..1100)
+        .map(|i| (i.to_string(), i))
+        .collect::<BTreeMap<_, _>>();
+    let value = vec![map.clone(), map];
+    test_de(&value, &to_vec(&value).unwrap());
+}
+
+#[test]
+fn shared_values() {
+    test_de(
+        &vec!["foo".to_string(), "foo".to_string()],
+        b":)\n\x03\xf8\x42foo\x01\xf9",
+    );
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+enum Enum {
+    Unit,
+    Newtype(i32),
+    Struct { foo: i32 },
+}
+
+#[test]
+fn enums() {
+    test_serde(&Enum::Unit, &smile(b"\x43Unit"));
+    test_serde(&Enum::Newtype(1), &smile(b"\xfa\x86Newtype\xc2\xfb"));
+    test_de(
+        &Enum::Struct { foo: 1 },
+        &to_vec(&Enum::Struct { foo: 1 }).unwrap(),
+    );
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Foo {
+    foo: i32,
+}
+
+#[test]
+fn client_unknown_fields() {
+    let smile = to_vec(&json!({"foo": 1, "bogus": {"hello": [1, 2]}})).unwrap();
+    let deserialized = client_from_slice::<Foo>(&smile).unwrap();
+    assert_eq!(Foo { foo: 1 }, deserialized);
+}
+
+#[test]
+fn server_unknown_fields() {
+    let smile = to_vec(&json!({"foo": 1, "bogus": "hello"})).unwrap();
+    let e = server_from_slice::<Foo>(&smile).unwrap_err();
+
+    assert!(e.to_string().contains("foo"));
+    assert!(e.to_string().contains("bogus"));
+
+    // unknown fields of nested values are still rejected, but other ignored values aren't
+    let smile = to_vec(&json!({"a": {"foo": 1, "bogus": 2}})).unwrap();
+    assert!(server_from_slice::<BTreeMap<String, Foo>>(&smile).is_err());
+    let value = server_from_slice::<serde::de::IgnoredAny>(&smile);
+    assert!(value.is_ok());
+}
+
+#[test]
+fn invalid() {
+    assert!(client_from_slice::<i32>(b"\xc2").is_err());
+    assert!(client_from_slice::<i32>(&smile(&[0xc2, 0xc2])).is_err());
+    assert!(client_from_slice::<i32>(&smile(&[0xc2, 0xff])).is_ok());
+    assert!(client_from_slice::<String>(&smile(&[0x01])).is_err());
+    assert!(client_from_slice::<Vec<i32>>(&smile(&[0xf8, 0xc2])).is_err());
+}
@@ -8,6 +8,15 @@ fn main() {
     println!("cargo:rerun-if-changed={}", input);
     conjure_codegen::Config::new()
         .strip_prefix("com.palantir.conjure".to_string())
+        .external_type("java.lang.Long", "crate::external::ExternalLong")
+        .marker_attribute(
+            "com.palantir.conjure.test.Idempotent",
+            "doc(alias = \"idempotent\")",
+        )
+        .marker_bound(
+            "com.palantir.conjure.test.Idempotent",
+            "crate::external::IdempotentClient",
+        )
         .generate_files(input, output)
         .unwrap();
 }
@@ -0,0 +1,56 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use conjure_object::serde::{de, ser};
+use conjure_object::{FromPlain, Plain};
+use std::fmt;
+use std::num::ParseIntError;
+
+/// The Rust type that the `java.lang.Long` external import is mapped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExternalLong(pub i64);
+
+impl ser::Serialize for ExternalLong {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        s.serialize_i64(self.0)
+    }
+}
+
+impl<'de> de::Deserialize<'de> for ExternalLong {
+    fn deserialize<D>(d: D) -> Result<ExternalLong, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        i64::deserialize(d).map(ExternalLong)
+    }
+}
+
+impl Plain for ExternalLong {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, fmt)
+    }
+}
+
+impl FromPlain for ExternalLong {
+    type Err = ParseIntError;
+
+    fn from_plain(s: &str) -> Result<ExternalLong, ParseIntError> {
+        s.parse().map(ExternalLong)
+    }
+}
+
+/// A marker trait for clients which may be used with idempotent endpoints.
+pub trait IdempotentClient {}
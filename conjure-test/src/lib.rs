@@ -13,6 +13,7 @@
 // limitations under the License.
 #![warn(clippy::all)]
 
+mod external;
 #[cfg(test)]
 mod test;
 
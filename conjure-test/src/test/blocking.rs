@@ -0,0 +1,223 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use conjure_error::{Error, ErrorKind};
+use conjure_http::blocking::{BlockingClient, Executor};
+use conjure_http::encoding::Encoding;
+use conjure_http::loopback::LoopbackClient;
+use conjure_hyper::client::HyperClient;
+use conjure_object::BearerToken;
+use futures::{executor, future};
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::io::Read;
+use std::thread;
+use std::time::Duration;
+use tokio::runtime::{self, Handle};
+
+use super::clients::StreamingBody as RequestBody;
+use super::hyper_server;
+use super::servers::{StreamingBody, TestServiceHandler};
+use crate::types::*;
+
+#[derive(Clone)]
+struct FuturesExecutor;
+
+impl Executor for FuturesExecutor {
+    fn block_on<F>(&self, future: F) -> F::Output
+    where
+        F: Future,
+    {
+        executor::block_on(future)
+    }
+}
+
+// drives futures in the context of a runtime running on another thread
+#[derive(Clone)]
+struct TokioExecutor(Handle);
+
+impl Executor for TokioExecutor {
+    fn block_on<F>(&self, future: F) -> F::Output
+    where
+        F: Future,
+    {
+        self.0.enter(|| executor::block_on(future))
+    }
+}
+
+fn client(
+    handler: TestServiceHandler,
+) -> TestServiceClient<BlockingClient<LoopbackClient, FuturesExecutor>> {
+    let client = LoopbackClient::new().async_resource(TestServiceResource::new(handler));
+    TestServiceClient::new(BlockingClient::new(client, FuturesExecutor))
+}
+
+#[test]
+fn params() {
+    let client = client(
+        TestServiceHandler::new().query_params(|normal, custom, list, set| {
+            assert_eq!(normal, "hello world");
+            assert_eq!(custom, Some(10));
+            assert_eq!(list, vec![1, 2]);
+            assert_eq!(set, vec![true].into_iter().collect());
+            Ok(())
+        }),
+    );
+
+    let mut set = BTreeSet::new();
+    set.insert(true);
+    client
+        .query_params("hello world", Some(10), &[1, 2], &set)
+        .unwrap();
+}
+
+#[test]
+fn auth() {
+    let client = client(TestServiceHandler::new().cookie_auth(|auth| {
+        assert_eq!(auth, BearerToken::new("fizzbuzz").unwrap());
+        Ok(())
+    }));
+
+    client
+        .cookie_auth(&BearerToken::new("fizzbuzz").unwrap())
+        .unwrap();
+}
+
+#[test]
+fn json() {
+    let client = client(
+        TestServiceHandler::new()
+            .optional_json_request(|body| {
+                assert_eq!(body, Some("hello world".to_string()));
+                Ok(())
+            })
+            .map_json_response(|| {
+                Ok(vec![("hello".to_string(), "world".to_string())]
+                    .into_iter()
+                    .collect())
+            }),
+    );
+
+    client.optional_json_request(Some("hello world")).unwrap();
+    assert_eq!(
+        client.map_json_response().unwrap(),
+        vec![("hello".to_string(), "world".to_string())]
+            .into_iter()
+            .collect()
+    );
+}
+
+#[test]
+fn binary() {
+    let client = client(
+        TestServiceHandler::new()
+            .streaming_request(|body| {
+                assert_eq!(body, vec![1, 2, 3]);
+                Ok(())
+            })
+            .optional_streaming_response(|| Ok(Some(StreamingBody(vec![4, 5, 6]))))
+            .streaming_response(|| Ok(StreamingBody(vec![7, 8, 9]))),
+    );
+
+    client.streaming_request(RequestBody(&[1, 2, 3])).unwrap();
+
+    let mut body = client.optional_streaming_response().unwrap().unwrap();
+    let mut buf = vec![];
+    body.read_to_end(&mut buf).unwrap();
+    assert_eq!(buf, vec![4, 5, 6]);
+
+    let mut body = client.streaming_response().unwrap();
+    let mut buf = [0; 2];
+    assert_eq!(body.read(&mut buf).unwrap(), 2);
+    assert_eq!(buf, [7, 8]);
+    assert_eq!(body.read(&mut buf).unwrap(), 1);
+    assert_eq!(buf[0], 9);
+    assert_eq!(body.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn service_error() {
+    let client = client(TestServiceHandler::new().json_response(|| {
+        Err(Error::service_safe(
+            "simple error",
+            SimpleError::new("hello", 15, false),
+        ))
+    }));
+
+    let error = client.json_response().unwrap_err();
+    assert_eq!(
+        error.service_error::<SimpleError>(),
+        Some(SimpleError::new("hello", 15, false))
+    );
+}
+
+#[test]
+fn qos_error() {
+    let client = client(TestServiceHandler::new().json_response(|| {
+        Err(Error::throttle_for_safe(
+            "slow down",
+            Duration::from_millis(1500),
+        ))
+    }));
+
+    let error = client.json_response().unwrap_err();
+    match error.kind() {
+        ErrorKind::Throttle(e) => assert_eq!(e.duration(), Some(Duration::from_secs(2))),
+        kind => panic!("unexpected error kind {:?}", kind),
+    }
+}
+
+// creates a client over a HyperClient, driven by a runtime running on another thread
+fn hyper_client(
+    encoding: Encoding,
+) -> TestServiceClient<BlockingClient<HyperClient, TokioExecutor>> {
+    let mut runtime = runtime::Builder::new()
+        .basic_scheduler()
+        .enable_all()
+        .build()
+        .unwrap();
+    let handle = runtime.handle().clone();
+    thread::spawn(move || runtime.block_on(future::pending::<()>()));
+
+    let client = handle.enter(|| HyperClient::new(hyper_server::serve()).encoding(encoding));
+    TestServiceClient::new(BlockingClient::new(client, TokioExecutor(handle)))
+}
+
+#[test]
+fn hyper() {
+    let client = hyper_client(Encoding::Json);
+
+    client.json_request("hello world").unwrap();
+    assert_eq!(client.json_response().unwrap(), "hello world");
+
+    client
+        .streaming_request(RequestBody(b"hello world"))
+        .unwrap();
+
+    let mut body = client.streaming_response().unwrap();
+    let mut buf = vec![];
+    body.read_to_end(&mut buf).unwrap();
+    assert_eq!(buf, b"hello world");
+}
+
+#[test]
+fn hyper_smile() {
+    let client = hyper_client(Encoding::Smile);
+
+    let body = BinaryAndBooleanKeys::builder()
+        .binary(b"hello world".to_vec())
+        .insert_map(true, 1)
+        .insert_map(false, 2)
+        .build();
+    assert_eq!(client.echo_object(&body).unwrap(), body);
+}
@@ -0,0 +1,259 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use conjure_error::{Error, ErrorKind, PermissionDenied};
+use conjure_http::breaker::{CircuitBreakingClient, CircuitState};
+use conjure_http::loopback::LoopbackClient;
+use conjure_object::Any;
+use futures::executor;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use super::servers::TestServiceHandler;
+use super::support::loopback_client;
+use crate::types::*;
+
+type Transitions = Arc<Mutex<Vec<(&'static str, CircuitState, CircuitState)>>>;
+
+// creates a client whose `json_response` endpoint returns the result of `response` for each attempt number
+fn client<F>(
+    response: F,
+) -> (
+    CircuitBreakingClient<LoopbackClient>,
+    Arc<AtomicUsize>,
+    Transitions,
+)
+where
+    F: Fn(usize) -> Result<(), Error> + 'static + Sync + Send,
+{
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let response = Arc::new(response);
+    let handler = || {
+        let attempts = attempts.clone();
+        let response = response.clone();
+        TestServiceHandler::new()
+            .json_response(move || {
+                response(attempts.fetch_add(1, Ordering::SeqCst))
+                    .map(|()| "hello world".to_string())
+            })
+            .empty_request(|| Ok(()))
+    };
+    let client = loopback_client(handler);
+
+    let transitions = Transitions::default();
+    let client = CircuitBreakingClient::new(client)
+        .target("test")
+        .minimum_requests(4)
+        .window_size(4)
+        .failure_threshold(0.5)
+        .cool_down(Duration::from_millis(10))
+        .on_transition({
+            let transitions = transitions.clone();
+            move |transition| {
+                assert_eq!(transition.target(), Some("test"));
+                transitions.lock().unwrap().push((
+                    transition.endpoint().name(),
+                    transition.from(),
+                    transition.to(),
+                ))
+            }
+        });
+
+    (client, attempts, transitions)
+}
+
+fn assert_open(error: Error) {
+    match error.kind() {
+        ErrorKind::Unavailable(_) => {}
+        kind => panic!("unexpected error kind {:?}", kind),
+    }
+    assert_eq!(error.safe_params()["target"], Any::new("test").unwrap());
+    assert_eq!(
+        error.safe_params()["endpoint"],
+        Any::new("jsonResponse").unwrap()
+    );
+}
+
+#[test]
+fn open_after_failures() {
+    let (client, attempts, transitions) = client(|attempt| {
+        if attempt % 2 == 0 {
+            Err(Error::unavailable_safe("go away"))
+        } else {
+            Err(Error::internal_safe("oh no"))
+        }
+    });
+    let client = TestServiceClient::new(client);
+
+    for _ in 0..4 {
+        client.json_response().unwrap_err();
+    }
+    assert_eq!(
+        *transitions.lock().unwrap(),
+        vec![("jsonResponse", CircuitState::Closed, CircuitState::Open)]
+    );
+
+    assert_open(client.json_response().unwrap_err());
+    assert_eq!(attempts.load(Ordering::SeqCst), 4);
+
+    // other endpoints are unaffected
+    client.empty_request().unwrap();
+}
+
+#[test]
+fn targets_have_separate_circuits() {
+    let (client, attempts, _) = client(|_| Err(Error::unavailable_safe("go away")));
+    let other = TestServiceClient::new(client.clone().target("other"));
+    let clone = TestServiceClient::new(client.clone());
+    let client = TestServiceClient::new(client);
+
+    for _ in 0..4 {
+        client.json_response().unwrap_err();
+    }
+    assert_open(client.json_response().unwrap_err());
+    assert_open(clone.json_response().unwrap_err());
+    assert_eq!(attempts.load(Ordering::SeqCst), 4);
+
+    other.json_response().unwrap_err();
+    assert_eq!(attempts.load(Ordering::SeqCst), 5);
+}
+
+#[test]
+fn failure_threshold() {
+    let (client, attempts, transitions) = client(|attempt| {
+        if attempt % 4 == 0 {
+            Err(Error::unavailable_safe("go away"))
+        } else {
+            Ok(())
+        }
+    });
+    let client = TestServiceClient::new(client);
+
+    for _ in 0..12 {
+        let _ = client.json_response();
+    }
+    assert_eq!(attempts.load(Ordering::SeqCst), 12);
+    assert!(transitions.lock().unwrap().is_empty());
+}
+
+#[test]
+fn minimum_requests_limited_to_window() {
+    let (client, attempts, transitions) = client(|_| Err(Error::unavailable_safe("go away")));
+    let client = TestServiceClient::new(client.minimum_requests(10));
+
+    for _ in 0..4 {
+        client.json_response().unwrap_err();
+    }
+    assert_eq!(
+        *transitions.lock().unwrap(),
+        vec![("jsonResponse", CircuitState::Closed, CircuitState::Open)]
+    );
+
+    assert_open(client.json_response().unwrap_err());
+    assert_eq!(attempts.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+#[should_panic(expected = "window size must be greater than 0")]
+fn empty_window() {
+    CircuitBreakingClient::new(LoopbackClient::new()).window_size(0);
+}
+
+#[test]
+fn other_errors_ignored() {
+    let (client, attempts, transitions) = client(|attempt| {
+        if attempt % 2 == 0 {
+            Err(Error::throttle_safe("slow down"))
+        } else {
+            Err(Error::service_safe("no", PermissionDenied::new()))
+        }
+    });
+    let client = TestServiceClient::new(client);
+
+    for _ in 0..8 {
+        client.json_response().unwrap_err();
+    }
+    assert_eq!(attempts.load(Ordering::SeqCst), 8);
+    assert!(transitions.lock().unwrap().is_empty());
+}
+
+#[test]
+fn half_open_success() {
+    let (client, attempts, transitions) = client(|attempt| {
+        if attempt < 4 {
+            Err(Error::unavailable_safe("go away"))
+        } else {
+            Ok(())
+        }
+    });
+    let client = TestServiceClient::new(client);
+
+    for _ in 0..4 {
+        client.json_response().unwrap_err();
+    }
+    assert_open(client.json_response().unwrap_err());
+
+    thread::sleep(Duration::from_millis(20));
+    client.json_response().unwrap();
+    client.json_response().unwrap();
+    assert_eq!(attempts.load(Ordering::SeqCst), 6);
+    assert_eq!(
+        *transitions.lock().unwrap(),
+        vec![
+            ("jsonResponse", CircuitState::Closed, CircuitState::Open),
+            ("jsonResponse", CircuitState::Open, CircuitState::HalfOpen),
+            ("jsonResponse", CircuitState::HalfOpen, CircuitState::Closed),
+        ]
+    );
+}
+
+#[test]
+fn half_open_failure() {
+    let (client, attempts, transitions) = client(|_| Err(Error::unavailable_safe("go away")));
+    let client = TestServiceClient::new(client);
+
+    for _ in 0..4 {
+        client.json_response().unwrap_err();
+    }
+
+    thread::sleep(Duration::from_millis(20));
+    client.json_response().unwrap_err();
+    assert_open(client.json_response().unwrap_err());
+    assert_eq!(attempts.load(Ordering::SeqCst), 5);
+    assert_eq!(
+        *transitions.lock().unwrap(),
+        vec![
+            ("jsonResponse", CircuitState::Closed, CircuitState::Open),
+            ("jsonResponse", CircuitState::Open, CircuitState::HalfOpen),
+            ("jsonResponse", CircuitState::HalfOpen, CircuitState::Open),
+        ]
+    );
+}
+
+#[test]
+fn async_client() {
+    let (client, attempts, transitions) = client(|_| Err(Error::internal_safe("oh no")));
+    let client = TestServiceAsyncClient::new(client);
+
+    for _ in 0..4 {
+        executor::block_on(client.json_response()).unwrap_err();
+    }
+    assert_open(executor::block_on(client.json_response()).unwrap_err());
+    assert_eq!(attempts.load(Ordering::SeqCst), 4);
+    assert_eq!(
+        *transitions.lock().unwrap(),
+        vec![("jsonResponse", CircuitState::Closed, CircuitState::Open)]
+    );
+}
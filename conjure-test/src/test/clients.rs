@@ -15,9 +15,10 @@
 use async_trait::async_trait;
 use conjure_error::Error;
 use conjure_http::client::{
-    AsyncClient, AsyncRequestBody, AsyncVisitRequestBody, AsyncWriteBody, Client, RequestBody,
-    VisitRequestBody, VisitResponse, WriteBody,
+    AsyncClient, AsyncRequestBody, AsyncVisitRequestBody, AsyncWriteBody, Client, Endpoint,
+    RequestBody, VisitRequestBody, VisitResponse, WriteBody,
 };
+use conjure_http::server::{HeaderParameter, Parameter, ParameterType, PathParameter};
 use conjure_http::{PathParams, QueryParams};
 use conjure_object::serde::Serialize;
 use conjure_object::{BearerToken, ResourceIdentifier};
@@ -27,10 +28,12 @@ use http::{HeaderMap, Method};
 use std::collections::{BTreeMap, BTreeSet};
 use std::future::Future;
 use std::pin::Pin;
+use std::sync::Mutex;
 
+use crate::external::IdempotentClient;
 use crate::types::*;
 
-struct StreamingBody<'a>(&'a [u8]);
+pub(super) struct StreamingBody<'a>(pub(super) &'a [u8]);
 
 impl WriteBody<Vec<u8>> for StreamingBody<'_> {
     fn write_body(&mut self, w: &mut Vec<u8>) -> Result<(), Error> {
@@ -70,6 +73,7 @@ struct TestClient {
     headers: HeaderMap,
     body: TestBody,
     response: TestBody,
+    endpoint: Mutex<Option<&'static Endpoint>>,
 }
 
 impl TestClient {
@@ -82,6 +86,7 @@ impl TestClient {
             headers: HeaderMap::new(),
             body: TestBody::Empty,
             response: TestBody::Empty,
+            endpoint: Mutex::new(None),
         }
     }
 
@@ -117,8 +122,7 @@ impl<'b> Client for &'b TestClient {
 
     fn request<'a, T, U>(
         &self,
-        method: Method,
-        path: &'static str,
+        endpoint: &'static Endpoint,
         path_params: PathParams,
         query_params: QueryParams,
         headers: HeaderMap,
@@ -127,10 +131,12 @@ impl<'b> Client for &'b TestClient {
     ) -> Result<U::Output, Error>
     where
         T: RequestBody<'a, Vec<u8>>,
-        U: VisitResponse<Vec<u8>>,
+        U: VisitResponse<Vec<u8>> + Send,
     {
-        assert_eq!(method, self.method);
-        assert_eq!(path, self.path);
+        *self.endpoint.lock().unwrap() = Some(endpoint);
+        assert_eq!(endpoint.service(), "TestService");
+        assert_eq!(endpoint.method(), &self.method);
+        assert_eq!(endpoint.path(), self.path);
         assert_eq!(path_params, self.path_params);
         assert_eq!(query_params, self.query_params);
         assert_eq!(headers, self.headers);
@@ -152,8 +158,7 @@ impl<'b> AsyncClient for &'b TestClient {
 
     fn request<'a, T, U>(
         &'a self,
-        method: Method,
-        path: &'static str,
+        endpoint: &'static Endpoint,
         path_params: PathParams,
         query_params: QueryParams,
         headers: HeaderMap,
@@ -165,9 +170,10 @@ impl<'b> AsyncClient for &'b TestClient {
         U: VisitResponse<Self::BinaryBody> + Send + 'a,
     {
         let f = async move {
-            assert_eq!(method, self.method);
-            assert_eq!(method, self.method);
-            assert_eq!(path, self.path);
+            *self.endpoint.lock().unwrap() = Some(endpoint);
+            assert_eq!(endpoint.service(), "TestService");
+            assert_eq!(endpoint.method(), &self.method);
+            assert_eq!(endpoint.path(), self.path);
             assert_eq!(path_params, self.path_params);
             assert_eq!(query_params, self.query_params);
             assert_eq!(headers, self.headers);
@@ -194,6 +200,8 @@ impl<'b> AsyncClient for &'b TestClient {
     }
 }
 
+impl IdempotentClient for &TestClient {}
+
 struct TestBodyVisitor;
 
 impl<'a> VisitRequestBody<'a, Vec<u8>> for TestBodyVisitor {
@@ -246,7 +254,7 @@ impl<'a> AsyncVisitRequestBody<'a, Vec<u8>> for TestBodyVisitor {
 
 macro_rules! check {
     ($client:ident, $call:expr) => {
-        check!($client, $call, ());
+        check!($client, $call, ())
     };
     ($client:ident, $call:expr, $expected_response:expr) => {{
         let raw_client = $client;
@@ -257,6 +265,8 @@ macro_rules! check {
         let $client = TestServiceAsyncClient::new(&raw_client);
         let response = executor::block_on($call).unwrap();
         assert_eq!(response, $expected_response);
+
+        raw_client
     }};
 }
 
@@ -486,3 +496,61 @@ fn cookie_auth() {
         client.cookie_auth(&BearerToken::new("fizzbuzz").unwrap())
     );
 }
+
+#[test]
+fn marked_endpoint() {
+    let client = TestClient::new(Method::PUT, "/test/markedEndpoint/{id}").path_param("id", "foo");
+    let client = check!(client, client.marked_endpoint("foo"));
+
+    let endpoint = client.endpoint.lock().unwrap().unwrap();
+    assert_eq!(
+        endpoint.markers(),
+        &["com.palantir.conjure.test.Idempotent"]
+    );
+    assert_eq!(
+        TestServiceClient::<&TestClient>::MARKED_ENDPOINT_MARKERS,
+        endpoint.markers()
+    );
+}
+
+#[test]
+fn endpoint() {
+    let client = TestClient::new(Method::GET, "/test/safeParams/{safePath}/{unsafePath}")
+        .path_param("safePath", "a")
+        .path_param("unsafePath", "b")
+        .query_param("safeQueryId", "c")
+        .query_param("unsafeQueryId", "d")
+        .header("Safe-Header", "e")
+        .header("Unsafe-Header", "f");
+    TestServiceClient::new(&client)
+        .safe_params("a", "b", "c", "d", "e", "f")
+        .unwrap();
+
+    let endpoint = client.endpoint.lock().unwrap().unwrap();
+    assert_eq!(endpoint.name(), "safeParams");
+    assert!(!endpoint.deprecated());
+    assert!(endpoint.markers().is_empty());
+    assert_eq!(
+        endpoint.parameters()[4],
+        Parameter::new(
+            "safeHeader",
+            ParameterType::Header(HeaderParameter::new("Safe-Header")),
+        )
+        .with_safe(true)
+        .with_markers(&["com.palantir.logsafe.Safe"]),
+    );
+    assert_eq!(
+        endpoint.parameters()[1],
+        Parameter::new("unsafePath", ParameterType::Path(PathParameter::new())),
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_endpoint() {
+    let client = TestClient::new(Method::GET, "/test/deprecated");
+    TestServiceClient::new(&client).deprecated().unwrap();
+
+    let endpoint = client.endpoint.lock().unwrap().unwrap();
+    assert!(endpoint.deprecated());
+}
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use conjure_error::{ErrorCode, ErrorType};
+use conjure_error::{Error, ErrorCode, ErrorType, FromSerializableError, NotFound};
 use std::collections::BTreeMap;
 
 use crate::types::*;
@@ -36,3 +36,54 @@ fn error_serialization() {
     params.insert("unsafeFoo".to_string(), "false".to_string());
     assert_eq!(*encoded.parameters(), params);
 }
+
+#[test]
+fn error_decoding() {
+    let error = SimpleError::new("hello", 15, false);
+    let encoded = conjure_error::encode(&error);
+    assert_eq!(SimpleError::from_serializable_error(&encoded), Some(error));
+    assert_eq!(ComplexError::from_serializable_error(&encoded), None);
+
+    let error = ComplexError::builder()
+        .optional(1)
+        .list(vec!["hello".to_string()])
+        .alias(IntegerAlias(2))
+        .build();
+    let encoded = conjure_error::encode(&error);
+    let expected = ComplexError::builder()
+        .optional(1)
+        .alias(IntegerAlias(2))
+        .build();
+    assert_eq!(
+        ComplexError::from_serializable_error(&encoded),
+        Some(expected)
+    );
+
+    let error = ComplexError::builder().alias(IntegerAlias(2)).build();
+    let encoded = conjure_error::encode(&error);
+    assert_eq!(ComplexError::from_serializable_error(&encoded), Some(error));
+
+    let encoded = conjure_error::SerializableError::builder()
+        .error_code(ErrorCode::Internal)
+        .error_name("Test:SimpleError")
+        .error_instance_id(conjure_object::Uuid::nil())
+        .insert_parameters("foo", "hello")
+        .insert_parameters("bar", "fifteen")
+        .insert_parameters("unsafeFoo", "false")
+        .build();
+    assert_eq!(SimpleError::from_serializable_error(&encoded), None);
+}
+
+#[test]
+fn service_error() {
+    let error = Error::service("error", SimpleError::new("hello", 15, false));
+    assert_eq!(
+        error.service_error::<SimpleError>(),
+        Some(SimpleError::new("hello", 15, false))
+    );
+    assert_eq!(error.service_error::<ComplexError>(), None);
+    assert_eq!(error.service_error::<NotFound>(), None);
+
+    let error = Error::unavailable("unavailable");
+    assert_eq!(error.service_error::<SimpleError>(), None);
+}
@@ -0,0 +1,238 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use conjure_error::Error;
+use conjure_http::client::{
+    Accept, AsyncClient, AsyncRequestBody, AsyncVisitRequestBody, Endpoint, Sleep, VisitResponse,
+};
+use conjure_http::hedge::HedgingClient;
+use conjure_http::{PathParams, QueryParams};
+use conjure_object::serde::{Deserialize, Deserializer};
+use conjure_serde::json;
+use futures::{executor, future};
+use http::{HeaderMap, Method};
+use std::error;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::Poll;
+
+use super::clients::StreamingBody;
+use super::support::{poll, GateClient, OneShotBody};
+use crate::types::*;
+
+enum RequestBody<'a> {
+    Json(&'static str),
+    Object(BinaryAndBooleanKeys),
+    Binary(StreamingBody<'a>),
+    OneShot,
+}
+
+impl<'a> AsyncRequestBody<'a, Vec<u8>> for RequestBody<'a> {
+    fn accept<V>(self, visitor: V) -> V::Output
+    where
+        V: AsyncVisitRequestBody<'a, Vec<u8>>,
+    {
+        match self {
+            RequestBody::Json(body) => visitor.visit_serializable(body),
+            RequestBody::Object(body) => visitor.visit_serializable(body),
+            RequestBody::Binary(body) => visitor.visit_binary(body),
+            RequestBody::OneShot => visitor.visit_binary(OneShotBody),
+        }
+    }
+}
+
+struct StringVisitor;
+
+impl VisitResponse<Vec<u8>> for StringVisitor {
+    type Output = String;
+
+    fn accept(&self) -> Accept {
+        Accept::Serializable
+    }
+
+    fn visit_serializable<'de, D>(self, deserializer: D) -> Result<String, Error>
+    where
+        D: Deserializer<'de>,
+        D::Error: Into<Box<dyn error::Error + Sync + Send>>,
+    {
+        String::deserialize(deserializer).map_err(Error::internal)
+    }
+}
+
+const MARKER: &str = "com.palantir.conjure.Idempotent";
+
+static IDEMPOTENT_ENDPOINT: Endpoint = Endpoint::new(
+    "TestService",
+    "idempotent",
+    Method::POST,
+    "/test/idempotent",
+    &[],
+    false,
+)
+.with_markers(&[MARKER]);
+
+fn idempotent_request<'a, C>(
+    client: &'a C,
+    body: RequestBody<'a>,
+) -> Pin<Box<dyn Future<Output = Result<String, Error>> + Send + 'a>>
+where
+    C: AsyncClient<BinaryWriter = Vec<u8>, BinaryBody = Vec<u8>>,
+{
+    client.request(
+        &IDEMPOTENT_ENDPOINT,
+        PathParams::new(),
+        QueryParams::new(),
+        HeaderMap::new(),
+        body,
+        StringVisitor,
+    )
+}
+
+// a client which hedges as soon as the first attempt is pending
+fn client(raw_client: &GateClient) -> HedgingClient<GateClient, impl Sleep> {
+    HedgingClient::new(raw_client.clone())
+        .sleep(|_| future::ready(()))
+        .idempotency_marker(MARKER)
+}
+
+#[test]
+fn hedge_slow_request() {
+    let raw_client = GateClient::default();
+    let client = TestServiceAsyncClient::new(client(&raw_client));
+
+    let slow = raw_client.gate();
+    raw_client.respond(Ok("second"));
+    assert_eq!(
+        executor::block_on(client.json_response()).unwrap(),
+        "second"
+    );
+    assert_eq!(raw_client.calls(), 2);
+    assert!(slow.is_canceled());
+}
+
+#[test]
+fn first_attempt_wins() {
+    let raw_client = GateClient::default();
+    let client = TestServiceAsyncClient::new(client(&raw_client));
+
+    let first = raw_client.gate();
+    let second = raw_client.gate();
+    let mut response = Box::pin(client.json_response());
+    assert!(poll(&mut response).is_pending());
+    assert_eq!(raw_client.calls(), 2);
+
+    first.send(Ok("first".to_string())).unwrap();
+    match poll(&mut response) {
+        Poll::Ready(result) => assert_eq!(result.unwrap(), "first"),
+        Poll::Pending => panic!("request not complete"),
+    }
+    assert!(second.is_canceled());
+}
+
+#[test]
+fn fast_request_not_hedged() {
+    let raw_client = GateClient::default();
+    let client = TestServiceAsyncClient::new(
+        HedgingClient::new(raw_client.clone()).sleep(|_| future::pending()),
+    );
+
+    raw_client.respond(Ok("first"));
+    assert_eq!(executor::block_on(client.json_response()).unwrap(), "first");
+    assert_eq!(raw_client.calls(), 1);
+}
+
+#[test]
+fn non_idempotent_not_hedged() {
+    let raw_client = GateClient::default();
+    let client = TestServiceAsyncClient::new(client(&raw_client));
+
+    let gate = raw_client.gate();
+    let mut response = Box::pin(client.optional_json_request(None));
+    assert!(poll(&mut response).is_pending());
+    assert_eq!(raw_client.calls(), 1);
+
+    gate.send(Ok(String::new())).unwrap();
+    match poll(&mut response) {
+        Poll::Ready(result) => result.unwrap(),
+        Poll::Pending => panic!("request not complete"),
+    }
+}
+
+#[test]
+fn idempotency_marker() {
+    let raw_client = GateClient::default();
+    let client = client(&raw_client);
+
+    let _slow = raw_client.gate();
+    raw_client.respond(Ok("second"));
+    let response = executor::block_on(idempotent_request(&client, RequestBody::Json("hello")));
+    assert_eq!(response.unwrap(), "second");
+    assert_eq!(
+        raw_client.bodies(),
+        vec![b"\"hello\"".to_vec(), b"\"hello\"".to_vec()]
+    );
+}
+
+#[test]
+fn object_body() {
+    let raw_client = GateClient::default();
+    let client = client(&raw_client);
+
+    let body = BinaryAndBooleanKeys::builder()
+        .binary(b"hello world".to_vec())
+        .insert_map(true, 1)
+        .insert_map(false, 2)
+        .build();
+
+    let _slow = raw_client.gate();
+    raw_client.respond(Ok("second"));
+    let response = executor::block_on(idempotent_request(
+        &client,
+        RequestBody::Object(body.clone()),
+    ));
+    assert_eq!(response.unwrap(), "second");
+    let body = json::to_vec(&body).unwrap();
+    assert_eq!(raw_client.bodies(), vec![body.clone(), body]);
+}
+
+#[test]
+fn binary_body() {
+    let raw_client = GateClient::default();
+    let client = client(&raw_client);
+
+    let _slow = raw_client.gate();
+    raw_client.respond(Ok("second"));
+    let response = executor::block_on(idempotent_request(
+        &client,
+        RequestBody::Binary(StreamingBody(&[1, 2, 3])),
+    ));
+    assert_eq!(response.unwrap(), "second");
+    assert_eq!(raw_client.bodies(), vec![vec![1, 2, 3], vec![1, 2, 3]]);
+}
+
+#[test]
+fn unresettable_body() {
+    let raw_client = GateClient::default();
+    let client = client(&raw_client);
+
+    let gate = raw_client.gate();
+    let mut response = idempotent_request(&client, RequestBody::OneShot);
+    assert!(poll(&mut response).is_pending());
+    assert_eq!(raw_client.calls(), 1);
+
+    gate.send(Ok("first".to_string())).unwrap();
+    match poll(&mut response) {
+        Poll::Ready(result) => assert_eq!(result.unwrap(), "first"),
+        Poll::Pending => panic!("request not complete"),
+    }
+}
@@ -0,0 +1,282 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use async_trait::async_trait;
+use bytes::Bytes;
+use conjure_error::{Error, ErrorCode, ErrorKind};
+use conjure_http::client::AsyncWriteBody;
+use conjure_http::encoding::Encoding;
+use conjure_hyper::body::BodyWriter;
+use conjure_hyper::client::HyperClient;
+use conjure_object::ResourceIdentifier;
+use conjure_serde::{json, smile};
+use futures::io::{AsyncReadExt, AsyncWriteExt};
+use http::request::Parts;
+use http::{HeaderValue, Method, Request, Response, StatusCode};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Server, Uri};
+use std::collections::BTreeSet;
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::types::*;
+
+// Serves requests on a local port, returning a client pointed at it. The handler is passed the request with its body
+// fully read.
+fn client<F>(handler: F) -> TestServiceAsyncClient<HyperClient>
+where
+    F: Fn(Parts, Bytes) -> Response<Body> + Sync + Send + 'static,
+{
+    TestServiceAsyncClient::new(HyperClient::new(serve(handler)))
+}
+
+fn serve<F>(handler: F) -> Uri
+where
+    F: Fn(Parts, Bytes) -> Response<Body> + Sync + Send + 'static,
+{
+    let handler = Arc::new(handler);
+    let make_service = make_service_fn(move |_| {
+        let handler = handler.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                let handler = handler.clone();
+                async move {
+                    let (parts, body) = request.into_parts();
+                    let body = hyper::body::to_bytes(body).await?;
+                    Ok::<_, hyper::Error>(handler(parts, body))
+                }
+            }))
+        }
+    });
+
+    let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+    let uri = format!("http://{}/api", server.local_addr())
+        .parse()
+        .unwrap();
+    tokio::spawn(server);
+
+    uri
+}
+
+fn response(status: StatusCode, body: impl Into<Body>) -> Response<Body> {
+    let mut response = Response::new(body.into());
+    *response.status_mut() = status;
+    response
+}
+
+struct HyperBody(&'static [u8]);
+
+#[async_trait]
+impl AsyncWriteBody<BodyWriter> for HyperBody {
+    async fn write_body(self: Pin<&mut Self>, mut w: Pin<&mut BodyWriter>) -> Result<(), Error> {
+        w.write_all(self.0).await.map_err(Error::internal_safe)
+    }
+
+    async fn reset(self: Pin<&mut Self>) -> bool {
+        true
+    }
+}
+
+#[tokio::test]
+async fn query_params() {
+    let client = client(|parts, _| {
+        assert_eq!(parts.method, Method::GET);
+        assert_eq!(
+            parts.uri.path_and_query().unwrap(),
+            "/api/test/queryParams?custom=10&list=1&list=2&normal=hello%20world&set=true"
+        );
+        response(StatusCode::NO_CONTENT, Body::empty())
+    });
+
+    let mut set = BTreeSet::new();
+    set.insert(true);
+    client
+        .query_params("hello world", Some(10), &[1, 2], &set)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn path_params() {
+    let client = client(|parts, _| {
+        assert_eq!(
+            parts.uri.path(),
+            "/api/test/pathParams/hello%2Fworld%3F/false/raw/ri.conjure.main.test.foo"
+        );
+        response(StatusCode::NO_CONTENT, Body::empty())
+    });
+
+    client
+        .path_params(
+            "hello/world?",
+            false,
+            &ResourceIdentifier::new("ri.conjure.main.test.foo").unwrap(),
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn headers() {
+    let client = client(|parts, _| {
+        assert_eq!(parts.headers["Some-Custom-Header"], "hello world");
+        assert_eq!(parts.headers["Some-Optional-Header"], "2");
+        assert!(!parts.headers.contains_key("Accept"));
+        response(StatusCode::NO_CONTENT, Body::empty())
+    });
+
+    client.headers("hello world", Some(2)).await.unwrap();
+}
+
+#[tokio::test]
+async fn json_request() {
+    let client = client(|parts, body| {
+        assert_eq!(parts.method, Method::POST);
+        assert_eq!(parts.headers["Content-Type"], "application/json");
+        assert_eq!(body, r#""hello world""#);
+        response(StatusCode::NO_CONTENT, Body::empty())
+    });
+
+    client.json_request("hello world").await.unwrap();
+}
+
+#[tokio::test]
+async fn json_response() {
+    let client = client(|parts, _| {
+        assert_eq!(parts.headers["Accept"], "application/json");
+        match parts.uri.path() {
+            "/api/test/jsonResponse" => response(StatusCode::OK, r#""hello world""#),
+            "/api/test/optionalJsonResponse" => response(StatusCode::NO_CONTENT, Body::empty()),
+            path => panic!("unexpected path {}", path),
+        }
+    });
+
+    assert_eq!(client.json_response().await.unwrap(), "hello world");
+    assert_eq!(client.optional_json_response().await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn smile_request() {
+    let uri = serve(|parts, body| {
+        assert_eq!(parts.method, Method::POST);
+        assert_eq!(parts.headers["Content-Type"], "application/x-jackson-smile");
+        assert_eq!(body, smile::to_vec("hello world").unwrap());
+        response(StatusCode::NO_CONTENT, Body::empty())
+    });
+    let client = TestServiceAsyncClient::new(HyperClient::new(uri).encoding(Encoding::Smile));
+
+    client.json_request("hello world").await.unwrap();
+}
+
+#[tokio::test]
+async fn smile_response() {
+    let uri = serve(|parts, _| {
+        assert_eq!(
+            parts.headers["Accept"],
+            "application/x-jackson-smile, application/json;q=0.9"
+        );
+        let mut response = response(StatusCode::OK, smile::to_vec("hello world").unwrap());
+        response.headers_mut().insert(
+            "Content-Type",
+            HeaderValue::from_static("application/x-jackson-smile"),
+        );
+        response
+    });
+    let client = TestServiceAsyncClient::new(HyperClient::new(uri).encoding(Encoding::Smile));
+
+    assert_eq!(client.json_response().await.unwrap(), "hello world");
+}
+
+#[tokio::test]
+async fn streaming_request() {
+    let client = client(|parts, body| {
+        assert_eq!(parts.headers["Content-Type"], "application/octet-stream");
+        assert_eq!(body, &b"hello world"[..]);
+        response(StatusCode::NO_CONTENT, Body::empty())
+    });
+
+    client
+        .streaming_request(HyperBody(b"hello world"))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn streaming_response() {
+    let client = client(|parts, _| {
+        assert_eq!(parts.headers["Accept"], "application/octet-stream");
+        response(StatusCode::OK, &b"hello world"[..])
+    });
+
+    let mut body = client.streaming_response().await.unwrap();
+    let mut buf = vec![];
+    body.read_to_end(&mut buf).await.unwrap();
+    assert_eq!(buf, b"hello world");
+}
+
+#[tokio::test]
+async fn service_error() {
+    let client = client(|_, _| {
+        let error = conjure_error::encode(&SimpleError::new("hello", 15, false));
+        response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            json::to_vec(&error).unwrap(),
+        )
+    });
+
+    let error = client.json_response().await.unwrap_err();
+    assert_eq!(
+        error.service_error::<SimpleError>(),
+        Some(SimpleError::new("hello", 15, false))
+    );
+}
+
+#[tokio::test]
+async fn qos_errors() {
+    let client = client(|parts, _| match parts.uri.path() {
+        "/api/test/jsonResponse" => {
+            let mut response = response(StatusCode::TOO_MANY_REQUESTS, Body::empty());
+            response
+                .headers_mut()
+                .insert("Retry-After", "2".parse().unwrap());
+            response
+        }
+        "/api/test/emptyRequest" => response(StatusCode::SERVICE_UNAVAILABLE, Body::empty()),
+        path => panic!("unexpected path {}", path),
+    });
+
+    let error = client.json_response().await.unwrap_err();
+    match error.kind() {
+        ErrorKind::Throttle(e) => assert_eq!(e.duration(), Some(Duration::from_secs(2))),
+        kind => panic!("unexpected error kind {:?}", kind),
+    }
+
+    let error = client.empty_request().await.unwrap_err();
+    match error.kind() {
+        ErrorKind::Unavailable(_) => {}
+        kind => panic!("unexpected error kind {:?}", kind),
+    }
+}
+
+#[tokio::test]
+async fn unknown_error() {
+    let client = client(|_, _| response(StatusCode::BAD_GATEWAY, "bad gateway"));
+
+    let error = client.empty_request().await.unwrap_err();
+    match error.kind() {
+        ErrorKind::Service(e) => assert_eq!(*e.error_code(), ErrorCode::Internal),
+        kind => panic!("unexpected error kind {:?}", kind),
+    }
+}
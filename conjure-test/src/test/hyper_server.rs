@@ -0,0 +1,459 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use async_trait::async_trait;
+use conjure_error::{Error, ErrorKind};
+use conjure_http::encoding::Encoding;
+use conjure_http::server::AsyncWriteBody;
+use conjure_hyper::body::{BodyReader, BodyWriter};
+use conjure_hyper::client::HyperClient;
+use conjure_hyper::server::HyperService;
+use conjure_object::{BearerToken, ResourceIdentifier};
+use conjure_serde::{json, smile};
+use futures::io::{AsyncReadExt, AsyncWriteExt};
+use http::{Method, Request, StatusCode};
+use hyper::service::make_service_fn;
+use hyper::{Body, Server, Uri};
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::time::Duration;
+
+use crate::types::*;
+
+pub(super) fn serve() -> Uri {
+    serve_service(HyperService::new())
+}
+
+fn serve_service(service: HyperService) -> Uri {
+    let service = service.resource(TestServiceResource::new(Handler));
+    let make_service = make_service_fn(move |_| {
+        let service = service.clone();
+        async move { Ok::<_, Infallible>(service) }
+    });
+
+    let server = Server::bind(&([127, 0, 0, 1], 0).into()).serve(make_service);
+    let uri = format!("http://{}", server.local_addr()).parse().unwrap();
+    tokio::spawn(server);
+
+    uri
+}
+
+fn client() -> TestServiceAsyncClient<HyperClient> {
+    TestServiceAsyncClient::new(HyperClient::new(serve()))
+}
+
+struct HyperBody(&'static [u8]);
+
+#[async_trait]
+impl AsyncWriteBody<BodyWriter> for HyperBody {
+    async fn write_body(self, mut w: Pin<&mut BodyWriter>) -> Result<(), Error> {
+        w.write_all(self.0).await.map_err(Error::internal_safe)
+    }
+}
+
+#[async_trait]
+impl conjure_http::client::AsyncWriteBody<BodyWriter> for HyperBody {
+    async fn write_body(self: Pin<&mut Self>, mut w: Pin<&mut BodyWriter>) -> Result<(), Error> {
+        w.write_all(self.0).await.map_err(Error::internal_safe)
+    }
+
+    async fn reset(self: Pin<&mut Self>) -> bool {
+        true
+    }
+}
+
+async fn read_body(mut body: BodyReader) -> Vec<u8> {
+    let mut buf = vec![];
+    body.read_to_end(&mut buf).await.unwrap();
+    buf
+}
+
+fn unimplemented<T>() -> Result<T, Error> {
+    Err(Error::internal_safe("unimplemented"))
+}
+
+struct Handler;
+
+#[async_trait]
+impl AsyncTestService<BodyReader, BodyWriter> for Handler {
+    type StreamingResponseBody = HyperBody;
+    type OptionalStreamingResponseBody = HyperBody;
+    type StreamingAliasResponseBody = HyperBody;
+    type OptionalStreamingAliasResponseBody = HyperBody;
+
+    async fn query_params(
+        &self,
+        normal: String,
+        optional: Option<i32>,
+        list: Vec<i32>,
+        set: BTreeSet<bool>,
+    ) -> Result<(), Error> {
+        assert_eq!(normal, "hello world+&=");
+        assert_eq!(optional, Some(10));
+        assert_eq!(list, vec![1, 2]);
+        assert_eq!(set, vec![true].into_iter().collect());
+        Ok(())
+    }
+
+    async fn alias_query_params(
+        &self,
+        _: OptionalAliasAlias,
+        _: ListAliasAlias,
+        _: SetAliasAlias,
+    ) -> Result<(), Error> {
+        unimplemented()
+    }
+
+    async fn path_params(
+        &self,
+        foo: String,
+        bar: bool,
+        baz: ResourceIdentifier,
+    ) -> Result<(), Error> {
+        assert_eq!(foo, "hello/world?");
+        assert!(bar);
+        assert_eq!(baz.as_str(), "ri.conjure.main.test.foo");
+        Ok(())
+    }
+
+    async fn headers(&self, foo: String, bar: Option<i32>) -> Result<(), Error> {
+        assert_eq!(foo, "hello world");
+        assert_eq!(bar, Some(2));
+        Ok(())
+    }
+
+    async fn alias_headers(&self, _: OptionalAliasAlias) -> Result<(), Error> {
+        unimplemented()
+    }
+
+    async fn empty_request(&self) -> Result<(), Error> {
+        Err(Error::unavailable_safe("go away"))
+    }
+
+    async fn json_request(&self, body: String) -> Result<(), Error> {
+        assert_eq!(body, "hello world");
+        Ok(())
+    }
+
+    async fn optional_json_request(&self, body: Option<String>) -> Result<(), Error> {
+        assert_eq!(body, None);
+        Ok(())
+    }
+
+    async fn echo_object(&self, body: BinaryAndBooleanKeys) -> Result<BinaryAndBooleanKeys, Error> {
+        Ok(body)
+    }
+
+    async fn streaming_request(&self, body: BodyReader) -> Result<(), Error> {
+        assert_eq!(read_body(body).await, b"hello world");
+        Ok(())
+    }
+
+    async fn streaming_alias_request(&self, _: BodyReader) -> Result<(), Error> {
+        unimplemented()
+    }
+
+    async fn json_response(&self) -> Result<String, Error> {
+        Ok("hello world".to_string())
+    }
+
+    async fn optional_json_response(&self) -> Result<Option<String>, Error> {
+        Ok(None)
+    }
+
+    async fn list_json_response(&self) -> Result<Vec<String>, Error> {
+        unimplemented()
+    }
+
+    async fn set_json_response(&self) -> Result<BTreeSet<String>, Error> {
+        unimplemented()
+    }
+
+    async fn map_json_response(&self) -> Result<BTreeMap<String, String>, Error> {
+        unimplemented()
+    }
+
+    async fn streaming_response(&self) -> Result<HyperBody, Error> {
+        Ok(HyperBody(b"hello world"))
+    }
+
+    async fn optional_streaming_response(&self) -> Result<Option<HyperBody>, Error> {
+        Ok(None)
+    }
+
+    async fn streaming_alias_response(&self) -> Result<HyperBody, Error> {
+        unimplemented()
+    }
+
+    async fn optional_streaming_alias_response(&self) -> Result<Option<HyperBody>, Error> {
+        unimplemented()
+    }
+
+    async fn header_auth(&self, auth: BearerToken) -> Result<(), Error> {
+        assert_eq!(auth.as_str(), "fizzbuzz");
+        Ok(())
+    }
+
+    async fn cookie_auth(&self, auth: BearerToken) -> Result<(), Error> {
+        assert_eq!(auth.as_str(), "fizzbuzz");
+        Ok(())
+    }
+
+    async fn safe_params(
+        &self,
+        _: String,
+        _: String,
+        _: String,
+        _: String,
+        _: String,
+        _: String,
+    ) -> Result<(), Error> {
+        Err(Error::service_safe(
+            "simple error",
+            SimpleError::new("hello", 15, false),
+        ))
+    }
+
+    async fn deprecated(&self) -> Result<(), Error> {
+        Err(Error::throttle_for_safe(
+            "slow down",
+            Duration::from_millis(1500),
+        ))
+    }
+
+    async fn marked_endpoint(&self, _: String) -> Result<(), Error> {
+        unimplemented()
+    }
+}
+
+#[tokio::test]
+async fn params() {
+    let client = client();
+
+    let mut set = BTreeSet::new();
+    set.insert(true);
+    client
+        .query_params("hello world+&=", Some(10), &[1, 2], &set)
+        .await
+        .unwrap();
+
+    client
+        .path_params(
+            "hello/world?",
+            true,
+            &ResourceIdentifier::new("ri.conjure.main.test.foo").unwrap(),
+        )
+        .await
+        .unwrap();
+
+    client.headers("hello world", Some(2)).await.unwrap();
+}
+
+#[tokio::test]
+async fn auth() {
+    let client = client();
+    let token = BearerToken::new("fizzbuzz").unwrap();
+
+    client.header_auth(&token).await.unwrap();
+    client.cookie_auth(&token).await.unwrap();
+}
+
+#[tokio::test]
+async fn json() {
+    let client = client();
+
+    client.json_request("hello world").await.unwrap();
+    client.optional_json_request(None).await.unwrap();
+    assert_eq!(client.json_response().await.unwrap(), "hello world");
+    assert_eq!(client.optional_json_response().await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn smile() {
+    let client = TestServiceAsyncClient::new(HyperClient::new(serve()).encoding(Encoding::Smile));
+
+    client.json_request("hello world").await.unwrap();
+    assert_eq!(client.json_response().await.unwrap(), "hello world");
+}
+
+#[tokio::test]
+async fn content_negotiation() {
+    let uri = serve();
+    let client = hyper::Client::new();
+
+    let request = |accept| {
+        Request::builder()
+            .uri(format!(
+                "http://{}/test/jsonResponse",
+                uri.authority().unwrap()
+            ))
+            .header("Accept", accept)
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = client
+        .request(request(
+            "application/x-jackson-smile, application/json;q=0.9",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["Content-Type"],
+        "application/x-jackson-smile"
+    );
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    assert_eq!(
+        smile::client_from_slice::<String>(&body).unwrap(),
+        "hello world"
+    );
+
+    let response = client
+        .request(request("application/*;q=0.5, application/json"))
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["Content-Type"], "application/json");
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    assert_eq!(body, r#""hello world""#);
+}
+
+#[tokio::test]
+async fn request_limits() {
+    let mut json = json::ServerDeserializerBuilder::new();
+    json.max_string_length(20);
+    let uri = serve_service(
+        HyperService::new()
+            .max_request_bytes(30)
+            .json_deserializer(json),
+    );
+    let client = hyper::Client::new();
+
+    let request = |body: &str| {
+        Request::builder()
+            .method(Method::POST)
+            .uri(format!(
+                "http://{}/test/jsonRequest",
+                uri.authority().unwrap()
+            ))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let response = client.request(request(r#""hello world""#)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = client
+        .request(request(r#""hello world hello world""#))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = client
+        .request(request(r#""hello world hello world hello world""#))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn binary() {
+    let client = client();
+
+    client
+        .streaming_request(HyperBody(b"hello world"))
+        .await
+        .unwrap();
+
+    let body = client.streaming_response().await.unwrap();
+    assert_eq!(read_body(body).await, b"hello world");
+
+    assert!(client
+        .optional_streaming_response()
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn service_error() {
+    let client = client();
+
+    let error = client
+        .safe_params("a", "b", "c", "d", "e", "f")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.service_error::<SimpleError>(),
+        Some(SimpleError::new("hello", 15, false))
+    );
+}
+
+#[tokio::test]
+#[allow(deprecated)]
+async fn qos_errors() {
+    let client = client();
+
+    let error = client.deprecated().await.unwrap_err();
+    match error.kind() {
+        ErrorKind::Throttle(e) => assert_eq!(e.duration(), Some(Duration::from_secs(2))),
+        kind => panic!("unexpected error kind {:?}", kind),
+    }
+
+    let error = client.empty_request().await.unwrap_err();
+    match error.kind() {
+        ErrorKind::Unavailable(_) => {}
+        kind => panic!("unexpected error kind {:?}", kind),
+    }
+}
+
+#[tokio::test]
+async fn routing() {
+    let uri = serve();
+    let client = hyper::Client::new();
+
+    let request = |method, path| {
+        Request::builder()
+            .method(method)
+            .uri(format!("http://{}{}", uri.authority().unwrap(), path))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let response = client
+        .request(request(Method::GET, "/test/missing"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = client
+        .request(request(Method::GET, "/test/emptyRequest"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(response.headers()["Allow"], "POST");
+
+    let response = client
+        .request(request(Method::GET, "/test/pathParams/a/true/raw"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = client
+        .request(request(Method::GET, "/test/pathParams/%FF/true/raw/b"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
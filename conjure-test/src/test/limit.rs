@@ -0,0 +1,171 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use conjure_error::{Error, ErrorKind, PermissionDenied};
+use conjure_http::limit::LimitingClient;
+use futures::executor;
+use std::io;
+use std::task::Poll;
+
+use super::support::{poll, GateClient};
+use crate::types::*;
+
+#[test]
+fn queue_at_limit() {
+    let raw_client = GateClient::default();
+    let client =
+        TestServiceAsyncClient::new(LimitingClient::new(raw_client.clone()).initial_limit(1));
+
+    let first_gate = raw_client.gate();
+    let mut first = Box::pin(client.empty_request());
+    assert!(poll(&mut first).is_pending());
+
+    let second_gate = raw_client.gate();
+    let mut second = Box::pin(client.empty_request());
+    assert!(poll(&mut second).is_pending());
+    assert_eq!(raw_client.calls(), 1);
+
+    first_gate.send(Ok(String::new())).unwrap();
+    match poll(&mut first) {
+        Poll::Ready(result) => result.unwrap(),
+        Poll::Pending => panic!("request not complete"),
+    }
+
+    assert!(poll(&mut second).is_pending());
+    assert_eq!(raw_client.calls(), 2);
+    second_gate.send(Ok(String::new())).unwrap();
+    match poll(&mut second) {
+        Poll::Ready(result) => result.unwrap(),
+        Poll::Pending => panic!("request not complete"),
+    }
+}
+
+#[test]
+fn cancelled_waiter() {
+    let raw_client = GateClient::default();
+    let client =
+        TestServiceAsyncClient::new(LimitingClient::new(raw_client.clone()).initial_limit(1));
+
+    let first_gate = raw_client.gate();
+    let mut first = Box::pin(client.empty_request());
+    assert!(poll(&mut first).is_pending());
+
+    let mut second = Box::pin(client.empty_request());
+    assert!(poll(&mut second).is_pending());
+    let mut third = Box::pin(client.empty_request());
+    assert!(poll(&mut third).is_pending());
+    drop(second);
+
+    first_gate.send(Ok(String::new())).unwrap();
+    assert!(poll(&mut first).is_ready());
+
+    raw_client.respond(Ok(""));
+    match poll(&mut third) {
+        Poll::Ready(result) => result.unwrap(),
+        Poll::Pending => panic!("request not complete"),
+    }
+    assert_eq!(raw_client.calls(), 2);
+}
+
+#[test]
+fn fail_fast_at_limit() {
+    let raw_client = GateClient::default();
+    let client = TestServiceAsyncClient::new(
+        LimitingClient::new(raw_client.clone())
+            .initial_limit(1)
+            .fail_fast(true),
+    );
+
+    let _gate = raw_client.gate();
+    let mut first = Box::pin(client.empty_request());
+    assert!(poll(&mut first).is_pending());
+
+    let mut second = Box::pin(client.empty_request());
+    match poll(&mut second) {
+        Poll::Ready(Err(error)) => match error.kind() {
+            ErrorKind::Throttle(_) => {}
+            kind => panic!("unexpected error kind {:?}", kind),
+        },
+        _ => panic!("request not rejected"),
+    }
+    assert_eq!(raw_client.calls(), 1);
+}
+
+#[test]
+fn limits_per_endpoint() {
+    let raw_client = GateClient::default();
+    let client = TestServiceAsyncClient::new(
+        LimitingClient::new(raw_client.clone())
+            .initial_limit(1)
+            .fail_fast(true),
+    );
+
+    let _gate = raw_client.gate();
+    let mut first = Box::pin(client.empty_request());
+    assert!(poll(&mut first).is_pending());
+
+    raw_client.respond(Ok(""));
+    let mut second = Box::pin(client.optional_json_request(None));
+    match poll(&mut second) {
+        Poll::Ready(result) => result.unwrap(),
+        Poll::Pending => panic!("request not complete"),
+    }
+}
+
+#[test]
+fn grow_on_success() {
+    let raw_client = GateClient::default();
+    let client = LimitingClient::new(raw_client.clone()).initial_limit(2);
+    let service = TestServiceAsyncClient::new(client.clone());
+
+    for _ in 0..3 {
+        raw_client.respond(Ok(""));
+        executor::block_on(service.empty_request()).unwrap();
+    }
+    assert_eq!(client.limit(raw_client.last_endpoint()), 3);
+}
+
+#[test]
+fn shrink_on_dropped_requests() {
+    let errors = vec![
+        Error::throttle_safe("slow down"),
+        Error::unavailable_safe("go away"),
+        Error::internal_safe(io::Error::new(io::ErrorKind::TimedOut, "timed out")),
+    ];
+
+    for error in errors {
+        let raw_client = GateClient::default();
+        let client = LimitingClient::new(raw_client.clone()).initial_limit(2);
+        let service = TestServiceAsyncClient::new(client.clone());
+
+        raw_client.respond(Err(error));
+        executor::block_on(service.empty_request()).unwrap_err();
+        assert_eq!(client.limit(raw_client.last_endpoint()), 1);
+
+        // the limit never drops below the minimum
+        raw_client.respond(Err(Error::unavailable_safe("go away")));
+        executor::block_on(service.empty_request()).unwrap_err();
+        assert_eq!(client.limit(raw_client.last_endpoint()), 1);
+    }
+}
+
+#[test]
+fn ignore_other_errors() {
+    let raw_client = GateClient::default();
+    let client = LimitingClient::new(raw_client.clone()).initial_limit(2);
+    let service = TestServiceAsyncClient::new(client.clone());
+
+    raw_client.respond(Err(Error::service_safe("no", PermissionDenied::new())));
+    executor::block_on(service.empty_request()).unwrap_err();
+    assert_eq!(client.limit(raw_client.last_endpoint()), 2);
+}
@@ -0,0 +1,185 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use conjure_error::{Error, ErrorCode, ErrorKind, NotFound};
+use conjure_http::loopback::LoopbackClient;
+use conjure_object::BearerToken;
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use super::clients::StreamingBody as RequestBody;
+use super::servers::{StreamingBody, TestServiceHandler};
+use super::support::loopback_client;
+use crate::types::*;
+
+// checks that a call succeeds with the expected value against both the blocking and async clients
+macro_rules! check {
+    ($client:ident, $call:expr, $expected:expr) => {
+        for result in call!($client, $call) {
+            assert_eq!(result.unwrap(), $expected);
+        }
+    };
+}
+
+#[test]
+fn params() {
+    let client = loopback_client(|| {
+        TestServiceHandler::new().query_params(|normal, custom, list, set| {
+            assert_eq!(normal, "hello world");
+            assert_eq!(custom, Some(10));
+            assert_eq!(list, vec![1, 2]);
+            assert_eq!(set, vec![true].into_iter().collect());
+            Ok(())
+        })
+    });
+
+    let mut set = BTreeSet::new();
+    set.insert(true);
+    check!(
+        client,
+        client.query_params("hello world", Some(10), &[1, 2], &set),
+        ()
+    );
+}
+
+#[test]
+fn auth() {
+    let client = loopback_client(|| {
+        TestServiceHandler::new().cookie_auth(|auth| {
+            assert_eq!(auth, BearerToken::new("fizzbuzz").unwrap());
+            Ok(())
+        })
+    });
+
+    check!(
+        client,
+        client.cookie_auth(&BearerToken::new("fizzbuzz").unwrap()),
+        ()
+    );
+}
+
+#[test]
+fn json() {
+    let client = loopback_client(|| {
+        TestServiceHandler::new()
+            .optional_json_request(|body| {
+                assert_eq!(body, Some("hello world".to_string()));
+                Ok(())
+            })
+            .map_json_response(|| {
+                Ok(vec![("hello".to_string(), "world".to_string())]
+                    .into_iter()
+                    .collect())
+            })
+    });
+
+    check!(
+        client,
+        client.optional_json_request(Some("hello world")),
+        ()
+    );
+    check!(
+        client,
+        client.map_json_response(),
+        vec![("hello".to_string(), "world".to_string())]
+            .into_iter()
+            .collect()
+    );
+}
+
+#[test]
+fn binary() {
+    let client = loopback_client(|| {
+        TestServiceHandler::new()
+            .streaming_request(|body| {
+                assert_eq!(body, vec![1, 2, 3]);
+                Ok(())
+            })
+            .optional_streaming_response(|| Ok(Some(StreamingBody(vec![4, 5, 6]))))
+    });
+
+    check!(
+        client,
+        client.streaming_request(RequestBody(&[1, 2, 3])),
+        ()
+    );
+    check!(
+        client,
+        client.optional_streaming_response(),
+        Some(vec![4, 5, 6])
+    );
+}
+
+#[test]
+fn service_error() {
+    let client = loopback_client(|| {
+        TestServiceHandler::new().json_response(|| {
+            Err(Error::service_safe(
+                "simple error",
+                SimpleError::new("hello", 15, false),
+            ))
+        })
+    });
+
+    for result in call!(client, client.json_response()) {
+        let error = result.unwrap_err();
+        assert_eq!(
+            error.service_error::<SimpleError>(),
+            Some(SimpleError::new("hello", 15, false))
+        );
+    }
+}
+
+#[test]
+fn qos_errors() {
+    let client = loopback_client(|| {
+        TestServiceHandler::new()
+            .json_response(|| {
+                Err(Error::throttle_for_safe(
+                    "slow down",
+                    Duration::from_millis(1500),
+                ))
+            })
+            .empty_request(|| Err(Error::unavailable_safe("go away")))
+    });
+
+    for result in call!(client, client.json_response()) {
+        let error = result.unwrap_err();
+        match error.kind() {
+            ErrorKind::Throttle(e) => assert_eq!(e.duration(), Some(Duration::from_secs(2))),
+            kind => panic!("unexpected error kind {:?}", kind),
+        }
+    }
+
+    for result in call!(client, client.empty_request()) {
+        let error = result.unwrap_err();
+        match error.kind() {
+            ErrorKind::Unavailable(_) => {}
+            kind => panic!("unexpected error kind {:?}", kind),
+        }
+    }
+}
+
+#[test]
+fn unregistered_endpoint() {
+    let client = LoopbackClient::new();
+
+    for result in call!(client, client.empty_request()) {
+        let error = result.unwrap_err();
+        assert!(error.service_error::<NotFound>().is_some());
+        match error.kind() {
+            ErrorKind::Service(e) => assert_eq!(*e.error_code(), ErrorCode::NotFound),
+            kind => panic!("unexpected error kind {:?}", kind),
+        }
+    }
+}
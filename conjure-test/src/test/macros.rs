@@ -0,0 +1,51 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use conjure_error::ErrorType;
+
+#[allow(dead_code, clippy::all)]
+mod types {
+    conjure_macros::include_conjure!("test-ir.json", strip_prefix = "com.palantir.conjure");
+}
+
+#[allow(dead_code, clippy::all)]
+mod exhaustive_types {
+    conjure_macros::include_conjure!(
+        "test-ir.json",
+        exhaustive = true,
+        strip_prefix = "com.palantir.conjure",
+    );
+}
+
+#[test]
+fn matches_build_script() {
+    let inline = types::foo::SubpackageObject::new(types::IntegerAlias(1));
+    let generated = crate::types::foo::SubpackageObject::new(crate::types::IntegerAlias(1));
+    assert_eq!(
+        conjure_serde::json::to_string(&inline).unwrap(),
+        conjure_serde::json::to_string(&generated).unwrap(),
+    );
+
+    let error = types::SimpleError::new("hello", 15, false);
+    assert_eq!(error.name(), "Test:SimpleError");
+}
+
+#[test]
+#[allow(deprecated)]
+fn exhaustive() {
+    let value = match exhaustive_types::TestEnum::One {
+        exhaustive_types::TestEnum::One => 1,
+        exhaustive_types::TestEnum::Two => 2,
+    };
+    assert_eq!(value, 1);
+}
@@ -12,7 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#[macro_use]
+mod support;
+
+mod blocking;
+mod breaker;
 mod clients;
 mod errors;
+mod hedge;
+mod hyper_client;
+mod hyper_server;
+mod limit;
+mod loopback;
+mod macros;
 mod objects;
+mod retry;
 mod servers;
+mod staged_builders;
@@ -12,8 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::external::ExternalLong;
 use crate::types::*;
-use conjure_object::Any;
+use conjure_object::{Any, FromPlain, ToPlain};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::collections::{BTreeMap, BTreeSet};
@@ -68,12 +69,38 @@ where
     assert_eq!(*ty, deserialized);
 }
 
+fn test_cbor<T>(ty: &T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let cbor = conjure_serde::cbor::to_vec(ty).unwrap();
+    let deserialized = conjure_serde::cbor::client_from_slice(&cbor).unwrap();
+    assert_eq!(*ty, deserialized);
+    let deserialized = conjure_serde::cbor::server_from_slice(&cbor).unwrap();
+    assert_eq!(*ty, deserialized);
+}
+
+fn test_canonical<T>(ty: &T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let canonical = conjure_serde::json::to_canonical_vec(ty).unwrap();
+    let any = conjure_serde::json::to_canonical_vec(&Any::new(ty).unwrap()).unwrap();
+    assert_eq!(canonical, any);
+    let deserialized = conjure_serde::json::client_from_slice::<T>(&canonical).unwrap();
+    assert_eq!(*ty, deserialized);
+    let roundtrip = conjure_serde::json::to_canonical_vec(&deserialized).unwrap();
+    assert_eq!(canonical, roundtrip);
+}
+
 fn test_serde<T>(ty: &T, expected_json: &str)
 where
     T: Serialize + DeserializeOwned + PartialEq + Debug,
 {
     test_ser(ty, expected_json);
     test_de(ty, expected_json);
+    test_cbor(ty);
+    test_canonical(ty);
 }
 
 #[test]
@@ -254,3 +281,62 @@ fn optional_binary_field() {
     let value = OptionalBinaryField::builder().build();
     test_serde(&value, json);
 }
+
+#[test]
+fn try_build() {
+    let value = CustomValueHandling::builder()
+        .binary(b"hello".to_vec())
+        .double(1.)
+        .try_build()
+        .unwrap();
+    assert_eq!(value, CustomValueHandling::new(b"hello".to_vec(), 1.));
+
+    let e = CustomValueHandling::builder()
+        .double(1.)
+        .try_build()
+        .unwrap_err();
+    assert_eq!(e.type_name(), "CustomValueHandling");
+    assert_eq!(e.missing_fields(), &["binary"]);
+
+    let e = CustomValueHandling::builder().try_build().unwrap_err();
+    assert_eq!(e.missing_fields(), &["binary", "double"]);
+    assert_eq!(
+        e.to_string(),
+        "required fields of `CustomValueHandling` were not set: binary, double"
+    );
+
+    let value = OptionalConstructorFields::builder().try_build().unwrap();
+    assert_eq!(value, OptionalConstructorFields::builder().build());
+}
+
+#[test]
+fn external_types() {
+    let json = r#"
+    {
+        "long": 1,
+        "optional": 2,
+        "list": [3, 4]
+    }
+    "#;
+    let value = ExternalFields::builder()
+        .long(ExternalLong(1))
+        .optional(ExternalLong(2))
+        .extend_list(vec![ExternalLong(3), ExternalLong(4)])
+        .build();
+    let constructor = ExternalFields::new(
+        ExternalLong(1),
+        ExternalLong(2),
+        vec![ExternalLong(3), ExternalLong(4)],
+    );
+    assert_eq!(value, constructor);
+    assert_eq!(*value.long(), ExternalLong(1));
+    assert_eq!(value.optional(), Some(&ExternalLong(2)));
+    test_serde(&value, json);
+
+    test_serde(&ExternalLongAlias(ExternalLong(5)), "5");
+    assert_eq!(ExternalLongAlias(ExternalLong(5)).to_plain(), "5");
+    assert_eq!(
+        ExternalLongAlias::from_plain("5").unwrap(),
+        ExternalLongAlias(ExternalLong(5))
+    );
+}
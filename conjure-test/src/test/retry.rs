@@ -0,0 +1,210 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use conjure_error::{Error, ErrorKind};
+use conjure_http::client::Sleep;
+use conjure_http::loopback::LoopbackClient;
+use conjure_http::retry::RetryingClient;
+use futures::future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::clients::StreamingBody as RequestBody;
+use super::servers::TestServiceHandler;
+use super::support::{loopback_client, OneShotBody};
+use crate::types::*;
+
+fn client<F>(handler: F) -> RetryingClient<LoopbackClient, impl Sleep + Clone>
+where
+    F: Fn() -> TestServiceHandler,
+{
+    RetryingClient::new(loopback_client(handler))
+        .backoff_slot_size(Duration::from_millis(1))
+        .sleep(|_| future::ready(()))
+}
+
+fn failing_handler<F>(failures: usize, error: F, attempts: &Arc<AtomicUsize>) -> TestServiceHandler
+where
+    F: Fn() -> Error + 'static + Sync + Send,
+{
+    let attempts = attempts.clone();
+    let count = AtomicUsize::new(0);
+    TestServiceHandler::new().json_response(move || {
+        attempts.fetch_add(1, Ordering::SeqCst);
+        if count.fetch_add(1, Ordering::SeqCst) < failures {
+            Err(error())
+        } else {
+            Ok("hello world".to_string())
+        }
+    })
+}
+
+#[test]
+fn retry_throttle() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let client = client(|| failing_handler(2, || Error::throttle_safe("slow down"), &attempts));
+
+    for result in call!(client, client.json_response()) {
+        assert_eq!(result.unwrap(), "hello world");
+    }
+    assert_eq!(attempts.load(Ordering::SeqCst), 6);
+}
+
+#[test]
+fn retry_unavailable() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let client = client(|| failing_handler(3, || Error::unavailable_safe("go away"), &attempts));
+
+    for result in call!(client, client.json_response()) {
+        assert_eq!(result.unwrap(), "hello world");
+    }
+    assert_eq!(attempts.load(Ordering::SeqCst), 8);
+}
+
+#[test]
+fn max_attempts() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let client = client(|| failing_handler(10, || Error::unavailable_safe("go away"), &attempts))
+        .max_attempts(2);
+
+    for result in call!(client, client.json_response()) {
+        match result.unwrap_err().kind() {
+            ErrorKind::Unavailable(_) => {}
+            kind => panic!("unexpected error kind {:?}", kind),
+        }
+    }
+    assert_eq!(attempts.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn service_errors_not_retried() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let client = client(|| failing_handler(10, || Error::internal_safe("oh no"), &attempts));
+
+    for result in call!(client, client.json_response()) {
+        result.unwrap_err();
+    }
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn retry_budget() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let client = client(|| {
+        failing_handler(
+            10,
+            || Error::throttle_for_safe("slow down", Duration::from_secs(1)),
+            &attempts,
+        )
+    })
+    .retry_budget(Duration::from_millis(100));
+
+    for result in call!(client, client.json_response()) {
+        match result.unwrap_err().kind() {
+            ErrorKind::Throttle(_) => {}
+            kind => panic!("unexpected error kind {:?}", kind),
+        }
+    }
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn serializable_body() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let client = client(|| {
+        let attempts = attempts.clone();
+        TestServiceHandler::new().optional_json_request(move |body| {
+            assert_eq!(body, Some("hello world".to_string()));
+            if attempts.fetch_add(1, Ordering::SeqCst) & 1 == 0 {
+                Err(Error::unavailable_safe("go away"))
+            } else {
+                Ok(())
+            }
+        })
+    });
+
+    for result in call!(client, client.optional_json_request(Some("hello world"))) {
+        result.unwrap();
+    }
+    assert_eq!(attempts.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn object_body() {
+    let body = BinaryAndBooleanKeys::builder()
+        .binary(b"hello world".to_vec())
+        .insert_map(true, 1)
+        .insert_map(false, 2)
+        .build();
+
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let client = client(|| {
+        let attempts = attempts.clone();
+        let expected = body.clone();
+        TestServiceHandler::new().echo_object(move |body| {
+            assert_eq!(body, expected);
+            if attempts.fetch_add(1, Ordering::SeqCst) & 1 == 0 {
+                Err(Error::unavailable_safe("go away"))
+            } else {
+                Ok(body)
+            }
+        })
+    });
+
+    for result in call!(client, client.echo_object(&body)) {
+        assert_eq!(result.unwrap(), body);
+    }
+    assert_eq!(attempts.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn binary_body() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let client = client(|| {
+        let attempts = attempts.clone();
+        TestServiceHandler::new().streaming_request(move |body| {
+            assert_eq!(body, vec![1, 2, 3]);
+            if attempts.fetch_add(1, Ordering::SeqCst) & 1 == 0 {
+                Err(Error::unavailable_safe("go away"))
+            } else {
+                Ok(())
+            }
+        })
+    });
+
+    for result in call!(client, client.streaming_request(RequestBody(&[1, 2, 3]))) {
+        result.unwrap();
+    }
+    assert_eq!(attempts.load(Ordering::SeqCst), 4);
+}
+
+#[test]
+fn unresettable_body() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let client = client(|| {
+        let attempts = attempts.clone();
+        TestServiceHandler::new().streaming_request(move |_| {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(Error::unavailable_safe("go away"))
+        })
+    });
+
+    for result in call!(client, client.streaming_request(OneShotBody)) {
+        match result.unwrap_err().kind() {
+            ErrorKind::Unavailable(_) => {}
+            kind => panic!("unexpected error kind {:?}", kind),
+        }
+    }
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+}
@@ -21,7 +21,7 @@ use conjure_http::server::{
     WriteBody,
 };
 use conjure_http::{PathParams, QueryParams};
-use conjure_object::{BearerToken, ResourceIdentifier};
+use conjure_object::{Any, BearerToken, ResourceIdentifier};
 use conjure_serde::json::{self, ServerDeserializer};
 use http::{HeaderMap, Method};
 use serde::Serialize;
@@ -36,14 +36,14 @@ macro_rules! test_service_handler {
     ($(
         fn $fn_name:ident(&self $(, $arg_name:ident : $arg_type:ty)*) -> Result<$ret_type:ty, Error>;
     )*) => {
-        struct TestServiceHandler {
+        pub(super) struct TestServiceHandler {
             $(
                 $fn_name: Option<Box<dyn Fn($($arg_type),*) -> Result<$ret_type, Error> + Sync + Send>>,
             )*
         }
 
         impl TestServiceHandler {
-            fn new() -> TestServiceHandler {
+            pub(super) fn new() -> TestServiceHandler {
                 TestServiceHandler {
                     $($fn_name: None,)*
                 }
@@ -51,7 +51,7 @@ macro_rules! test_service_handler {
 
             $(
                 #[allow(dead_code)]
-                fn $fn_name<F>(mut self, f: F) -> TestServiceHandler
+                pub(super) fn $fn_name<F>(mut self, f: F) -> TestServiceHandler
                 where
                     F: Fn($($arg_type),*) -> Result<$ret_type, Error> + 'static + Sync + Send,
                 {
@@ -125,6 +125,8 @@ test_service_handler! {
 
     fn optional_json_request(&self, body: Option<String>) -> Result<(), Error>;
 
+    fn echo_object(&self, body: BinaryAndBooleanKeys) -> Result<BinaryAndBooleanKeys, Error>;
+
     fn streaming_request(&self, body: Vec<u8>) -> Result<(), Error>;
 
     fn streaming_alias_request(&self, body: Vec<u8>) -> Result<(), Error>;
@@ -162,6 +164,8 @@ test_service_handler! {
     ) -> Result<(), Error>;
 
     fn deprecated(&self) -> Result<(), Error>;
+
+    fn marked_endpoint(&self, id: String) -> Result<(), Error>;
 }
 
 impl TestServiceHandler {
@@ -266,7 +270,7 @@ impl Call {
 }
 
 #[derive(PartialEq, Debug, Clone)]
-struct StreamingBody(Vec<u8>);
+pub(super) struct StreamingBody(pub(super) Vec<u8>);
 
 impl WriteBody<Vec<u8>> for StreamingBody {
     fn write_body(self, w: &mut Vec<u8>) -> Result<(), Error> {
@@ -513,6 +517,28 @@ fn json_request() {
         .send("jsonRequest");
 }
 
+#[test]
+fn invalid_json_request() {
+    let resource = TestServiceResource::new(TestServiceHandler::new());
+    let endpoint = <TestServiceResource<TestServiceHandler> as Resource<_, _>>::endpoints()
+        .into_iter()
+        .find(|e| e.metadata.name() == "jsonRequest")
+        .unwrap();
+
+    let error = endpoint
+        .handler
+        .handle(
+            &resource,
+            &PathParams::new(),
+            &QueryParams::new(),
+            &HeaderMap::new(),
+            TestBody::Json("1".to_string()),
+            TestResponseVisitor,
+        )
+        .unwrap_err();
+    assert_eq!(error.safe_params()["path"], Any::new("$").unwrap());
+}
+
 #[test]
 fn optional_json_request() {
     TestServiceHandler::new()
@@ -728,13 +754,16 @@ fn endpoint() {
     assert!(!endpoint.metadata.deprecated());
 
     let expected_params = &[
-        Parameter::new("safePath", ParameterType::Path(PathParameter::new())).with_safe(true),
+        Parameter::new("safePath", ParameterType::Path(PathParameter::new()))
+            .with_safe(true)
+            .with_markers(&["com.palantir.logsafe.Safe"]),
         Parameter::new("unsafePath", ParameterType::Path(PathParameter::new())),
         Parameter::new(
             "safeQuery",
             ParameterType::Query(QueryParameter::new("safeQueryId")),
         )
-        .with_safe(true),
+        .with_safe(true)
+        .with_markers(&["com.palantir.logsafe.Safe"]),
         Parameter::new(
             "unsafeQuery",
             ParameterType::Query(QueryParameter::new("unsafeQueryId")),
@@ -743,7 +772,8 @@ fn endpoint() {
             "safeHeader",
             ParameterType::Header(HeaderParameter::new("Safe-Header")),
         )
-        .with_safe(true),
+        .with_safe(true)
+        .with_markers(&["com.palantir.logsafe.Safe"]),
         Parameter::new(
             "unsafeHeader",
             ParameterType::Header(HeaderParameter::new("Unsafe-Header")),
@@ -766,6 +796,29 @@ fn deprecated_endpoint() {
     assert!(endpoint.metadata.deprecated());
 }
 
+#[test]
+fn marked_endpoint() {
+    let endpoint = <TestServiceResource<TestServiceHandler> as Resource<_, _>>::endpoints::<
+        TestBody,
+        TestResponseVisitor,
+    >()
+    .into_iter()
+    .find(|e| e.metadata.name() == "markedEndpoint")
+    .unwrap();
+
+    assert_eq!(
+        endpoint.metadata.markers(),
+        &["com.palantir.conjure.test.Idempotent"]
+    );
+
+    let expected_params = &[
+        Parameter::new("id", ParameterType::Path(PathParameter::new()))
+            .with_safe(true)
+            .with_markers(&["com.palantir.logsafe.Safe"]),
+    ];
+    assert_eq!(endpoint.metadata.parameters(), expected_params);
+}
+
 struct EnsureAsyncTraitWorks;
 
 #[async_trait]
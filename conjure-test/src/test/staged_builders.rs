@@ -0,0 +1,63 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use conjure_object::SafeLong;
+
+#[allow(dead_code, clippy::all)]
+mod types {
+    conjure_macros::include_conjure!(
+        "test-ir.json",
+        staged_builders = true,
+        strip_prefix = "com.palantir.conjure",
+    );
+}
+
+#[test]
+fn required_fields() {
+    let value = types::CustomValueHandling::builder()
+        .binary(b"hello".to_vec())
+        .double(1.)
+        .build();
+    assert_eq!(
+        value,
+        types::CustomValueHandling::new(b"hello".to_vec(), 1.)
+    );
+}
+
+#[test]
+fn optional_fields() {
+    let value = types::ExternalFields::builder()
+        .long(SafeLong::new(1).unwrap())
+        .optional(SafeLong::new(2).unwrap())
+        .push_list(SafeLong::new(3).unwrap())
+        .build();
+    assert_eq!(value.long(), SafeLong::new(1).unwrap());
+    assert_eq!(value.optional(), Some(SafeLong::new(2).unwrap()));
+    assert_eq!(value.list(), &[SafeLong::new(3).unwrap()]);
+
+    let value = types::OptionalConstructorFields::builder()
+        .integer(1)
+        .build();
+    assert_eq!(value.integer(), Some(1));
+}
+
+#[test]
+fn from_value() {
+    let value = types::ExternalFields::builder()
+        .long(SafeLong::new(1).unwrap())
+        .build();
+
+    let mut builder = types::external_fields::Builder::from(value);
+    builder.long(SafeLong::new(2).unwrap());
+    assert_eq!(builder.build().long(), SafeLong::new(2).unwrap());
+}
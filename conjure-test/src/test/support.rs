@@ -0,0 +1,219 @@
+// Copyright 2020 Palantir Technologies, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use async_trait::async_trait;
+use conjure_error::Error;
+use conjure_http::client::{
+    Accept, AsyncClient, AsyncRequestBody, AsyncVisitRequestBody, AsyncWriteBody, Endpoint,
+    VisitResponse, WriteBody,
+};
+use conjure_http::loopback::LoopbackClient;
+use conjure_http::{PathParams, QueryParams};
+use conjure_object::serde::Serialize;
+use conjure_serde::json;
+use futures::channel::oneshot;
+use futures::task::noop_waker;
+use futures::FutureExt;
+use http::HeaderMap;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use super::servers::TestServiceHandler;
+use crate::types::*;
+
+// a loopback client serving both blocking and async requests with handlers created by `handler`
+pub(super) fn loopback_client<F>(handler: F) -> LoopbackClient
+where
+    F: Fn() -> TestServiceHandler,
+{
+    LoopbackClient::new()
+        .resource(TestServiceResource::new(handler()))
+        .async_resource(TestServiceResource::new(handler()))
+}
+
+// runs a call against both the blocking and async clients, returning the results
+macro_rules! call {
+    ($client:ident, $call:expr) => {{
+        let raw_client = $client.clone();
+
+        let $client = TestServiceClient::new(raw_client.clone());
+        let sync = $call;
+
+        let $client = TestServiceAsyncClient::new(raw_client);
+        let async_ = futures::executor::block_on($call);
+
+        vec![sync, async_]
+    }};
+}
+
+type Gate = oneshot::Receiver<Result<String, Error>>;
+type Call = (&'static Endpoint, Vec<u8>);
+
+// a client whose responses are controlled by the test
+#[derive(Clone, Default)]
+pub(super) struct GateClient {
+    gates: Arc<Mutex<VecDeque<Gate>>>,
+    calls: Arc<Mutex<Vec<Call>>>,
+}
+
+impl GateClient {
+    // queues a response which is returned once the sender is used
+    pub(super) fn gate(&self) -> oneshot::Sender<Result<String, Error>> {
+        let (tx, rx) = oneshot::channel();
+        self.gates.lock().unwrap().push_back(rx);
+        tx
+    }
+
+    // queues a response which is returned immediately
+    pub(super) fn respond(&self, response: Result<&str, Error>) {
+        let _ = self.gate().send(response.map(str::to_string));
+    }
+
+    pub(super) fn calls(&self) -> usize {
+        self.calls.lock().unwrap().len()
+    }
+
+    pub(super) fn last_endpoint(&self) -> &'static Endpoint {
+        self.calls.lock().unwrap().last().unwrap().0
+    }
+
+    // the request bodies of each call, serializable bodies encoded as JSON
+    pub(super) fn bodies(&self) -> Vec<Vec<u8>> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, body)| body.clone())
+            .collect()
+    }
+}
+
+impl AsyncClient for GateClient {
+    type BinaryWriter = Vec<u8>;
+    type BinaryBody = Vec<u8>;
+
+    fn request<'a, T, U>(
+        &'a self,
+        endpoint: &'static Endpoint,
+        _: PathParams,
+        _: QueryParams,
+        _: HeaderMap,
+        body: T,
+        response_visitor: U,
+    ) -> Pin<Box<dyn Future<Output = Result<U::Output, Error>> + Send + 'a>>
+    where
+        T: AsyncRequestBody<'a, Self::BinaryWriter> + Send + 'a,
+        U: VisitResponse<Self::BinaryBody> + Send + 'a,
+    {
+        let gate = self.gates.lock().unwrap().pop_front().unwrap();
+
+        Box::pin(async move {
+            let buf = match body.accept(BodyVisitor)? {
+                Some(mut body) => {
+                    let mut buf = vec![];
+                    body.as_mut().write_body(Pin::new(&mut buf)).await?;
+                    buf
+                }
+                None => vec![],
+            };
+            self.calls.lock().unwrap().push((endpoint, buf));
+
+            let response = gate.await.unwrap()?;
+            match response_visitor.accept() {
+                Accept::Serializable => {
+                    let response = json::to_vec(&response).unwrap();
+                    response_visitor
+                        .visit_serializable(&mut json::ClientDeserializer::from_slice(&response))
+                }
+                _ => response_visitor.visit_empty(),
+            }
+        })
+    }
+}
+
+// writes serializable bodies out immediately, and returns binary bodies to be written by the client
+struct BodyVisitor;
+
+impl<'a> AsyncVisitRequestBody<'a, Vec<u8>> for BodyVisitor {
+    type Output = Result<Option<Pin<Box<dyn AsyncWriteBody<Vec<u8>> + Sync + Send + 'a>>>, Error>;
+
+    fn visit_empty(self) -> Self::Output {
+        Ok(None)
+    }
+
+    fn visit_serializable<T>(self, body: T) -> Self::Output
+    where
+        T: Serialize + 'a,
+    {
+        let body = json::to_vec(&body).map_err(Error::internal)?;
+        Ok(Some(Box::pin(StaticBody(body))))
+    }
+
+    fn visit_binary<T>(self, body: T) -> Self::Output
+    where
+        T: AsyncWriteBody<Vec<u8>> + Sync + Send + 'a,
+    {
+        Ok(Some(Box::pin(body)))
+    }
+}
+
+struct StaticBody(Vec<u8>);
+
+#[async_trait]
+impl AsyncWriteBody<Vec<u8>> for StaticBody {
+    async fn write_body(self: Pin<&mut Self>, mut w: Pin<&mut Vec<u8>>) -> Result<(), Error> {
+        w.extend_from_slice(&self.0);
+        Ok(())
+    }
+
+    async fn reset(self: Pin<&mut Self>) -> bool {
+        true
+    }
+}
+
+// a binary body which can only be written once
+pub(super) struct OneShotBody;
+
+impl WriteBody<Vec<u8>> for OneShotBody {
+    fn write_body(&mut self, w: &mut Vec<u8>) -> Result<(), Error> {
+        w.extend_from_slice(&[1, 2, 3]);
+        Ok(())
+    }
+
+    fn reset(&mut self) -> bool {
+        false
+    }
+}
+
+#[async_trait]
+impl AsyncWriteBody<Vec<u8>> for OneShotBody {
+    async fn write_body(self: Pin<&mut Self>, mut w: Pin<&mut Vec<u8>>) -> Result<(), Error> {
+        w.extend_from_slice(&[1, 2, 3]);
+        Ok(())
+    }
+
+    async fn reset(self: Pin<&mut Self>) -> bool {
+        false
+    }
+}
+
+// polls a future once without a runtime
+pub(super) fn poll<F>(future: &mut F) -> Poll<F::Output>
+where
+    F: Future + Unpin,
+{
+    future.poll_unpin(&mut Context::from_waker(&noop_waker()))
+}
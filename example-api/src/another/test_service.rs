@@ -10,6 +10,8 @@ where
     pub fn new(client: T) -> TestServiceAsyncClient<T> {
         TestServiceAsyncClient(client)
     }
+    #[doc = "The fully qualified names of the `get_file_systems` endpoint's marker types."]
+    pub const GET_FILE_SYSTEMS_MARKERS: &[&str] = &["javax.annotation.Nonnull"];
     #[doc = "Returns a mapping from file system id to backing file system configuration."]
     pub async fn get_file_systems(
         &self,
@@ -18,6 +20,15 @@ where
         std::collections::BTreeMap<String, super::super::product::datasets::BackingFileSystem>,
         conjure_http::private::Error,
     > {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "getFileSystems",
+            conjure_http::private::http::Method::GET,
+            "/catalog/fileSystems",
+            &[],
+            false,
+        )
+        .with_markers(&["javax.annotation.Nonnull"]);
         let path_params_ = conjure_http::PathParams::new();
         let query_params_ = conjure_http::QueryParams::new();
         let mut headers_ = conjure_http::private::http::HeaderMap::new();
@@ -26,8 +37,7 @@ where
         let response_visitor_ = conjure_http::private::DefaultSerializableResponseVisitor::new();
         self.0
             .request(
-                conjure_http::private::http::Method::GET,
-                "/catalog/fileSystems",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -42,6 +52,24 @@ where
         request: &super::super::product::CreateDatasetRequest,
         test_header_arg: &str,
     ) -> Result<super::super::product::datasets::Dataset, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "createDataset",
+            conjure_http::private::http::Method::POST,
+            "/catalog/datasets",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] =
+                    &[conjure_http::server::Parameter::new(
+                        "testHeaderArg",
+                        conjure_http::server::ParameterType::Header(
+                            conjure_http::server::HeaderParameter::new("Test-Header"),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"])];
+                PARAMS
+            },
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let query_params_ = conjure_http::QueryParams::new();
         let mut headers_ = conjure_http::private::http::HeaderMap::new();
@@ -56,8 +84,7 @@ where
         let response_visitor_ = conjure_http::private::SerializableResponseVisitor::new();
         self.0
             .request(
-                conjure_http::private::http::Method::POST,
-                "/catalog/datasets",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -72,6 +99,24 @@ where
         dataset_rid: &conjure_object::ResourceIdentifier,
     ) -> Result<Option<super::super::product::datasets::Dataset>, conjure_http::private::Error>
     {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "getDataset",
+            conjure_http::private::http::Method::GET,
+            "/catalog/datasets/{datasetRid}",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] =
+                    &[conjure_http::server::Parameter::new(
+                        "datasetRid",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"])];
+                PARAMS
+            },
+            false,
+        );
         let mut path_params_ = conjure_http::PathParams::new();
         conjure_http::private::encode_path_param(&mut path_params_, "datasetRid", dataset_rid);
         let query_params_ = conjure_http::QueryParams::new();
@@ -81,8 +126,7 @@ where
         let response_visitor_ = conjure_http::private::DefaultSerializableResponseVisitor::new();
         self.0
             .request(
-                conjure_http::private::http::Method::GET,
-                "/catalog/datasets/{datasetRid}",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -96,6 +140,24 @@ where
         auth_: &conjure_object::BearerToken,
         dataset_rid: &conjure_object::ResourceIdentifier,
     ) -> Result<T::BinaryBody, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "getRawData",
+            conjure_http::private::http::Method::GET,
+            "/catalog/datasets/{datasetRid}/raw",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] =
+                    &[conjure_http::server::Parameter::new(
+                        "datasetRid",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"])];
+                PARAMS
+            },
+            false,
+        );
         let mut path_params_ = conjure_http::PathParams::new();
         conjure_http::private::encode_path_param(&mut path_params_, "datasetRid", dataset_rid);
         let query_params_ = conjure_http::QueryParams::new();
@@ -105,8 +167,7 @@ where
         let response_visitor_ = conjure_http::private::BinaryResponseVisitor;
         self.0
             .request(
-                conjure_http::private::http::Method::GET,
-                "/catalog/datasets/{datasetRid}/raw",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -120,6 +181,24 @@ where
         auth_: &conjure_object::BearerToken,
         dataset_rid: &conjure_object::ResourceIdentifier,
     ) -> Result<T::BinaryBody, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "getAliasedRawData",
+            conjure_http::private::http::Method::GET,
+            "/catalog/datasets/{datasetRid}/raw-aliased",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] =
+                    &[conjure_http::server::Parameter::new(
+                        "datasetRid",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"])];
+                PARAMS
+            },
+            false,
+        );
         let mut path_params_ = conjure_http::PathParams::new();
         conjure_http::private::encode_path_param(&mut path_params_, "datasetRid", dataset_rid);
         let query_params_ = conjure_http::QueryParams::new();
@@ -129,8 +208,7 @@ where
         let response_visitor_ = conjure_http::private::BinaryResponseVisitor;
         self.0
             .request(
-                conjure_http::private::http::Method::GET,
-                "/catalog/datasets/{datasetRid}/raw-aliased",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -144,6 +222,24 @@ where
         auth_: &conjure_object::BearerToken,
         dataset_rid: &conjure_object::ResourceIdentifier,
     ) -> Result<Option<T::BinaryBody>, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "maybeGetRawData",
+            conjure_http::private::http::Method::GET,
+            "/catalog/datasets/{datasetRid}/raw-maybe",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] =
+                    &[conjure_http::server::Parameter::new(
+                        "datasetRid",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe", "javax.annotation.Nonnull"])];
+                PARAMS
+            },
+            false,
+        );
         let mut path_params_ = conjure_http::PathParams::new();
         conjure_http::private::encode_path_param(&mut path_params_, "datasetRid", dataset_rid);
         let query_params_ = conjure_http::QueryParams::new();
@@ -153,8 +249,7 @@ where
         let response_visitor_ = conjure_http::private::OptionalBinaryResponseVisitor;
         self.0
             .request(
-                conjure_http::private::http::Method::GET,
-                "/catalog/datasets/{datasetRid}/raw-maybe",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -168,6 +263,24 @@ where
         auth_: &conjure_object::BearerToken,
         dataset_rid: &conjure_object::ResourceIdentifier,
     ) -> Result<super::super::product::AliasedString, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "getAliasedString",
+            conjure_http::private::http::Method::GET,
+            "/catalog/datasets/{datasetRid}/string-aliased",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] =
+                    &[conjure_http::server::Parameter::new(
+                        "datasetRid",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"])];
+                PARAMS
+            },
+            false,
+        );
         let mut path_params_ = conjure_http::PathParams::new();
         conjure_http::private::encode_path_param(&mut path_params_, "datasetRid", dataset_rid);
         let query_params_ = conjure_http::QueryParams::new();
@@ -177,8 +290,7 @@ where
         let response_visitor_ = conjure_http::private::SerializableResponseVisitor::new();
         self.0
             .request(
-                conjure_http::private::http::Method::GET,
-                "/catalog/datasets/{datasetRid}/string-aliased",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -195,6 +307,14 @@ where
     where
         U: conjure_http::client::AsyncWriteBody<T::BinaryWriter> + Sync + Send,
     {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "uploadRawData",
+            conjure_http::private::http::Method::POST,
+            "/catalog/datasets/upload-raw",
+            &[],
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let query_params_ = conjure_http::QueryParams::new();
         let mut headers_ = conjure_http::private::http::HeaderMap::new();
@@ -203,8 +323,7 @@ where
         let response_visitor_ = conjure_http::private::EmptyResponseVisitor;
         self.0
             .request(
-                conjure_http::private::http::Method::POST,
-                "/catalog/datasets/upload-raw",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -221,6 +340,14 @@ where
     where
         U: conjure_http::client::AsyncWriteBody<T::BinaryWriter> + Sync + Send,
     {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "uploadAliasedRawData",
+            conjure_http::private::http::Method::POST,
+            "/catalog/datasets/upload-raw-aliased",
+            &[],
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let query_params_ = conjure_http::QueryParams::new();
         let mut headers_ = conjure_http::private::http::HeaderMap::new();
@@ -229,8 +356,7 @@ where
         let response_visitor_ = conjure_http::private::EmptyResponseVisitor;
         self.0
             .request(
-                conjure_http::private::http::Method::POST,
-                "/catalog/datasets/upload-raw-aliased",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -244,6 +370,24 @@ where
         auth_: &conjure_object::BearerToken,
         dataset_rid: &conjure_object::ResourceIdentifier,
     ) -> Result<std::collections::BTreeSet<String>, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "getBranches",
+            conjure_http::private::http::Method::GET,
+            "/catalog/datasets/{datasetRid}/branches",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] =
+                    &[conjure_http::server::Parameter::new(
+                        "datasetRid",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"])];
+                PARAMS
+            },
+            false,
+        );
         let mut path_params_ = conjure_http::PathParams::new();
         conjure_http::private::encode_path_param(&mut path_params_, "datasetRid", dataset_rid);
         let query_params_ = conjure_http::QueryParams::new();
@@ -253,8 +397,7 @@ where
         let response_visitor_ = conjure_http::private::DefaultSerializableResponseVisitor::new();
         self.0
             .request(
-                conjure_http::private::http::Method::GET,
-                "/catalog/datasets/{datasetRid}/branches",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -270,6 +413,24 @@ where
         auth_: &conjure_object::BearerToken,
         dataset_rid: &conjure_object::ResourceIdentifier,
     ) -> Result<std::collections::BTreeSet<String>, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "getBranchesDeprecated",
+            conjure_http::private::http::Method::GET,
+            "/catalog/datasets/{datasetRid}/branchesDeprecated",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] =
+                    &[conjure_http::server::Parameter::new(
+                        "datasetRid",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"])];
+                PARAMS
+            },
+            true,
+        );
         let mut path_params_ = conjure_http::PathParams::new();
         conjure_http::private::encode_path_param(&mut path_params_, "datasetRid", dataset_rid);
         let query_params_ = conjure_http::QueryParams::new();
@@ -279,8 +440,7 @@ where
         let response_visitor_ = conjure_http::private::DefaultSerializableResponseVisitor::new();
         self.0
             .request(
-                conjure_http::private::http::Method::GET,
-                "/catalog/datasets/{datasetRid}/branchesDeprecated",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -295,6 +455,31 @@ where
         dataset_rid: &conjure_object::ResourceIdentifier,
         branch: &str,
     ) -> Result<Option<String>, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "resolveBranch",
+            conjure_http::private::http::Method::GET,
+            "/catalog/datasets/{datasetRid}/branches/{branch:.+}/resolve",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] = &[
+                    conjure_http::server::Parameter::new(
+                        "datasetRid",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"]),
+                    conjure_http::server::Parameter::new(
+                        "branch",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    ),
+                ];
+                PARAMS
+            },
+            false,
+        );
         let mut path_params_ = conjure_http::PathParams::new();
         conjure_http::private::encode_path_param(&mut path_params_, "datasetRid", dataset_rid);
         conjure_http::private::encode_path_param(&mut path_params_, "branch", branch);
@@ -305,8 +490,7 @@ where
         let response_visitor_ = conjure_http::private::DefaultSerializableResponseVisitor::new();
         self.0
             .request(
-                conjure_http::private::http::Method::GET,
-                "/catalog/datasets/{datasetRid}/branches/{branch:.+}/resolve",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -320,6 +504,24 @@ where
         auth_: &conjure_object::BearerToken,
         dataset_rid: &conjure_object::ResourceIdentifier,
     ) -> Result<Option<String>, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "testParam",
+            conjure_http::private::http::Method::GET,
+            "/catalog/datasets/{datasetRid}/testParam",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] =
+                    &[conjure_http::server::Parameter::new(
+                        "datasetRid",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"])];
+                PARAMS
+            },
+            false,
+        );
         let mut path_params_ = conjure_http::PathParams::new();
         conjure_http::private::encode_path_param(&mut path_params_, "datasetRid", dataset_rid);
         let query_params_ = conjure_http::QueryParams::new();
@@ -329,8 +531,7 @@ where
         let response_visitor_ = conjure_http::private::DefaultSerializableResponseVisitor::new();
         self.0
             .request(
-                conjure_http::private::http::Method::GET,
-                "/catalog/datasets/{datasetRid}/testParam",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -349,6 +550,49 @@ where
         set_end: &std::collections::BTreeSet<String>,
         optional_end: Option<&conjure_object::ResourceIdentifier>,
     ) -> Result<i32, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "testQueryParams",
+            conjure_http::private::http::Method::POST,
+            "/catalog/test-query-params",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] = &[
+                    conjure_http::server::Parameter::new(
+                        "something",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("different"),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"]),
+                    conjure_http::server::Parameter::new(
+                        "optionalMiddle",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("optionalMiddle"),
+                        ),
+                    ),
+                    conjure_http::server::Parameter::new(
+                        "implicit",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("implicit"),
+                        ),
+                    ),
+                    conjure_http::server::Parameter::new(
+                        "setEnd",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("setEnd"),
+                        ),
+                    ),
+                    conjure_http::server::Parameter::new(
+                        "optionalEnd",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("optionalEnd"),
+                        ),
+                    ),
+                ];
+                PARAMS
+            },
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let mut query_params_ = conjure_http::QueryParams::new();
         conjure_http::private::encode_query_param(&mut query_params_, "different", something);
@@ -370,8 +614,7 @@ where
         let response_visitor_ = conjure_http::private::SerializableResponseVisitor::new();
         self.0
             .request(
-                conjure_http::private::http::Method::POST,
-                "/catalog/test-query-params",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -390,6 +633,48 @@ where
         set_end: &std::collections::BTreeSet<String>,
         optional_end: Option<&conjure_object::ResourceIdentifier>,
     ) -> Result<(), conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "testNoResponseQueryParams",
+            conjure_http::private::http::Method::POST,
+            "/catalog/test-no-response-query-params",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] = &[
+                    conjure_http::server::Parameter::new(
+                        "something",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("different"),
+                        ),
+                    ),
+                    conjure_http::server::Parameter::new(
+                        "optionalMiddle",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("optionalMiddle"),
+                        ),
+                    ),
+                    conjure_http::server::Parameter::new(
+                        "implicit",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("implicit"),
+                        ),
+                    ),
+                    conjure_http::server::Parameter::new(
+                        "setEnd",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("setEnd"),
+                        ),
+                    ),
+                    conjure_http::server::Parameter::new(
+                        "optionalEnd",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("optionalEnd"),
+                        ),
+                    ),
+                ];
+                PARAMS
+            },
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let mut query_params_ = conjure_http::QueryParams::new();
         conjure_http::private::encode_query_param(&mut query_params_, "different", something);
@@ -411,8 +696,7 @@ where
         let response_visitor_ = conjure_http::private::EmptyResponseVisitor;
         self.0
             .request(
-                conjure_http::private::http::Method::POST,
-                "/catalog/test-no-response-query-params",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -425,6 +709,14 @@ where
         &self,
         auth_: &conjure_object::BearerToken,
     ) -> Result<bool, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "testBoolean",
+            conjure_http::private::http::Method::GET,
+            "/catalog/boolean",
+            &[],
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let query_params_ = conjure_http::QueryParams::new();
         let mut headers_ = conjure_http::private::http::HeaderMap::new();
@@ -433,8 +725,7 @@ where
         let response_visitor_ = conjure_http::private::SerializableResponseVisitor::new();
         self.0
             .request(
-                conjure_http::private::http::Method::GET,
-                "/catalog/boolean",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -447,6 +738,14 @@ where
         &self,
         auth_: &conjure_object::BearerToken,
     ) -> Result<f64, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "testDouble",
+            conjure_http::private::http::Method::GET,
+            "/catalog/double",
+            &[],
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let query_params_ = conjure_http::QueryParams::new();
         let mut headers_ = conjure_http::private::http::HeaderMap::new();
@@ -455,8 +754,7 @@ where
         let response_visitor_ = conjure_http::private::SerializableResponseVisitor::new();
         self.0
             .request(
-                conjure_http::private::http::Method::GET,
-                "/catalog/double",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -469,6 +767,14 @@ where
         &self,
         auth_: &conjure_object::BearerToken,
     ) -> Result<i32, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "testInteger",
+            conjure_http::private::http::Method::GET,
+            "/catalog/integer",
+            &[],
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let query_params_ = conjure_http::QueryParams::new();
         let mut headers_ = conjure_http::private::http::HeaderMap::new();
@@ -477,8 +783,7 @@ where
         let response_visitor_ = conjure_http::private::SerializableResponseVisitor::new();
         self.0
             .request(
-                conjure_http::private::http::Method::GET,
-                "/catalog/integer",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -492,6 +797,14 @@ where
         auth_: &conjure_object::BearerToken,
         maybe_string: Option<&str>,
     ) -> Result<Option<String>, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "testPostOptional",
+            conjure_http::private::http::Method::POST,
+            "/catalog/optional",
+            &[],
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let query_params_ = conjure_http::QueryParams::new();
         let mut headers_ = conjure_http::private::http::HeaderMap::new();
@@ -500,8 +813,7 @@ where
         let response_visitor_ = conjure_http::private::DefaultSerializableResponseVisitor::new();
         self.0
             .request(
-                conjure_http::private::http::Method::POST,
-                "/catalog/optional",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -516,6 +828,30 @@ where
         maybe_integer: Option<i32>,
         maybe_double: Option<f64>,
     ) -> Result<(), conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "testOptionalIntegerAndDouble",
+            conjure_http::private::http::Method::GET,
+            "/catalog/optional-integer-double",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] = &[
+                    conjure_http::server::Parameter::new(
+                        "maybeInteger",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("maybeInteger"),
+                        ),
+                    ),
+                    conjure_http::server::Parameter::new(
+                        "maybeDouble",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("maybeDouble"),
+                        ),
+                    ),
+                ];
+                PARAMS
+            },
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let mut query_params_ = conjure_http::QueryParams::new();
         conjure_http::private::encode_optional_query_param(
@@ -534,8 +870,7 @@ where
         let response_visitor_ = conjure_http::private::EmptyResponseVisitor;
         self.0
             .request(
-                conjure_http::private::http::Method::GET,
-                "/catalog/optional-integer-double",
+                &ENDPOINT,
                 path_params_,
                 query_params_,
                 headers_,
@@ -557,6 +892,8 @@ where
     pub fn new(client: T) -> TestServiceClient<T> {
         TestServiceClient(client)
     }
+    #[doc = "The fully qualified names of the `get_file_systems` endpoint's marker types."]
+    pub const GET_FILE_SYSTEMS_MARKERS: &[&str] = &["javax.annotation.Nonnull"];
     #[doc = "Returns a mapping from file system id to backing file system configuration."]
     pub fn get_file_systems(
         &self,
@@ -565,6 +902,15 @@ where
         std::collections::BTreeMap<String, super::super::product::datasets::BackingFileSystem>,
         conjure_http::private::Error,
     > {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "getFileSystems",
+            conjure_http::private::http::Method::GET,
+            "/catalog/fileSystems",
+            &[],
+            false,
+        )
+        .with_markers(&["javax.annotation.Nonnull"]);
         let path_params_ = conjure_http::PathParams::new();
         let query_params_ = conjure_http::QueryParams::new();
         let mut headers_ = conjure_http::private::http::HeaderMap::new();
@@ -572,8 +918,7 @@ where
         let body_ = conjure_http::private::EmptyRequestBody;
         let response_visitor_ = conjure_http::private::DefaultSerializableResponseVisitor::new();
         self.0.request(
-            conjure_http::private::http::Method::GET,
-            "/catalog/fileSystems",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -587,6 +932,24 @@ where
         request: &super::super::product::CreateDatasetRequest,
         test_header_arg: &str,
     ) -> Result<super::super::product::datasets::Dataset, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "createDataset",
+            conjure_http::private::http::Method::POST,
+            "/catalog/datasets",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] =
+                    &[conjure_http::server::Parameter::new(
+                        "testHeaderArg",
+                        conjure_http::server::ParameterType::Header(
+                            conjure_http::server::HeaderParameter::new("Test-Header"),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"])];
+                PARAMS
+            },
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let query_params_ = conjure_http::QueryParams::new();
         let mut headers_ = conjure_http::private::http::HeaderMap::new();
@@ -600,8 +963,7 @@ where
         let body_ = conjure_http::private::SerializableRequestBody(request);
         let response_visitor_ = conjure_http::private::SerializableResponseVisitor::new();
         self.0.request(
-            conjure_http::private::http::Method::POST,
-            "/catalog/datasets",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -615,6 +977,24 @@ where
         dataset_rid: &conjure_object::ResourceIdentifier,
     ) -> Result<Option<super::super::product::datasets::Dataset>, conjure_http::private::Error>
     {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "getDataset",
+            conjure_http::private::http::Method::GET,
+            "/catalog/datasets/{datasetRid}",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] =
+                    &[conjure_http::server::Parameter::new(
+                        "datasetRid",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"])];
+                PARAMS
+            },
+            false,
+        );
         let mut path_params_ = conjure_http::PathParams::new();
         conjure_http::private::encode_path_param(&mut path_params_, "datasetRid", dataset_rid);
         let query_params_ = conjure_http::QueryParams::new();
@@ -623,8 +1003,7 @@ where
         let body_ = conjure_http::private::EmptyRequestBody;
         let response_visitor_ = conjure_http::private::DefaultSerializableResponseVisitor::new();
         self.0.request(
-            conjure_http::private::http::Method::GET,
-            "/catalog/datasets/{datasetRid}",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -637,6 +1016,24 @@ where
         auth_: &conjure_object::BearerToken,
         dataset_rid: &conjure_object::ResourceIdentifier,
     ) -> Result<T::BinaryBody, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "getRawData",
+            conjure_http::private::http::Method::GET,
+            "/catalog/datasets/{datasetRid}/raw",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] =
+                    &[conjure_http::server::Parameter::new(
+                        "datasetRid",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"])];
+                PARAMS
+            },
+            false,
+        );
         let mut path_params_ = conjure_http::PathParams::new();
         conjure_http::private::encode_path_param(&mut path_params_, "datasetRid", dataset_rid);
         let query_params_ = conjure_http::QueryParams::new();
@@ -645,8 +1042,7 @@ where
         let body_ = conjure_http::private::EmptyRequestBody;
         let response_visitor_ = conjure_http::private::BinaryResponseVisitor;
         self.0.request(
-            conjure_http::private::http::Method::GET,
-            "/catalog/datasets/{datasetRid}/raw",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -659,6 +1055,24 @@ where
         auth_: &conjure_object::BearerToken,
         dataset_rid: &conjure_object::ResourceIdentifier,
     ) -> Result<T::BinaryBody, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "getAliasedRawData",
+            conjure_http::private::http::Method::GET,
+            "/catalog/datasets/{datasetRid}/raw-aliased",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] =
+                    &[conjure_http::server::Parameter::new(
+                        "datasetRid",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"])];
+                PARAMS
+            },
+            false,
+        );
         let mut path_params_ = conjure_http::PathParams::new();
         conjure_http::private::encode_path_param(&mut path_params_, "datasetRid", dataset_rid);
         let query_params_ = conjure_http::QueryParams::new();
@@ -667,8 +1081,7 @@ where
         let body_ = conjure_http::private::EmptyRequestBody;
         let response_visitor_ = conjure_http::private::BinaryResponseVisitor;
         self.0.request(
-            conjure_http::private::http::Method::GET,
-            "/catalog/datasets/{datasetRid}/raw-aliased",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -681,6 +1094,24 @@ where
         auth_: &conjure_object::BearerToken,
         dataset_rid: &conjure_object::ResourceIdentifier,
     ) -> Result<Option<T::BinaryBody>, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "maybeGetRawData",
+            conjure_http::private::http::Method::GET,
+            "/catalog/datasets/{datasetRid}/raw-maybe",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] =
+                    &[conjure_http::server::Parameter::new(
+                        "datasetRid",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe", "javax.annotation.Nonnull"])];
+                PARAMS
+            },
+            false,
+        );
         let mut path_params_ = conjure_http::PathParams::new();
         conjure_http::private::encode_path_param(&mut path_params_, "datasetRid", dataset_rid);
         let query_params_ = conjure_http::QueryParams::new();
@@ -689,8 +1120,7 @@ where
         let body_ = conjure_http::private::EmptyRequestBody;
         let response_visitor_ = conjure_http::private::OptionalBinaryResponseVisitor;
         self.0.request(
-            conjure_http::private::http::Method::GET,
-            "/catalog/datasets/{datasetRid}/raw-maybe",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -703,6 +1133,24 @@ where
         auth_: &conjure_object::BearerToken,
         dataset_rid: &conjure_object::ResourceIdentifier,
     ) -> Result<super::super::product::AliasedString, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "getAliasedString",
+            conjure_http::private::http::Method::GET,
+            "/catalog/datasets/{datasetRid}/string-aliased",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] =
+                    &[conjure_http::server::Parameter::new(
+                        "datasetRid",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"])];
+                PARAMS
+            },
+            false,
+        );
         let mut path_params_ = conjure_http::PathParams::new();
         conjure_http::private::encode_path_param(&mut path_params_, "datasetRid", dataset_rid);
         let query_params_ = conjure_http::QueryParams::new();
@@ -711,8 +1159,7 @@ where
         let body_ = conjure_http::private::EmptyRequestBody;
         let response_visitor_ = conjure_http::private::SerializableResponseVisitor::new();
         self.0.request(
-            conjure_http::private::http::Method::GET,
-            "/catalog/datasets/{datasetRid}/string-aliased",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -728,6 +1175,14 @@ where
     where
         U: conjure_http::client::WriteBody<T::BinaryWriter>,
     {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "uploadRawData",
+            conjure_http::private::http::Method::POST,
+            "/catalog/datasets/upload-raw",
+            &[],
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let query_params_ = conjure_http::QueryParams::new();
         let mut headers_ = conjure_http::private::http::HeaderMap::new();
@@ -735,8 +1190,7 @@ where
         let body_ = conjure_http::private::BinaryRequestBody(input);
         let response_visitor_ = conjure_http::private::EmptyResponseVisitor;
         self.0.request(
-            conjure_http::private::http::Method::POST,
-            "/catalog/datasets/upload-raw",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -752,6 +1206,14 @@ where
     where
         U: conjure_http::client::WriteBody<T::BinaryWriter>,
     {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "uploadAliasedRawData",
+            conjure_http::private::http::Method::POST,
+            "/catalog/datasets/upload-raw-aliased",
+            &[],
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let query_params_ = conjure_http::QueryParams::new();
         let mut headers_ = conjure_http::private::http::HeaderMap::new();
@@ -759,8 +1221,7 @@ where
         let body_ = conjure_http::private::BinaryRequestBody(input);
         let response_visitor_ = conjure_http::private::EmptyResponseVisitor;
         self.0.request(
-            conjure_http::private::http::Method::POST,
-            "/catalog/datasets/upload-raw-aliased",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -773,6 +1234,24 @@ where
         auth_: &conjure_object::BearerToken,
         dataset_rid: &conjure_object::ResourceIdentifier,
     ) -> Result<std::collections::BTreeSet<String>, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "getBranches",
+            conjure_http::private::http::Method::GET,
+            "/catalog/datasets/{datasetRid}/branches",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] =
+                    &[conjure_http::server::Parameter::new(
+                        "datasetRid",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"])];
+                PARAMS
+            },
+            false,
+        );
         let mut path_params_ = conjure_http::PathParams::new();
         conjure_http::private::encode_path_param(&mut path_params_, "datasetRid", dataset_rid);
         let query_params_ = conjure_http::QueryParams::new();
@@ -781,8 +1260,7 @@ where
         let body_ = conjure_http::private::EmptyRequestBody;
         let response_visitor_ = conjure_http::private::DefaultSerializableResponseVisitor::new();
         self.0.request(
-            conjure_http::private::http::Method::GET,
-            "/catalog/datasets/{datasetRid}/branches",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -797,6 +1275,24 @@ where
         auth_: &conjure_object::BearerToken,
         dataset_rid: &conjure_object::ResourceIdentifier,
     ) -> Result<std::collections::BTreeSet<String>, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "getBranchesDeprecated",
+            conjure_http::private::http::Method::GET,
+            "/catalog/datasets/{datasetRid}/branchesDeprecated",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] =
+                    &[conjure_http::server::Parameter::new(
+                        "datasetRid",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"])];
+                PARAMS
+            },
+            true,
+        );
         let mut path_params_ = conjure_http::PathParams::new();
         conjure_http::private::encode_path_param(&mut path_params_, "datasetRid", dataset_rid);
         let query_params_ = conjure_http::QueryParams::new();
@@ -805,8 +1301,7 @@ where
         let body_ = conjure_http::private::EmptyRequestBody;
         let response_visitor_ = conjure_http::private::DefaultSerializableResponseVisitor::new();
         self.0.request(
-            conjure_http::private::http::Method::GET,
-            "/catalog/datasets/{datasetRid}/branchesDeprecated",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -820,6 +1315,31 @@ where
         dataset_rid: &conjure_object::ResourceIdentifier,
         branch: &str,
     ) -> Result<Option<String>, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "resolveBranch",
+            conjure_http::private::http::Method::GET,
+            "/catalog/datasets/{datasetRid}/branches/{branch:.+}/resolve",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] = &[
+                    conjure_http::server::Parameter::new(
+                        "datasetRid",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"]),
+                    conjure_http::server::Parameter::new(
+                        "branch",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    ),
+                ];
+                PARAMS
+            },
+            false,
+        );
         let mut path_params_ = conjure_http::PathParams::new();
         conjure_http::private::encode_path_param(&mut path_params_, "datasetRid", dataset_rid);
         conjure_http::private::encode_path_param(&mut path_params_, "branch", branch);
@@ -829,8 +1349,7 @@ where
         let body_ = conjure_http::private::EmptyRequestBody;
         let response_visitor_ = conjure_http::private::DefaultSerializableResponseVisitor::new();
         self.0.request(
-            conjure_http::private::http::Method::GET,
-            "/catalog/datasets/{datasetRid}/branches/{branch:.+}/resolve",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -843,6 +1362,24 @@ where
         auth_: &conjure_object::BearerToken,
         dataset_rid: &conjure_object::ResourceIdentifier,
     ) -> Result<Option<String>, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "testParam",
+            conjure_http::private::http::Method::GET,
+            "/catalog/datasets/{datasetRid}/testParam",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] =
+                    &[conjure_http::server::Parameter::new(
+                        "datasetRid",
+                        conjure_http::server::ParameterType::Path(
+                            conjure_http::server::PathParameter::new(),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"])];
+                PARAMS
+            },
+            false,
+        );
         let mut path_params_ = conjure_http::PathParams::new();
         conjure_http::private::encode_path_param(&mut path_params_, "datasetRid", dataset_rid);
         let query_params_ = conjure_http::QueryParams::new();
@@ -851,8 +1388,7 @@ where
         let body_ = conjure_http::private::EmptyRequestBody;
         let response_visitor_ = conjure_http::private::DefaultSerializableResponseVisitor::new();
         self.0.request(
-            conjure_http::private::http::Method::GET,
-            "/catalog/datasets/{datasetRid}/testParam",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -870,6 +1406,49 @@ where
         set_end: &std::collections::BTreeSet<String>,
         optional_end: Option<&conjure_object::ResourceIdentifier>,
     ) -> Result<i32, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "testQueryParams",
+            conjure_http::private::http::Method::POST,
+            "/catalog/test-query-params",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] = &[
+                    conjure_http::server::Parameter::new(
+                        "something",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("different"),
+                        ),
+                    )
+                    .with_markers(&["com.palantir.redaction.Safe"]),
+                    conjure_http::server::Parameter::new(
+                        "optionalMiddle",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("optionalMiddle"),
+                        ),
+                    ),
+                    conjure_http::server::Parameter::new(
+                        "implicit",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("implicit"),
+                        ),
+                    ),
+                    conjure_http::server::Parameter::new(
+                        "setEnd",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("setEnd"),
+                        ),
+                    ),
+                    conjure_http::server::Parameter::new(
+                        "optionalEnd",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("optionalEnd"),
+                        ),
+                    ),
+                ];
+                PARAMS
+            },
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let mut query_params_ = conjure_http::QueryParams::new();
         conjure_http::private::encode_query_param(&mut query_params_, "different", something);
@@ -890,8 +1469,7 @@ where
         let body_ = conjure_http::private::SerializableRequestBody(query);
         let response_visitor_ = conjure_http::private::SerializableResponseVisitor::new();
         self.0.request(
-            conjure_http::private::http::Method::POST,
-            "/catalog/test-query-params",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -909,6 +1487,48 @@ where
         set_end: &std::collections::BTreeSet<String>,
         optional_end: Option<&conjure_object::ResourceIdentifier>,
     ) -> Result<(), conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "testNoResponseQueryParams",
+            conjure_http::private::http::Method::POST,
+            "/catalog/test-no-response-query-params",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] = &[
+                    conjure_http::server::Parameter::new(
+                        "something",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("different"),
+                        ),
+                    ),
+                    conjure_http::server::Parameter::new(
+                        "optionalMiddle",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("optionalMiddle"),
+                        ),
+                    ),
+                    conjure_http::server::Parameter::new(
+                        "implicit",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("implicit"),
+                        ),
+                    ),
+                    conjure_http::server::Parameter::new(
+                        "setEnd",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("setEnd"),
+                        ),
+                    ),
+                    conjure_http::server::Parameter::new(
+                        "optionalEnd",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("optionalEnd"),
+                        ),
+                    ),
+                ];
+                PARAMS
+            },
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let mut query_params_ = conjure_http::QueryParams::new();
         conjure_http::private::encode_query_param(&mut query_params_, "different", something);
@@ -929,8 +1549,7 @@ where
         let body_ = conjure_http::private::SerializableRequestBody(query);
         let response_visitor_ = conjure_http::private::EmptyResponseVisitor;
         self.0.request(
-            conjure_http::private::http::Method::POST,
-            "/catalog/test-no-response-query-params",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -942,6 +1561,14 @@ where
         &self,
         auth_: &conjure_object::BearerToken,
     ) -> Result<bool, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "testBoolean",
+            conjure_http::private::http::Method::GET,
+            "/catalog/boolean",
+            &[],
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let query_params_ = conjure_http::QueryParams::new();
         let mut headers_ = conjure_http::private::http::HeaderMap::new();
@@ -949,8 +1576,7 @@ where
         let body_ = conjure_http::private::EmptyRequestBody;
         let response_visitor_ = conjure_http::private::SerializableResponseVisitor::new();
         self.0.request(
-            conjure_http::private::http::Method::GET,
-            "/catalog/boolean",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -962,6 +1588,14 @@ where
         &self,
         auth_: &conjure_object::BearerToken,
     ) -> Result<f64, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "testDouble",
+            conjure_http::private::http::Method::GET,
+            "/catalog/double",
+            &[],
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let query_params_ = conjure_http::QueryParams::new();
         let mut headers_ = conjure_http::private::http::HeaderMap::new();
@@ -969,8 +1603,7 @@ where
         let body_ = conjure_http::private::EmptyRequestBody;
         let response_visitor_ = conjure_http::private::SerializableResponseVisitor::new();
         self.0.request(
-            conjure_http::private::http::Method::GET,
-            "/catalog/double",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -982,6 +1615,14 @@ where
         &self,
         auth_: &conjure_object::BearerToken,
     ) -> Result<i32, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "testInteger",
+            conjure_http::private::http::Method::GET,
+            "/catalog/integer",
+            &[],
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let query_params_ = conjure_http::QueryParams::new();
         let mut headers_ = conjure_http::private::http::HeaderMap::new();
@@ -989,8 +1630,7 @@ where
         let body_ = conjure_http::private::EmptyRequestBody;
         let response_visitor_ = conjure_http::private::SerializableResponseVisitor::new();
         self.0.request(
-            conjure_http::private::http::Method::GET,
-            "/catalog/integer",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -1003,6 +1643,14 @@ where
         auth_: &conjure_object::BearerToken,
         maybe_string: Option<&str>,
     ) -> Result<Option<String>, conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "testPostOptional",
+            conjure_http::private::http::Method::POST,
+            "/catalog/optional",
+            &[],
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let query_params_ = conjure_http::QueryParams::new();
         let mut headers_ = conjure_http::private::http::HeaderMap::new();
@@ -1010,8 +1658,7 @@ where
         let body_ = conjure_http::private::SerializableRequestBody(maybe_string);
         let response_visitor_ = conjure_http::private::DefaultSerializableResponseVisitor::new();
         self.0.request(
-            conjure_http::private::http::Method::POST,
-            "/catalog/optional",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -1025,6 +1672,30 @@ where
         maybe_integer: Option<i32>,
         maybe_double: Option<f64>,
     ) -> Result<(), conjure_http::private::Error> {
+        static ENDPOINT: conjure_http::client::Endpoint = conjure_http::client::Endpoint::new(
+            "TestService",
+            "testOptionalIntegerAndDouble",
+            conjure_http::private::http::Method::GET,
+            "/catalog/optional-integer-double",
+            {
+                const PARAMS: &[conjure_http::server::Parameter] = &[
+                    conjure_http::server::Parameter::new(
+                        "maybeInteger",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("maybeInteger"),
+                        ),
+                    ),
+                    conjure_http::server::Parameter::new(
+                        "maybeDouble",
+                        conjure_http::server::ParameterType::Query(
+                            conjure_http::server::QueryParameter::new("maybeDouble"),
+                        ),
+                    ),
+                ];
+                PARAMS
+            },
+            false,
+        );
         let path_params_ = conjure_http::PathParams::new();
         let mut query_params_ = conjure_http::QueryParams::new();
         conjure_http::private::encode_optional_query_param(
@@ -1042,8 +1713,7 @@ where
         let body_ = conjure_http::private::EmptyRequestBody;
         let response_visitor_ = conjure_http::private::EmptyResponseVisitor;
         self.0.request(
-            conjure_http::private::http::Method::GET,
-            "/catalog/optional-integer-double",
+            &ENDPOINT,
             path_params_,
             query_params_,
             headers_,
@@ -2093,7 +2763,8 @@ where
                     "/catalog/fileSystems",
                     &[],
                     false,
-                ),
+                )
+                .with_markers(&["javax.annotation.Nonnull"]),
                 handler: &GetFileSystemsHandler_,
             },
             conjure_http::server::Endpoint {
@@ -2108,7 +2779,8 @@ where
                                 conjure_http::server::ParameterType::Header(
                                     conjure_http::server::HeaderParameter::new("Test-Header"),
                                 ),
-                            )];
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"])];
                         PARAMS
                     },
                     false,
@@ -2127,7 +2799,8 @@ where
                                 conjure_http::server::ParameterType::Path(
                                     conjure_http::server::PathParameter::new(),
                                 ),
-                            )];
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"])];
                         PARAMS
                     },
                     false,
@@ -2146,7 +2819,8 @@ where
                                 conjure_http::server::ParameterType::Path(
                                     conjure_http::server::PathParameter::new(),
                                 ),
-                            )];
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"])];
                         PARAMS
                     },
                     false,
@@ -2165,7 +2839,8 @@ where
                                 conjure_http::server::ParameterType::Path(
                                     conjure_http::server::PathParameter::new(),
                                 ),
-                            )];
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"])];
                         PARAMS
                     },
                     false,
@@ -2184,7 +2859,11 @@ where
                                 conjure_http::server::ParameterType::Path(
                                     conjure_http::server::PathParameter::new(),
                                 ),
-                            )];
+                            )
+                            .with_markers(&[
+                                "com.palantir.redaction.Safe",
+                                "javax.annotation.Nonnull",
+                            ])];
                         PARAMS
                     },
                     false,
@@ -2203,7 +2882,8 @@ where
                                 conjure_http::server::ParameterType::Path(
                                     conjure_http::server::PathParameter::new(),
                                 ),
-                            )];
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"])];
                         PARAMS
                     },
                     false,
@@ -2242,7 +2922,8 @@ where
                                 conjure_http::server::ParameterType::Path(
                                     conjure_http::server::PathParameter::new(),
                                 ),
-                            )];
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"])];
                         PARAMS
                     },
                     false,
@@ -2261,7 +2942,8 @@ where
                                 conjure_http::server::ParameterType::Path(
                                     conjure_http::server::PathParameter::new(),
                                 ),
-                            )];
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"])];
                         PARAMS
                     },
                     true,
@@ -2280,7 +2962,8 @@ where
                                 conjure_http::server::ParameterType::Path(
                                     conjure_http::server::PathParameter::new(),
                                 ),
-                            ),
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"]),
                             conjure_http::server::Parameter::new(
                                 "branch",
                                 conjure_http::server::ParameterType::Path(
@@ -2306,7 +2989,8 @@ where
                                 conjure_http::server::ParameterType::Path(
                                     conjure_http::server::PathParameter::new(),
                                 ),
-                            )];
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"])];
                         PARAMS
                     },
                     false,
@@ -2325,7 +3009,8 @@ where
                                 conjure_http::server::ParameterType::Query(
                                     conjure_http::server::QueryParameter::new("different"),
                                 ),
-                            ),
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"]),
                             conjure_http::server::Parameter::new(
                                 "optionalMiddle",
                                 conjure_http::server::ParameterType::Query(
@@ -3375,7 +4060,8 @@ where
                     "/catalog/fileSystems",
                     &[],
                     false,
-                ),
+                )
+                .with_markers(&["javax.annotation.Nonnull"]),
                 handler: &GetFileSystemsHandlerAsync_,
             },
             conjure_http::server::AsyncEndpoint {
@@ -3390,7 +4076,8 @@ where
                                 conjure_http::server::ParameterType::Header(
                                     conjure_http::server::HeaderParameter::new("Test-Header"),
                                 ),
-                            )];
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"])];
                         PARAMS
                     },
                     false,
@@ -3409,7 +4096,8 @@ where
                                 conjure_http::server::ParameterType::Path(
                                     conjure_http::server::PathParameter::new(),
                                 ),
-                            )];
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"])];
                         PARAMS
                     },
                     false,
@@ -3428,7 +4116,8 @@ where
                                 conjure_http::server::ParameterType::Path(
                                     conjure_http::server::PathParameter::new(),
                                 ),
-                            )];
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"])];
                         PARAMS
                     },
                     false,
@@ -3447,7 +4136,8 @@ where
                                 conjure_http::server::ParameterType::Path(
                                     conjure_http::server::PathParameter::new(),
                                 ),
-                            )];
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"])];
                         PARAMS
                     },
                     false,
@@ -3466,7 +4156,11 @@ where
                                 conjure_http::server::ParameterType::Path(
                                     conjure_http::server::PathParameter::new(),
                                 ),
-                            )];
+                            )
+                            .with_markers(&[
+                                "com.palantir.redaction.Safe",
+                                "javax.annotation.Nonnull",
+                            ])];
                         PARAMS
                     },
                     false,
@@ -3485,7 +4179,8 @@ where
                                 conjure_http::server::ParameterType::Path(
                                     conjure_http::server::PathParameter::new(),
                                 ),
-                            )];
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"])];
                         PARAMS
                     },
                     false,
@@ -3524,7 +4219,8 @@ where
                                 conjure_http::server::ParameterType::Path(
                                     conjure_http::server::PathParameter::new(),
                                 ),
-                            )];
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"])];
                         PARAMS
                     },
                     false,
@@ -3543,7 +4239,8 @@ where
                                 conjure_http::server::ParameterType::Path(
                                     conjure_http::server::PathParameter::new(),
                                 ),
-                            )];
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"])];
                         PARAMS
                     },
                     true,
@@ -3562,7 +4259,8 @@ where
                                 conjure_http::server::ParameterType::Path(
                                     conjure_http::server::PathParameter::new(),
                                 ),
-                            ),
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"]),
                             conjure_http::server::Parameter::new(
                                 "branch",
                                 conjure_http::server::ParameterType::Path(
@@ -3588,7 +4286,8 @@ where
                                 conjure_http::server::ParameterType::Path(
                                     conjure_http::server::PathParameter::new(),
                                 ),
-                            )];
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"])];
                         PARAMS
                     },
                     false,
@@ -3607,7 +4306,8 @@ where
                                 conjure_http::server::ParameterType::Query(
                                     conjure_http::server::QueryParameter::new("different"),
                                 ),
-                            ),
+                            )
+                            .with_markers(&["com.palantir.redaction.Safe"]),
                             conjure_http::server::Parameter::new(
                                 "optionalMiddle",
                                 conjure_http::server::ParameterType::Query(
@@ -53,6 +53,21 @@ impl Builder {
                 .expect("field bearer_token_value was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<BearerTokenExample, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.bearer_token_value.is_none() {
+            missing_fields.push("bearer_token_value");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "BearerTokenExample",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<BearerTokenExample> for Builder {
     #[inline]
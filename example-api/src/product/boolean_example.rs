@@ -45,6 +45,21 @@ impl Builder {
             coin: self.coin.clone().expect("field coin was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<BooleanExample, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.coin.is_none() {
+            missing_fields.push("coin");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "BooleanExample",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<BooleanExample> for Builder {
     #[inline]
@@ -73,6 +73,24 @@ impl Builder {
             path: self.path.clone().expect("field path was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<CreateDatasetRequest, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.file_system_id.is_none() {
+            missing_fields.push("file_system_id");
+        }
+        if self.path.is_none() {
+            missing_fields.push("path");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "CreateDatasetRequest",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<CreateDatasetRequest> for Builder {
     #[inline]
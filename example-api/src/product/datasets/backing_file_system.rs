@@ -106,6 +106,24 @@ impl Builder {
             configuration: self.configuration.clone(),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<BackingFileSystem, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.file_system_id.is_none() {
+            missing_fields.push("file_system_id");
+        }
+        if self.base_uri.is_none() {
+            missing_fields.push("base_uri");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "BackingFileSystem",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<BackingFileSystem> for Builder {
     #[inline]
@@ -48,6 +48,21 @@ impl Builder {
             datetime: self.datetime.clone().expect("field datetime was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<DateTimeExample, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.datetime.is_none() {
+            missing_fields.push("datetime");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "DateTimeExample",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<DateTimeExample> for Builder {
     #[inline]
@@ -45,6 +45,21 @@ impl Builder {
             enum_: self.enum_.clone().expect("field enum_ was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<EnumFieldExample, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.enum_.is_none() {
+            missing_fields.push("enum_");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "EnumFieldExample",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<EnumFieldExample> for Builder {
     #[inline]
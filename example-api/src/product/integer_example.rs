@@ -45,6 +45,21 @@ impl Builder {
             integer: self.integer.clone().expect("field integer was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<IntegerExample, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.integer.is_none() {
+            missing_fields.push("integer");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "IntegerExample",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<IntegerExample> for Builder {
     #[inline]
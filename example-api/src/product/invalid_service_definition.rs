@@ -82,6 +82,24 @@ impl Builder {
                 .expect("field service_def was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<InvalidServiceDefinition, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.service_name.is_none() {
+            missing_fields.push("service_name");
+        }
+        if self.service_def.is_none() {
+            missing_fields.push("service_def");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "InvalidServiceDefinition",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<InvalidServiceDefinition> for Builder {
     #[inline]
@@ -200,3 +218,11 @@ impl conjure_error::ErrorType for InvalidServiceDefinition {
         &["serviceName"]
     }
 }
+impl conjure_error::FromSerializableError for InvalidServiceDefinition {
+    fn from_serializable_error(
+        error: &conjure_error::SerializableError,
+    ) -> Option<InvalidServiceDefinition> {
+        let _ = error;
+        None
+    }
+}
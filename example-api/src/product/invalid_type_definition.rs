@@ -72,6 +72,24 @@ impl Builder {
             type_def: self.type_def.clone().expect("field type_def was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<InvalidTypeDefinition, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.type_name.is_none() {
+            missing_fields.push("type_name");
+        }
+        if self.type_def.is_none() {
+            missing_fields.push("type_def");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "InvalidTypeDefinition",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<InvalidTypeDefinition> for Builder {
     #[inline]
@@ -186,3 +204,11 @@ impl conjure_error::ErrorType for InvalidTypeDefinition {
         &["typeName"]
     }
 }
+impl conjure_error::FromSerializableError for InvalidTypeDefinition {
+    fn from_serializable_error(
+        error: &conjure_error::SerializableError,
+    ) -> Option<InvalidTypeDefinition> {
+        let _ = error;
+        None
+    }
+}
@@ -118,6 +118,11 @@ impl Builder {
             double_items: self.double_items.clone(),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<ListExample, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<ListExample> for Builder {
     #[inline]
@@ -50,6 +50,11 @@ impl Builder {
             item: self.item.clone(),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<OptionalExample, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<OptionalExample> for Builder {
     #[inline]
@@ -124,6 +124,11 @@ impl Builder {
             uuid: self.uuid.clone(),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<PrimitiveOptionalsExample, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<PrimitiveOptionalsExample> for Builder {
     #[inline]
@@ -114,6 +114,33 @@ impl Builder {
                 .expect("field memoized_hash_code was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<ReservedKeyExample, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.package.is_none() {
+            missing_fields.push("package");
+        }
+        if self.interface.is_none() {
+            missing_fields.push("interface");
+        }
+        if self.field_name_with_dashes.is_none() {
+            missing_fields.push("field_name_with_dashes");
+        }
+        if self.primitve_field_name_with_dashes.is_none() {
+            missing_fields.push("primitve_field_name_with_dashes");
+        }
+        if self.memoized_hash_code.is_none() {
+            missing_fields.push("memoized_hash_code");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "ReservedKeyExample",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<ReservedKeyExample> for Builder {
     #[inline]
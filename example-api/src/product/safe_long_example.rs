@@ -50,6 +50,21 @@ impl Builder {
                 .expect("field safe_long_value was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<SafeLongExample, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.safe_long_value.is_none() {
+            missing_fields.push("safe_long_value");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "SafeLongExample",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<SafeLongExample> for Builder {
     #[inline]
@@ -64,6 +64,11 @@ impl Builder {
             items: self.items.clone(),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<SetExample, conjure_object::BuildError> {
+        Ok(self.build())
+    }
 }
 impl From<SetExample> for Builder {
     #[inline]
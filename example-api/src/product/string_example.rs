@@ -52,6 +52,21 @@ impl Builder {
             string: self.string.clone().expect("field string was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<StringExample, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.string.is_none() {
+            missing_fields.push("string");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "StringExample",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<StringExample> for Builder {
     #[inline]
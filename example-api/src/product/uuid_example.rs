@@ -45,6 +45,21 @@ impl Builder {
             uuid: self.uuid.clone().expect("field uuid was not set"),
         }
     }
+    #[doc = r" Constructs a new instance of the type, returning an error if a required field was not set."]
+    #[inline]
+    pub fn try_build(&self) -> Result<UuidExample, conjure_object::BuildError> {
+        let mut missing_fields = Vec::new();
+        if self.uuid.is_none() {
+            missing_fields.push("uuid");
+        }
+        if !missing_fields.is_empty() {
+            return Err(conjure_object::private::build_error(
+                "UuidExample",
+                missing_fields,
+            ));
+        }
+        Ok(self.build())
+    }
 }
 impl From<UuidExample> for Builder {
     #[inline]